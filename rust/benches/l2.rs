@@ -22,7 +22,7 @@ use criterion::{criterion_group, criterion_main, Criterion};
 #[cfg(target_os = "linux")]
 use pprof::criterion::{Output, PProfProfiler};
 
-use lance::linalg::l2::l2_distance_batch;
+use lance::linalg::l2::{l2_distance, l2_distance_batch};
 use lance::utils::testing::generate_random_array;
 
 #[inline]
@@ -89,17 +89,37 @@ fn bench_distance(c: &mut Criterion) {
     });
 }
 
+fn bench_small_dimension(c: &mut Criterion) {
+    const DIMENSION: usize = 8;
+    const TOTAL: usize = 1024 * 1024;
+
+    let key = generate_random_array(DIMENSION);
+    let target = generate_random_array(TOTAL * DIMENSION);
+
+    // Dominated by the per-call overhead, i.e., selecting the SIMD kernel.
+    c.bench_function("L2(simd, dim=8)", |b| {
+        let x = key.values();
+        b.iter(|| {
+            target
+                .values()
+                .chunks_exact(DIMENSION)
+                .map(|y| l2_distance(x, y))
+                .sum::<f32>()
+        });
+    });
+}
+
 #[cfg(target_os = "linux")]
 criterion_group!(
     name=benches;
     config = Criterion::default().significance_level(0.1).sample_size(10)
         .with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
-    targets = bench_distance);
+    targets = bench_distance, bench_small_dimension);
 
 // Non-linux version does not support pprof.
 #[cfg(not(target_os = "linux"))]
 criterion_group!(
     name=benches;
     config = Criterion::default().significance_level(0.1).sample_size(10);
-    targets = bench_distance);
+    targets = bench_distance, bench_small_dimension);
 criterion_main!(benches);
@@ -0,0 +1,105 @@
+// Copyright 2023 Lance Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-call overhead of reading many small batches with the same projection.
+
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema as ArrowSchema};
+use criterion::{criterion_group, criterion_main, Criterion};
+use object_store::path::Path;
+#[cfg(target_os = "linux")]
+use pprof::criterion::{Output, PProfProfiler};
+
+use lance::datatypes::Schema;
+use lance::io::{FileReader, FileWriter, ObjectStore};
+
+const NUM_COLUMNS: usize = 50;
+const NUM_BATCHES: i32 = 200;
+const BATCH_SIZE: i32 = 10;
+
+fn bench_read_batch(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let (store, path) = rt.block_on(create_file());
+    let reader = rt.block_on(async { FileReader::try_new(&store, &path).await.unwrap() });
+    let names = reader
+        .schema()
+        .fields
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect::<Vec<_>>();
+    // Projections of the same number of columns, so both benchmarks read the same data.
+    let first = reader.schema().project(&names[..NUM_COLUMNS - 1]).unwrap();
+    let last = reader.schema().project(&names[1..]).unwrap();
+
+    c.bench_function("Read small batches with the same projection", |b| {
+        b.to_async(&rt).iter(|| async {
+            for batch_id in 0..NUM_BATCHES {
+                let batch = reader.read_batch(batch_id, .., &first).await.unwrap();
+                assert_eq!(batch.num_rows(), BATCH_SIZE as usize);
+            }
+        })
+    });
+
+    // Baseline: alternating the projections resolves the projection on every call.
+    c.bench_function("Read small batches with alternating projections", |b| {
+        b.to_async(&rt).iter(|| async {
+            for batch_id in 0..NUM_BATCHES {
+                let projection = if batch_id % 2 == 0 { &first } else { &last };
+                let batch = reader.read_batch(batch_id, .., projection).await.unwrap();
+                assert_eq!(batch.num_rows(), BATCH_SIZE as usize);
+            }
+        })
+    });
+}
+
+async fn create_file() -> (ObjectStore, Path) {
+    let arrow_schema = Arc::new(ArrowSchema::new(
+        (0..NUM_COLUMNS)
+            .map(|i| Field::new(format!("c{i}"), DataType::Int32, false))
+            .collect::<Vec<_>>(),
+    ));
+    let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+    let store = ObjectStore::new(":memory:").await.unwrap();
+    let path = Path::from("/read_batch.lance");
+    let mut writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+    for b in 0..NUM_BATCHES {
+        let columns = (0..NUM_COLUMNS)
+            .map(|_| {
+                Arc::new(Int32Array::from_iter_values(
+                    b * BATCH_SIZE..(b + 1) * BATCH_SIZE,
+                )) as ArrayRef
+            })
+            .collect::<Vec<_>>();
+        let batch = RecordBatch::try_new(arrow_schema.clone(), columns).unwrap();
+        writer.write(&[batch]).await.unwrap();
+    }
+    writer.finish().await.unwrap();
+    (store, path)
+}
+
+#[cfg(target_os = "linux")]
+criterion_group!(
+    name=benches;
+    config = Criterion::default().significance_level(0.1).sample_size(10)
+        .with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = bench_read_batch);
+#[cfg(not(target_os = "linux"))]
+criterion_group!(
+    name=benches;
+    config = Criterion::default().significance_level(0.1).sample_size(10);
+    targets = bench_read_batch);
+criterion_main!(benches);
@@ -19,16 +19,22 @@
 use std::sync::Arc;
 
 use arrow::array::as_struct_array;
+use arrow::row::{RowConverter, SortField};
 use arrow_array::{
-    Array, ArrayRef, ArrowNumericType, FixedSizeBinaryArray, FixedSizeListArray, GenericListArray,
-    OffsetSizeTrait, PrimitiveArray, RecordBatch, StructArray, UInt8Array,
+    Array, ArrayRef, ArrowNumericType, BooleanArray, FixedSizeBinaryArray, FixedSizeListArray,
+    Float32Array, GenericListArray, OffsetSizeTrait, PrimitiveArray, RecordBatch, StructArray,
+    UInt32Array, UInt64Array, UInt8Array,
 };
+use arrow_buffer::{Buffer, MutableBuffer};
+use arrow_cast::cast::cast;
 use arrow_data::ArrayDataBuilder;
-use arrow_schema::{DataType, Field, FieldRef, Fields, Schema};
+use arrow_schema::{DataType, Field, FieldRef, Fields, Schema, SchemaRef};
+use arrow_select::concat::concat;
 
 mod kernels;
 pub mod linalg;
 mod record_batch;
+use crate::datatypes::LogicalType;
 use crate::error::{Error, Result};
 pub use kernels::*;
 pub use record_batch::*;
@@ -62,7 +68,44 @@ pub trait DataTypeExt {
     /// Returns true if the [DataType] is a dictionary type.
     fn is_dictionary(&self) -> bool;
 
+    /// Returns true if the [DataType] is a container of other types, i.e., Struct,
+    /// (Large/FixedSize)List, Map and Union.
+    ///
+    /// Dictionary is not nested, as it is a leaf encoding in Lance.
+    ///
+    /// ```
+    /// use lance::arrow::*;
+    /// use arrow_schema::{DataType, Field};
+    ///
+    /// let struct_type = DataType::Struct(vec![Field::new("a", DataType::Int32, true)].into());
+    /// assert!(struct_type.is_nested());
+    /// assert!(!DataType::Int32.is_nested());
+    /// ```
+    fn is_nested(&self) -> bool;
+
     fn byte_width(&self) -> usize;
+
+    /// The logical type name used to store this data type in a Lance file.
+    ///
+    /// ```
+    /// use lance::arrow::*;
+    /// use arrow_schema::DataType;
+    ///
+    /// assert_eq!(DataType::Float32.lance_encoding().unwrap(), "float");
+    /// ```
+    fn lance_encoding(&self) -> Result<String>;
+
+    /// Returns true if the data type, including the types of its children, can be
+    /// stored in a Lance file.
+    ///
+    /// ```
+    /// use lance::arrow::*;
+    /// use arrow_schema::{DataType, Field, IntervalUnit};
+    ///
+    /// assert!(DataType::Int32.is_lance_writable());
+    /// assert!(!DataType::Interval(IntervalUnit::DayTime).is_lance_writable());
+    /// ```
+    fn is_lance_writable(&self) -> bool;
 }
 
 impl DataTypeExt for DataType {
@@ -108,6 +151,14 @@ impl DataTypeExt for DataType {
         matches!(self, Self::Dictionary(_, _))
     }
 
+    fn is_nested(&self) -> bool {
+        use DataType::*;
+        matches!(
+            self,
+            Struct(..) | List(..) | LargeList(..) | FixedSizeList(..) | Map(..) | Union(..)
+        )
+    }
+
     fn byte_width(&self) -> usize {
         match self {
             Self::Int8 => 1,
@@ -134,6 +185,23 @@ impl DataTypeExt for DataType {
             _ => panic!("Does not support get byte width on type {self}"),
         }
     }
+
+    fn lance_encoding(&self) -> Result<String> {
+        Ok(LogicalType::try_from(self)?.to_string())
+    }
+
+    fn is_lance_writable(&self) -> bool {
+        if LogicalType::try_from(self).is_err() {
+            return false;
+        }
+        match self {
+            Self::Struct(fields) => fields.iter().all(|f| f.data_type().is_lance_writable()),
+            Self::List(item) | Self::LargeList(item) | Self::FixedSizeList(item, _) => {
+                item.data_type().is_lance_writable()
+            }
+            _ => true,
+        }
+    }
 }
 
 pub trait GenericListArrayExt<Offset: ArrowNumericType>
@@ -234,6 +302,75 @@ pub fn as_fixed_size_list_array(arr: &dyn Array) -> &FixedSizeListArray {
     arr.as_any().downcast_ref::<FixedSizeListArray>().unwrap()
 }
 
+/// The flat values and the dimension of a `FixedSizeList<Float32>` array, i.e., to
+/// pass the vectors to the [linalg] kernels.
+///
+/// Returns [`Error::Arrow`] if the values are not `Float32`, or contain nulls.
+///
+/// ```
+/// use arrow_array::{FixedSizeListArray, Float32Array};
+/// use lance::arrow::*;
+///
+/// let values = Float32Array::from_iter_values((0..8).map(|v| v as f32));
+/// let vectors = FixedSizeListArray::try_new(values, 4).unwrap();
+/// let (flat, dim) = fsl_values_f32(&vectors).unwrap();
+/// assert_eq!(dim, 4);
+/// assert_eq!(flat, &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+/// ```
+pub fn fsl_values_f32(arr: &FixedSizeListArray) -> Result<(&[f32], usize)> {
+    let data = arr.data();
+    let values = &data.child_data()[0];
+    if values.data_type() != &DataType::Float32 {
+        return Err(Error::Arrow(format!(
+            "Expect FixedSizeList<Float32>, got {}",
+            arr.data_type()
+        )));
+    }
+    if values.null_count() > 0 {
+        return Err(Error::Arrow(
+            "The values of FixedSizeList<Float32> contain nulls".to_string(),
+        ));
+    }
+    let dim = arr.value_length() as usize;
+    let start = values.offset() + data.offset() * dim;
+    let flat = &values.buffers()[0].typed_data::<f32>()[start..start + arr.len() * dim];
+    Ok((flat, dim))
+}
+
+/// Build a batch of one row, i.e., the query of a vector search.
+///
+/// Returns [`Error::Arrow`] if any of `arrays` does not have exactly one value.
+pub fn single_row_batch(schema: &Schema, arrays: Vec<ArrayRef>) -> Result<RecordBatch> {
+    if let Some(arr) = arrays.iter().find(|arr| arr.len() != 1) {
+        return Err(Error::Arrow(format!(
+            "Single row batch expects arrays of one value, got {} values",
+            arr.len()
+        )));
+    }
+    Ok(RecordBatch::try_new(Arc::new(schema.clone()), arrays)?)
+}
+
+/// Build a batch of one row with the query `vector`, as a `FixedSizeList<Float32>`
+/// column named `field_name`.
+///
+/// ```
+/// use lance::arrow::*;
+///
+/// let batch = query_vector_batch("vec", &[1.0, 2.0, 3.0]).unwrap();
+/// assert_eq!(batch.num_rows(), 1);
+/// let vectors = as_fixed_size_list_array(batch.column(0).as_ref());
+/// assert_eq!(fsl_values_f32(vectors).unwrap(), (&[1.0_f32, 2.0, 3.0][..], 3));
+/// ```
+pub fn query_vector_batch(field_name: &str, vector: &[f32]) -> Result<RecordBatch> {
+    if vector.is_empty() {
+        return Err(Error::Arrow("Query vector is empty".to_string()));
+    }
+    let values = Float32Array::from(vector.to_vec());
+    let arr = FixedSizeListArray::try_new(values, vector.len() as i32)?;
+    let schema = Schema::new(vec![Field::new(field_name, arr.data_type().clone(), true)]);
+    single_row_batch(&schema, vec![Arc::new(arr)])
+}
+
 pub trait FixedSizeBinaryArrayExt {
     /// Create an [`FixedSizeBinaryArray`] from values and stride.
     ///
@@ -254,6 +391,30 @@ pub trait FixedSizeBinaryArrayExt {
     /// ]))
     /// ```
     fn try_new(values: &UInt8Array, stride: i32) -> Result<FixedSizeBinaryArray>;
+
+    /// Create a [`FixedSizeBinaryArray`] of stride `N` from fixed size byte arrays.
+    ///
+    /// A `None` item is a null row.
+    ///
+    /// ```
+    /// use arrow_array::{Array, FixedSizeBinaryArray};
+    /// use arrow_schema::DataType;
+    /// use lance::arrow::FixedSizeBinaryArrayExt;
+    ///
+    /// let uuid1 = [1_u8; 16];
+    /// let uuid2 = [2_u8; 16];
+    /// let arr =
+    ///     FixedSizeBinaryArray::try_new_from_iter(vec![Some(uuid1), None, Some(uuid2)].into_iter())
+    ///         .unwrap();
+    /// assert_eq!(arr.data_type(), &DataType::FixedSizeBinary(16));
+    /// assert_eq!(arr.len(), 3);
+    /// assert_eq!(arr.value(0), uuid1.as_slice());
+    /// assert!(arr.is_null(1));
+    /// assert_eq!(arr.value(2), uuid2.as_slice());
+    /// ```
+    fn try_new_from_iter<const N: usize>(
+        iter: impl Iterator<Item = Option<[u8; N]>>,
+    ) -> Result<FixedSizeBinaryArray>;
 }
 
 impl FixedSizeBinaryArrayExt for FixedSizeBinaryArray {
@@ -265,12 +426,83 @@ impl FixedSizeBinaryArrayExt for FixedSizeBinaryArray {
             .build()?;
         Ok(Self::from(data))
     }
+
+    fn try_new_from_iter<const N: usize>(
+        iter: impl Iterator<Item = Option<[u8; N]>>,
+    ) -> Result<Self> {
+        let stride = i32::try_from(N)
+            .map_err(|_| Error::Arrow(format!("FixedSizeBinary stride {N} does not fit in i32")))?;
+        let mut values = MutableBuffer::new(0);
+        let mut validity = vec![];
+        let mut null_count = 0;
+        for item in iter {
+            match item {
+                Some(bytes) => {
+                    values.extend_from_slice(&bytes);
+                    validity.push(true);
+                }
+                None => {
+                    values.extend_zeros(N);
+                    validity.push(false);
+                    null_count += 1;
+                }
+            }
+        }
+        let len = validity.len();
+        let mut builder = ArrayDataBuilder::new(DataType::FixedSizeBinary(stride))
+            .len(len)
+            .add_buffer(values.into());
+        if null_count > 0 {
+            builder = builder
+                .null_count(null_count)
+                .null_bit_buffer(Some(Buffer::from_iter(validity)));
+        }
+        Ok(Self::from(builder.build()?))
+    }
 }
 
 pub fn as_fixed_size_binary_array(arr: &dyn Array) -> &FixedSizeBinaryArray {
     arr.as_any().downcast_ref::<FixedSizeBinaryArray>().unwrap()
 }
 
+/// Concatenate `batches` into one [`RecordBatch`] of `schema`.
+///
+/// Unlike [`arrow_select::concat::concat_batches`], dictionary columns do not need to share
+/// the same dictionary: the dictionaries of all batches are unified into a single one, and the
+/// keys are remapped to it.
+pub fn concat_with_dict_unification(
+    schema: &SchemaRef,
+    batches: &[RecordBatch],
+) -> Result<RecordBatch> {
+    if batches.is_empty() {
+        return Ok(RecordBatch::new_empty(schema.clone()));
+    }
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let arrays = batches
+                .iter()
+                .map(|b| b.column(i).as_ref())
+                .collect::<Vec<_>>();
+            match field.data_type() {
+                DataType::Dictionary(_, value_type) => {
+                    // Decode the values, and re-encode them with one dictionary.
+                    let values = arrays
+                        .iter()
+                        .map(|arr| cast(*arr, value_type))
+                        .collect::<std::result::Result<Vec<_>, _>>()?;
+                    let values = concat(&values.iter().map(|v| v.as_ref()).collect::<Vec<_>>())?;
+                    Ok(cast(values.as_ref(), field.data_type())?)
+                }
+                _ => Ok(concat(&arrays)?),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
 /// Extends Arrow's [RecordBatch].
 pub trait RecordBatchExt {
     /// Append a new column to this [`RecordBatch`] and returns a new RecordBatch.
@@ -349,6 +581,98 @@ pub trait RecordBatchExt {
 
     /// Project the schema over the [RecordBatch].
     fn project_by_schema(&self, schema: &Schema) -> Result<RecordBatch>;
+
+    /// Hash the values of `columns` row by row.
+    ///
+    /// Rows are encoded with the arrow row format, so null values hash differently from
+    /// any valid value. The hash only depends on the values and the `seed`, thus rows
+    /// with the same keys in different batches get the same hash.
+    ///
+    /// Returns [Err] if any of the `columns` does not exist.
+    fn hash_rows(&self, columns: &[&str], seed: u64) -> Result<UInt64Array>;
+
+    /// Gather the rows at `indices` and return a new [`RecordBatch`] with the same schema.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use arrow_array::*;
+    /// use arrow_schema::{Schema, Field, DataType};
+    /// use lance::arrow::*;
+    ///
+    /// let schema = Arc::new(Schema::new(vec![
+    ///     Field::new("a", DataType::Int32, true),
+    ///     Field::new("s", DataType::Utf8, true),
+    /// ]));
+    /// let batch = RecordBatch::try_new(
+    ///     schema.clone(),
+    ///     vec![
+    ///         Arc::new(Int32Array::from(vec![1, 2, 3])),
+    ///         Arc::new(StringArray::from(vec!["a", "b", "c"])),
+    ///     ],
+    /// ).unwrap();
+    ///
+    /// let taken = batch.take(&UInt32Array::from(vec![2, 0, 2])).unwrap();
+    ///
+    /// assert_eq!(
+    ///     taken,
+    ///     RecordBatch::try_new(
+    ///         schema,
+    ///         vec![
+    ///             Arc::new(Int32Array::from(vec![3, 1, 3])),
+    ///             Arc::new(StringArray::from(vec!["c", "a", "c"])),
+    ///         ],
+    ///     ).unwrap()
+    /// )
+    /// ```
+    ///
+    /// Returns [Err] if any of the `indices` is out of bounds.
+    fn take(&self, indices: &UInt32Array) -> Result<RecordBatch>;
+
+    /// Keep the rows where `mask` is true and return a new [`RecordBatch`].
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use arrow_array::*;
+    /// use arrow_schema::{Schema, Field, DataType};
+    /// use lance::arrow::*;
+    ///
+    /// let schema = Arc::new(Schema::new(vec![
+    ///     Field::new("a", DataType::Int32, true),
+    ///     Field::new("s", DataType::Utf8, true),
+    /// ]));
+    /// let batch = RecordBatch::try_new(
+    ///     schema.clone(),
+    ///     vec![
+    ///         Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+    ///         Arc::new(StringArray::from(vec!["a", "b", "c", "d"])),
+    ///     ],
+    /// ).unwrap();
+    ///
+    /// let mask = BooleanArray::from(vec![true, false, false, true]);
+    /// let filtered = batch.filter(&mask).unwrap();
+    ///
+    /// assert_eq!(
+    ///     filtered,
+    ///     RecordBatch::try_new(
+    ///         schema,
+    ///         vec![
+    ///             Arc::new(Int32Array::from(vec![1, 4])),
+    ///             Arc::new(StringArray::from(vec!["a", "d"])),
+    ///         ],
+    ///     ).unwrap()
+    /// )
+    /// ```
+    ///
+    /// Returns [Err] if the length of `mask` is not the number of rows.
+    fn filter(&self, mask: &BooleanArray) -> Result<RecordBatch>;
+
+    /// Split this [`RecordBatch`] into batches of at most `max_rows` rows.
+    ///
+    /// The batches are zero-copy slices sharing the same schema. All of them have
+    /// `max_rows` rows, except the last one which can be smaller.
+    ///
+    /// Panics if `max_rows` is zero.
+    fn chunk(&self, max_rows: usize) -> Vec<RecordBatch>;
 }
 
 impl RecordBatchExt for RecordBatch {
@@ -409,6 +733,87 @@ impl RecordBatchExt for RecordBatch {
         let struct_array: StructArray = self.clone().into();
         project(&struct_array, schema.fields()).map(|arr| RecordBatch::from(arr))
     }
+
+    fn hash_rows(&self, columns: &[&str], seed: u64) -> Result<UInt64Array> {
+        let arrays = columns
+            .iter()
+            .map(|name| {
+                let arr = self.column_by_name(name).ok_or_else(|| {
+                    Error::Arrow(format!("column {name} does not exist in the RecordBatch"))
+                })?;
+                // The row format of a dictionary depends on the other dictionaries seen by
+                // the converter, so hash the values to be stable across batches.
+                match arr.data_type() {
+                    DataType::Dictionary(_, value_type) => Ok(cast(arr, value_type)?),
+                    _ => Ok(arr.clone()),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let converter = RowConverter::new(
+            arrays
+                .iter()
+                .map(|arr| SortField::new(arr.data_type().clone()))
+                .collect(),
+        )?;
+        let rows = converter.convert_columns(&arrays)?;
+        Ok(UInt64Array::from_iter_values(
+            rows.iter().map(|row| fnv1a_hash(row.as_ref(), seed)),
+        ))
+    }
+
+    fn take(&self, indices: &UInt32Array) -> Result<RecordBatch> {
+        if let Some(idx) = indices
+            .iter()
+            .flatten()
+            .find(|idx| *idx as usize >= self.num_rows())
+        {
+            return Err(Error::Arrow(format!(
+                "Take index {} is out of bounds for RecordBatch with {} rows",
+                idx,
+                self.num_rows()
+            )));
+        }
+        let columns = self
+            .columns()
+            .iter()
+            .map(|arr| arrow_select::take::take(arr.as_ref(), indices, None))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(Self::try_new(self.schema(), columns)?)
+    }
+
+    fn filter(&self, mask: &BooleanArray) -> Result<RecordBatch> {
+        if mask.len() != self.num_rows() {
+            return Err(Error::Arrow(format!(
+                "Filter mask length {} does not match the number of rows {}",
+                mask.len(),
+                self.num_rows()
+            )));
+        }
+        let columns = self
+            .columns()
+            .iter()
+            .map(|arr| arrow_select::filter::filter(arr.as_ref(), mask))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(Self::try_new(self.schema(), columns)?)
+    }
+
+    fn chunk(&self, max_rows: usize) -> Vec<RecordBatch> {
+        assert!(max_rows > 0, "max_rows must be greater than 0");
+        (0..self.num_rows())
+            .step_by(max_rows)
+            .map(|offset| self.slice(offset, max_rows.min(self.num_rows() - offset)))
+            .collect()
+    }
+}
+
+/// 64-bit FNV-1a hash of `bytes`, with the `seed` mixed into the offset basis.
+fn fnv1a_hash(bytes: &[u8], seed: u64) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS ^ seed, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(PRIME)
+    })
 }
 
 fn project(struct_array: &StructArray, fields: &Fields) -> Result<StructArray> {
@@ -531,8 +936,288 @@ fn get_sub_array<'a>(array: &'a ArrayRef, components: &[&str]) -> Option<&'a Arr
 #[cfg(test)]
 mod tests {
     use super::*;
-    use arrow_array::{ArrayRef, Int32Array, StringArray, StructArray};
+    use arrow_array::{
+        types::Int32Type, ArrayRef, DictionaryArray, Int32Array, StringArray, StructArray,
+    };
     use arrow_schema::{DataType, Field};
+    use arrow_select::concat::concat_batches;
+
+    #[test]
+    fn test_query_vector_batch() {
+        let vector = (0..16).map(|v| v as f32 / 16.0).collect::<Vec<_>>();
+        let batch = query_vector_batch("vec", &vector).unwrap();
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(batch.schema().field(0).name(), "vec");
+        let (values, dim) =
+            fsl_values_f32(as_fixed_size_list_array(batch.column(0).as_ref())).unwrap();
+        assert_eq!(dim, 16);
+        assert_eq!(values, vector.as_slice());
+
+        assert!(query_vector_batch("vec", &[]).is_err());
+        let schema = Schema::new(vec![Field::new("i", DataType::Int32, true)]);
+        assert!(matches!(
+            single_row_batch(&schema, vec![Arc::new(Int32Array::from(vec![1, 2]))]),
+            Err(Error::Arrow(_))
+        ));
+        assert_eq!(
+            single_row_batch(&schema, vec![Arc::new(Int32Array::from(vec![1]))])
+                .unwrap()
+                .num_rows(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_fsl_values_f32() {
+        use arrow_array::Float64Array;
+
+        let values = Float32Array::from_iter_values((0..12).map(|v| v as f32));
+        let vectors = FixedSizeListArray::try_new(values, 3).unwrap();
+        let (flat, dim) = fsl_values_f32(&vectors).unwrap();
+        assert_eq!(dim, 3);
+        assert_eq!(flat.len(), 12);
+
+        // A slice only returns its own vectors.
+        let sliced = Array::slice(&vectors, 1, 2);
+        let (flat, dim) = fsl_values_f32(as_fixed_size_list_array(sliced.as_ref())).unwrap();
+        assert_eq!(dim, 3);
+        assert_eq!(flat, &[3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+
+        let values = Float64Array::from_iter_values((0..12).map(|v| v as f64));
+        let vectors = FixedSizeListArray::try_new(values, 3).unwrap();
+        assert!(matches!(fsl_values_f32(&vectors), Err(Error::Arrow(_))));
+
+        let values = Float32Array::from(vec![Some(1.0), None, Some(3.0), Some(4.0)]);
+        let vectors = FixedSizeListArray::try_new(values, 2).unwrap();
+        assert!(matches!(fsl_values_f32(&vectors), Err(Error::Arrow(_))));
+    }
+
+    #[test]
+    fn test_is_lance_writable() {
+        use arrow_schema::{IntervalUnit, TimeUnit};
+
+        let item = |dt: DataType| Arc::new(Field::new("item", dt, true));
+        for dt in [
+            DataType::Utf8,
+            DataType::Timestamp(TimeUnit::Microsecond, None),
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+            DataType::FixedSizeList(item(DataType::Float32), 8),
+            DataType::List(item(DataType::Struct(
+                vec![Field::new("a", DataType::Int64, true)].into(),
+            ))),
+        ] {
+            assert!(dt.is_lance_writable(), "{dt}");
+        }
+
+        let interval = DataType::Interval(IntervalUnit::MonthDayNano);
+        for dt in [
+            interval.clone(),
+            DataType::List(item(interval.clone())),
+            DataType::FixedSizeList(item(interval.clone()), 2),
+            DataType::Struct(
+                vec![
+                    Field::new("a", DataType::Int64, true),
+                    Field::new("b", interval.clone(), true),
+                ]
+                .into(),
+            ),
+            // The logical type of a fixed size list of structs does not check their children.
+            DataType::FixedSizeList(
+                item(DataType::Struct(
+                    vec![Field::new("b", interval.clone(), true)].into(),
+                )),
+                2,
+            ),
+            DataType::LargeList(item(DataType::Struct(
+                vec![Field::new("b", interval, true)].into(),
+            ))),
+        ] {
+            assert!(!dt.is_lance_writable(), "{dt}");
+        }
+    }
+
+    #[test]
+    fn test_hash_rows() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("i", DataType::Int32, true),
+            Field::new("s", DataType::Utf8, true),
+            Field::new("v", DataType::Int32, true),
+        ]));
+        let left = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(3), Some(0)])),
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    Some("b"),
+                    None,
+                    Some(""),
+                ])),
+                Arc::new(Int32Array::from(vec![10, 20, 30, 40])),
+            ],
+        )
+        .unwrap();
+        // Same keys with different payloads.
+        let right = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(3), Some(0)])),
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    Some("b"),
+                    None,
+                    Some(""),
+                ])),
+                Arc::new(Int32Array::from(vec![-1, -2, -3, -4])),
+            ],
+        )
+        .unwrap();
+
+        let hashes = left.hash_rows(&["i", "s"], 42).unwrap();
+        assert_eq!(hashes.len(), 4);
+        assert_eq!(hashes, right.hash_rows(&["i", "s"], 42).unwrap());
+        // Nulls do not collide with zero values or empty strings.
+        assert_ne!(hashes.value(1), hashes.value(3));
+        assert_ne!(
+            left.hash_rows(&["s"], 42).unwrap().value(2),
+            left.hash_rows(&["s"], 42).unwrap().value(3)
+        );
+        assert_ne!(hashes, left.hash_rows(&["i", "s"], 7).unwrap());
+
+        assert!(left.hash_rows(&["i", "nonexistent"], 42).is_err());
+    }
+
+    #[test]
+    fn test_hash_rows_dictionary() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "d",
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+            true,
+        )]));
+        let make_batch = |keys: Vec<i32>, values: Vec<&str>| {
+            let dict_arr = DictionaryArray::<Int32Type>::try_new(
+                &Int32Array::from(keys),
+                &StringArray::from(values),
+            )
+            .unwrap();
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(dict_arr)]).unwrap()
+        };
+        // "a", "b" and "c" under different keys and dictionaries.
+        let first = make_batch(vec![0, 1, 2], vec!["a", "b", "c"]);
+        let second = make_batch(vec![2, 1, 0, 3], vec!["c", "b", "a", "x"]);
+
+        let first_hashes = first.hash_rows(&["d"], 42).unwrap();
+        let second_hashes = second.hash_rows(&["d"], 42).unwrap();
+        assert_eq!(first_hashes.values(), &second_hashes.values()[..3]);
+        assert!(!first_hashes.values().contains(&second_hashes.value(3)));
+    }
+
+    #[test]
+    fn test_take_out_of_bounds() {
+        let schema = Arc::new(Schema::new(vec![Field::new("i", DataType::Int32, true)]));
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2, 3]))]).unwrap();
+
+        assert!(batch.take(&UInt32Array::from(vec![0, 3])).is_err());
+
+        let taken = batch.take(&UInt32Array::from(Vec::<u32>::new())).unwrap();
+        assert_eq!(taken.num_rows(), 0);
+        assert_eq!(taken.schema(), batch.schema());
+    }
+
+    #[test]
+    fn test_filter_mask_length() {
+        let schema = Arc::new(Schema::new(vec![Field::new("i", DataType::Int32, true)]));
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2, 3]))]).unwrap();
+
+        assert!(batch
+            .filter(&BooleanArray::from(vec![true, false]))
+            .is_err());
+
+        let filtered = batch
+            .filter(&BooleanArray::from(vec![Some(true), None, Some(false)]))
+            .unwrap();
+        assert_eq!(filtered.column(0).as_ref(), &Int32Array::from(vec![1]));
+    }
+
+    #[test]
+    fn test_chunk() {
+        let schema = Arc::new(Schema::new(vec![Field::new("i", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter_values(0..10))],
+        )
+        .unwrap();
+
+        let chunks = batch.chunk(3);
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(
+            chunks.iter().map(|c| c.num_rows()).collect::<Vec<_>>(),
+            vec![3, 3, 3, 1]
+        );
+        assert!(chunks.iter().all(|c| c.schema() == schema));
+        assert_eq!(concat_batches(&schema, &chunks).unwrap(), batch);
+
+        assert_eq!(batch.chunk(10).len(), 1);
+        assert!(batch.slice(0, 0).chunk(3).is_empty());
+    }
+
+    #[test]
+    fn test_concat_with_dict_unification() {
+        let dict_type = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("i", DataType::Int32, true),
+            Field::new("d", dict_type.clone(), true),
+        ]));
+        let batch1 = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(DictionaryArray::<Int32Type>::from_iter(vec![
+                    Some("a"),
+                    Some("b"),
+                    Some("a"),
+                ])),
+            ],
+        )
+        .unwrap();
+        let batch2 = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![4, 5, 6])),
+                Arc::new(DictionaryArray::<Int32Type>::from_iter(vec![
+                    Some("c"),
+                    None,
+                    Some("a"),
+                ])),
+            ],
+        )
+        .unwrap();
+
+        let batch = concat_with_dict_unification(&schema, &[batch1, batch2]).unwrap();
+        assert_eq!(batch.schema(), schema);
+        assert_eq!(
+            batch.column(0).as_ref(),
+            &Int32Array::from_iter_values(1..=6)
+        );
+        let dict = batch.column(1);
+        assert_eq!(dict.data_type(), &dict_type);
+        assert_eq!(
+            cast(dict.as_ref(), &DataType::Utf8).unwrap().as_ref(),
+            &StringArray::from(vec![
+                Some("a"),
+                Some("b"),
+                Some("a"),
+                Some("c"),
+                None,
+                Some("a")
+            ])
+        );
+
+        let empty = concat_with_dict_unification(&schema, &[]).unwrap();
+        assert_eq!(empty.num_rows(), 0);
+    }
 
     #[test]
     fn test_merge_recursive() {
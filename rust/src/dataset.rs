@@ -23,7 +23,7 @@ use arrow_array::{
     cast::as_struct_array, RecordBatch, RecordBatchReader, StructArray, UInt64Array,
 };
 use arrow_schema::Schema as ArrowSchema;
-use arrow_select::{concat::concat_batches, take::take};
+use arrow_select::take::take;
 use chrono::prelude::*;
 use futures::stream::{self, StreamExt, TryStreamExt};
 use object_store::path::Path;
@@ -38,7 +38,7 @@ use self::fragment::FileFragment;
 use self::scanner::Scanner;
 use crate::arrow::*;
 use crate::datatypes::Schema;
-use crate::format::{pb, Fragment, Index, Manifest};
+use crate::format::{decode_row_id, pb, Fragment, Index, Manifest};
 use crate::io::{
     object_reader::{read_message, read_struct},
     read_manifest, read_metadata_offset, write_manifest, FileWriter, ObjectStore,
@@ -307,7 +307,7 @@ impl Dataset {
         if matches!(params.mode, WriteMode::Append) {
             if let Some(d) = dataset.as_ref() {
                 let m = d.manifest.as_ref();
-                if schema != m.schema {
+                if !schema.eq_ignore_field_metadata(&m.schema) {
                     return Err(Error::IO(format!(
                         "Append with different schema: original={} new={}",
                         m.schema, schema
@@ -365,6 +365,8 @@ impl Dataset {
             if let Some(w) = writer.as_mut() {
                 if w.len() >= params.max_rows_per_file {
                     w.finish().await?;
+                    // Later files extend the dictionaries of this one.
+                    schema = w.schema().clone();
                     writer = None;
                 }
             }
@@ -384,6 +386,8 @@ impl Dataset {
         if let Some(w) = writer.as_mut() {
             // Drop the last writer.
             w.finish().await?;
+            // The manifest stores the dictionaries extended by all the batches.
+            schema = w.schema().clone();
             drop(writer);
         };
 
@@ -525,7 +529,7 @@ impl Dataset {
             row_count = max_row_indices;
         }
 
-        let one_batch = concat_batches(&schema, &batches)?;
+        let one_batch = concat_with_dict_unification(&schema, &batches)?;
         let remapping_index: UInt64Array = row_indices
             .iter()
             .map(|o| sorted_indices.binary_search(&(*o as u32)).unwrap() as u64)
@@ -536,24 +540,34 @@ impl Dataset {
     }
 
     /// Take rows by the internal ROW ids.
-    pub(crate) async fn take_rows(
-        &self,
-        row_ids: &[u64],
-        projection: &Schema,
-    ) -> Result<RecordBatch> {
+    ///
+    /// Each row id encodes `(fragment_id, offset)` (see [`crate::format::row_id`]), such as
+    /// the ids returned by a vector search with [`ROW_ID`] column. Rows are fetched from each
+    /// fragment and returned in the same order as `row_ids`.
+    ///
+    /// Returns [`Error::IO`] if a row id refers to a fragment that is not in this dataset.
+    pub async fn take_rows(&self, row_ids: &[u64], projection: &Schema) -> Result<RecordBatch> {
         let mut sorted_row_ids = Vec::from(row_ids);
         sorted_row_ids.sort();
+        sorted_row_ids.dedup();
 
         // Group ROW Ids by the fragment
         let mut row_ids_per_fragment: BTreeMap<u64, Vec<u32>> = BTreeMap::new();
         sorted_row_ids.iter().for_each(|row_id| {
-            let fragment_id = row_id >> 32;
-            let offset = (row_id - (fragment_id << 32)) as u32;
+            let (fragment_id, offset) = decode_row_id(*row_id);
             row_ids_per_fragment
-                .entry(fragment_id)
+                .entry(fragment_id as u64)
                 .and_modify(|v| v.push(offset))
                 .or_insert_with(|| vec![offset]);
         });
+        if let Some(fragment_id) = row_ids_per_fragment
+            .keys()
+            .find(|id| !self.manifest.fragments.iter().any(|f| f.id == **id))
+        {
+            return Err(Error::IO(format!(
+                "Row id refers to fragment {fragment_id}, which does not exist in the dataset"
+            )));
+        }
         let schema = Arc::new(ArrowSchema::from(projection));
         let fragments = self.get_fragments();
         let fragment_and_indices = fragments
@@ -575,7 +589,7 @@ impl Dataset {
             })
             .try_collect::<Vec<_>>()
             .await?;
-        let one_batch = concat_batches(&schema, &batches)?;
+        let one_batch = concat_with_dict_unification(&schema, &batches)?;
 
         let remapping_index: UInt64Array = row_ids
             .iter()
@@ -736,13 +750,15 @@ mod tests {
     use crate::{datatypes::Schema, utils::testing::generate_random_array};
 
     use crate::dataset::WriteMode::Overwrite;
+    use crate::format::row_id;
     use arrow_array::{
         cast::{as_string_array, as_struct_array},
         DictionaryArray, FixedSizeListArray, Int32Array, RecordBatch, StringArray, UInt16Array,
     };
+    use arrow_cast::cast::cast;
     use arrow_ord::sort::sort_to_indices;
     use arrow_schema::{DataType, Field, Schema as ArrowSchema};
-    use arrow_select::take::take;
+    use arrow_select::{concat::concat_batches, take::take};
     use futures::stream::TryStreamExt;
     use tempfile::tempdir;
 
@@ -823,6 +839,129 @@ mod tests {
         )
     }
 
+    #[tokio::test]
+    async fn test_write_new_dictionary_values() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("i", DataType::Int32, false),
+            Field::new(
+                "dict",
+                DataType::Dictionary(Box::new(DataType::UInt16), Box::new(DataType::Utf8)),
+                false,
+            ),
+        ]));
+        let make_batch = |offset: i32, values: &[&str], keys: Vec<u16>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from_iter_values(offset..offset + 10)),
+                    Arc::new(
+                        DictionaryArray::try_new(
+                            &UInt16Array::from(keys),
+                            &StringArray::from_iter_values(values),
+                        )
+                        .unwrap(),
+                    ),
+                ],
+            )
+            .unwrap()
+        };
+        // The second batch has a new value, "c", and "a" under another key.
+        let batches = vec![
+            make_batch(0, &["a", "b"], (0..10).map(|v| v % 2).collect()),
+            make_batch(10, &["c", "a"], (0..10).map(|v| v % 2).collect()),
+        ];
+        let expected = batches
+            .iter()
+            .flat_map(|b| {
+                let values = cast(b.column(1), &DataType::Utf8).unwrap();
+                as_string_array(values.as_ref())
+                    .iter()
+                    .map(|v| v.unwrap().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // One file with two batches, and one file for each batch.
+        for max_rows_per_file in [1024, 10] {
+            let test_dir = tempdir().unwrap();
+            let test_uri = test_dir.path().to_str().unwrap();
+            let mut write_params = WriteParams::default();
+            write_params.max_rows_per_group = 10;
+            write_params.max_rows_per_file = max_rows_per_file;
+            let mut reader: Box<dyn RecordBatchReader> =
+                Box::new(RecordBatchBuffer::new(batches.clone()));
+            Dataset::write(&mut reader, test_uri, Some(write_params))
+                .await
+                .unwrap();
+
+            let dataset = Dataset::open(test_uri).await.unwrap();
+            let actual_batches = dataset
+                .scan()
+                .try_into_stream()
+                .await
+                .unwrap()
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            let actual_batch =
+                concat_batches(&actual_batches[0].schema(), &actual_batches).unwrap();
+            let sorted_indices =
+                sort_to_indices(actual_batch.column_by_name("i").unwrap(), None, None).unwrap();
+            let values = cast(
+                actual_batch.column_by_name("dict").unwrap(),
+                &DataType::Utf8,
+            )
+            .unwrap();
+            let values = take(values.as_ref(), &sorted_indices, None).unwrap();
+            let actual = as_string_array(values.as_ref())
+                .iter()
+                .map(|v| v.unwrap().to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_append_with_field_metadata() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let make_reader = |field: Field| -> Box<dyn RecordBatchReader> {
+            let schema = Arc::new(ArrowSchema::new(vec![field]));
+            let batch =
+                RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from_iter_values(0..10))])
+                    .unwrap();
+            Box::new(RecordBatchBuffer::new(vec![batch]))
+        };
+
+        let mut reader = make_reader(Field::new("i", DataType::Int32, false));
+        Dataset::write(&mut reader, test_uri, None).await.unwrap();
+
+        // The existing dataset has no field metadata.
+        let mut reader = make_reader(
+            Field::new("i", DataType::Int32, false).with_metadata(
+                [("model".to_string(), "clip".to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+        );
+        let mut write_params = WriteParams::default();
+        write_params.mode = WriteMode::Append;
+        Dataset::write(&mut reader, test_uri, Some(write_params))
+            .await
+            .unwrap();
+
+        let dataset = Dataset::open(test_uri).await.unwrap();
+        assert_eq!(dataset.count_rows().await.unwrap(), 20);
+
+        // Other differences are still rejected.
+        let mut reader = make_reader(Field::new("i", DataType::Int32, true));
+        let mut write_params = WriteParams::default();
+        write_params.mode = WriteMode::Append;
+        assert!(Dataset::write(&mut reader, test_uri, Some(write_params))
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_create_dataset() {
         // Appending / Overwriting a dataset that does not exist is treated as Create
@@ -1106,6 +1245,62 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_take_rows_interleaved_fragments() {
+        let test_dir = tempdir().unwrap();
+
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "i",
+            DataType::Int32,
+            false,
+        )]));
+        let batches = RecordBatchBuffer::new(
+            (0..4)
+                .map(|i| {
+                    RecordBatch::try_new(
+                        schema.clone(),
+                        vec![Arc::new(Int32Array::from_iter_values(i * 5..(i + 1) * 5))],
+                    )
+                    .unwrap()
+                })
+                .collect(),
+        );
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut write_params = WriteParams::default();
+        write_params.max_rows_per_file = 10;
+        write_params.max_rows_per_group = 5;
+        let mut batches: Box<dyn RecordBatchReader> = Box::new(batches);
+        Dataset::write(&mut batches, test_uri, Some(write_params))
+            .await
+            .unwrap();
+
+        let dataset = Dataset::open(test_uri).await.unwrap();
+        assert_eq!(dataset.get_fragments().len(), 2);
+        let projection = Schema::try_from(schema.as_ref()).unwrap();
+        let values = dataset
+            .take_rows(
+                &[
+                    row_id(1, 3),
+                    row_id(0, 7),
+                    row_id(1, 0),
+                    row_id(0, 2),
+                    row_id(1, 3),
+                ],
+                &projection,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            values.column_by_name("i").unwrap().as_ref(),
+            &Int32Array::from_iter_values([13, 7, 10, 2, 13])
+        );
+
+        let err = dataset
+            .take_rows(&[row_id(0, 1), row_id(5, 1)], &projection)
+            .await;
+        assert!(matches!(err, Err(Error::IO(_))));
+    }
+
     #[tokio::test]
     async fn test_fast_count_rows() {
         let test_dir = tempdir().unwrap();
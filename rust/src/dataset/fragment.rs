@@ -14,10 +14,12 @@
 
 //! Wraps a Fragment of the dataset.
 
+use std::collections::HashMap;
 use std::ops::Range;
 use std::sync::Arc;
 
 use arrow_array::{RecordBatch, RecordBatchReader};
+use futures::stream::{self, Stream, StreamExt};
 use uuid::Uuid;
 
 use crate::arrow::*;
@@ -262,6 +264,31 @@ impl FragmentReader {
         self
     }
 
+    /// The projected schema of this fragment, combining the columns of all opened data files.
+    ///
+    /// It does not include the row id column.
+    pub fn schema(&self) -> Schema {
+        let mut fields = vec![];
+        let mut metadata = HashMap::new();
+        for (_, schema) in self.readers.iter() {
+            fields.extend(schema.fields.iter().cloned());
+            metadata.extend(schema.metadata.clone());
+        }
+        Schema { fields, metadata }
+    }
+
+    /// Number of rows in this fragment.
+    ///
+    /// All data files of a fragment hold the same rows, so this is the length of any of them.
+    pub fn len(&self) -> usize {
+        self.readers[0].0.len()
+    }
+
+    /// Returns true if the fragment does not contain any row.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub(crate) fn num_batches(&self) -> usize {
         self.readers[0].0.num_batches()
     }
@@ -298,6 +325,14 @@ impl FragmentReader {
         merge_batches(&batches)
     }
 
+    /// Read all the batches of this fragment in order.
+    ///
+    /// Each batch contains the columns from every opened data file, and the row ids
+    /// (if [`Self::with_row_id`] is set) are tagged with the id of this fragment.
+    pub fn scan_stream(&self) -> impl Stream<Item = Result<RecordBatch>> + '_ {
+        stream::iter(0..self.num_batches()).then(move |batch_id| self.read_batch(batch_id, ..))
+    }
+
     /// Take rows from this fragment.
     pub async fn take(&self, indices: &[u32]) -> Result<RecordBatch> {
         let mut batches = vec![];
@@ -317,12 +352,17 @@ impl FragmentReader {
 mod tests {
 
     use arrow_arith::arithmetic::multiply_scalar;
-    use arrow_array::{cast::AsArray, ArrayRef, Int32Array, RecordBatchReader, StringArray};
+    use arrow_array::{
+        cast::AsArray, types::UInt64Type, ArrayRef, Int32Array, RecordBatchReader, StringArray,
+    };
     use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+    use arrow_select::concat::concat_batches;
     use futures::TryStreamExt;
     use tempfile::tempdir;
 
     use super::*;
+    use crate::dataset::ROW_ID;
+    use crate::format::{decode_row_id, row_id};
     use crate::{arrow::RecordBatchBuffer, dataset::WriteParams};
 
     async fn create_dataset(test_uri: &str) -> Dataset {
@@ -479,4 +519,73 @@ mod tests {
         .unwrap();
         assert_eq!(batches[0], expected_batch);
     }
+
+    #[tokio::test]
+    async fn test_fragment_reader_scan_multiple_files() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let dataset = create_dataset(test_uri).await;
+
+        // Add a second data file to fragment 1.
+        let fragment = &dataset.get_fragments()[1];
+        let mut updater = fragment.updater(Some(&["i"])).await.unwrap();
+        let new_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "double_i",
+            DataType::Int32,
+            true,
+        )]));
+        while let Some(batch) = updater.next().await.unwrap() {
+            let input_col = batch.column_by_name("i").unwrap();
+            let result_col: Int32Array = multiply_scalar(&input_col.as_primitive(), 2).unwrap();
+            let batch =
+                RecordBatch::try_new(new_schema.clone(), vec![Arc::new(result_col) as ArrayRef])
+                    .unwrap();
+            updater.update(batch).await.unwrap();
+        }
+        let new_fragment = updater.finish().await.unwrap();
+        assert_eq!(new_fragment.files.len(), 2);
+
+        let full_schema = dataset.schema().merge(new_schema.as_ref()).unwrap();
+        let dataset = Dataset::commit(
+            test_uri,
+            &full_schema,
+            &[new_fragment],
+            crate::dataset::WriteMode::Create,
+        )
+        .await
+        .unwrap();
+
+        let fragment = &dataset.get_fragments()[0];
+        assert_eq!(fragment.id(), 1);
+        let mut reader = fragment.open(dataset.schema()).await.unwrap();
+        reader.with_row_id();
+        assert_eq!(reader.len(), 40);
+        assert_eq!(
+            ArrowSchema::from(&reader.schema()),
+            ArrowSchema::from(&full_schema)
+        );
+
+        let batches = reader.scan_stream().try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(batches.len(), reader.num_batches());
+        let batch = concat_batches(&batches[0].schema(), &batches).unwrap();
+        assert_eq!(
+            batch.column_by_name("i").unwrap().as_ref(),
+            &Int32Array::from_iter_values(40..80)
+        );
+        assert_eq!(
+            batch.column_by_name("double_i").unwrap().as_ref(),
+            &Int32Array::from_iter_values((40..80).map(|v| v * 2))
+        );
+
+        let row_ids = batch
+            .column_by_name(ROW_ID)
+            .unwrap()
+            .as_primitive::<UInt64Type>();
+        let expected = (0..40).map(|offset| row_id(1, offset)).collect::<Vec<_>>();
+        assert_eq!(row_ids.values(), expected.as_slice());
+        assert!(row_ids
+            .values()
+            .iter()
+            .all(|id| decode_row_id(*id).0 == fragment.id() as u32));
+    }
 }
@@ -27,7 +27,7 @@ mod schema;
 use crate::format::pb;
 use crate::{Error, Result};
 pub use field::Field;
-pub use schema::Schema;
+pub use schema::{Schema, SchemaDiff};
 
 /// LogicalType is a string presentation of arrow type.
 /// to be serialized into protobuf.
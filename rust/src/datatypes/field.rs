@@ -14,7 +14,7 @@
 
 //! Lance Schema Field
 
-use std::{cmp::max, fmt, sync::Arc};
+use std::{collections::HashMap, fmt, sync::Arc};
 
 use arrow_array::{
     cast::AsArray,
@@ -25,6 +25,7 @@ use arrow_array::{
 };
 use arrow_schema::{DataType, Field as ArrowField};
 use async_recursion::async_recursion;
+use serde_json::{json, Value};
 
 use super::{Dictionary, LogicalType};
 use crate::{
@@ -47,6 +48,9 @@ pub struct Field {
     pub(crate) encoding: Option<Encoding>,
     pub nullable: bool,
 
+    /// Field-level metadata of the Arrow field.
+    pub metadata: HashMap<String, String>,
+
     pub children: Vec<Field>,
 
     /// Dictionary value array if this field is dictionary.
@@ -68,6 +72,10 @@ impl Field {
         }
     }
 
+    pub(crate) fn logical_type(&self) -> &LogicalType {
+        &self.logical_type
+    }
+
     pub fn child(&self, name: &str) -> Option<&Self> {
         self.children.iter().find(|f| f.name == name)
     }
@@ -76,6 +84,42 @@ impl Field {
         self.children.iter_mut().find(|f| f.name == name)
     }
 
+    /// Set the encoding used to store this field.
+    ///
+    /// A dictionary field can be stored without its dictionary by requesting the
+    /// encoding of its value type, in which case the field becomes the value type.
+    ///
+    /// Returns [`Error::Schema`] if the encoding is not compatible with the data type.
+    pub(crate) fn set_encoding(&mut self, encoding: Encoding) -> Result<()> {
+        let data_type = self.data_type();
+        let storage_type = match &data_type {
+            DataType::Dictionary(_, value_type) if encoding != Encoding::Dictionary => {
+                value_type.as_ref().clone()
+            }
+            dt => dt.clone(),
+        };
+        let compatible = match encoding {
+            Encoding::Plain => storage_type.is_fixed_stride(),
+            Encoding::VarBinary => storage_type.is_binary_like(),
+            Encoding::Dictionary => storage_type.is_dictionary(),
+            // There is no RLE encoder yet.
+            Encoding::RLE => false,
+        };
+        if !compatible {
+            return Err(Error::Schema(format!(
+                "Encoding {:?} is not compatible with field {} of type {}",
+                encoding, self.name, data_type
+            )));
+        }
+
+        if storage_type != data_type {
+            self.logical_type = LogicalType::try_from(&storage_type)?;
+            self.dictionary = None;
+        }
+        self.encoding = Some(encoding);
+        Ok(())
+    }
+
     /// Attach the Dictionary's value array, so that we can later serialize
     /// the dictionary to the manifest.
     pub(crate) fn set_dictionary_values(&mut self, arr: &ArrayRef) {
@@ -157,7 +201,8 @@ impl Field {
         }
     }
 
-    pub(crate) fn project(&self, path_components: &[&str]) -> Result<Self> {
+    /// Project the field along a path of nested children, i.e., `["sub_col3", "field4"]`.
+    pub(crate) fn project_path(&self, path_components: &[&str]) -> Result<Self> {
         let mut f = Self {
             name: self.name.clone(),
             id: self.id,
@@ -166,6 +211,7 @@ impl Field {
             extension_name: self.extension_name.clone(),
             encoding: self.encoding.clone(),
             nullable: self.nullable,
+            metadata: self.metadata.clone(),
             children: vec![],
             dictionary: self.dictionary.clone(),
         };
@@ -176,7 +222,7 @@ impl Field {
             let first = path_components[0];
             for c in self.children.as_slice() {
                 if c.name == first {
-                    let projected = c.project(&path_components[1..])?;
+                    let projected = c.project_path(&path_components[1..])?;
                     f.children.push(projected);
                     break;
                 }
@@ -185,6 +231,45 @@ impl Field {
         Ok(f)
     }
 
+    /// Project a struct field to the children named in `names`.
+    ///
+    /// For a list of struct field, the struct element is projected. The projected children
+    /// keep their field ids and nullability, and are in the order of `names`.
+    pub fn project(&self, names: &[&str]) -> Result<Self> {
+        match self.data_type() {
+            DataType::Struct(_) => {
+                if let Some(name) = names.iter().find(|n| self.child(n).is_none()) {
+                    return Err(Error::Schema(format!(
+                        "Field {} does not have child {}",
+                        self.name, name
+                    )));
+                }
+                let mut children: Vec<Self> = vec![];
+                for name in names {
+                    if !children.iter().any(|c| &c.name == name) {
+                        children.push(self.child(name).unwrap().clone());
+                    }
+                }
+                Ok(Self {
+                    children,
+                    ..self.clone()
+                })
+            }
+            DataType::List(_) | DataType::LargeList(_)
+                if self.children[0].data_type().is_struct() =>
+            {
+                Ok(Self {
+                    children: vec![self.children[0].project(names)?],
+                    ..self.clone()
+                })
+            }
+            dt => Err(Error::Schema(format!(
+                "Can not project the children of field {}: {}",
+                self.name, dt
+            ))),
+        }
+    }
+
     /// Project by a field.
     ///
     pub(super) fn project_by_field(&self, other: &Self) -> Result<Self> {
@@ -197,6 +282,16 @@ impl Field {
 
         match (self.data_type(), other.data_type()) {
             (DataType::Boolean, DataType::Boolean) => Ok(self.clone()),
+            // The offsets are stored as file positions, so strings and binaries can be read
+            // with either offset width.
+            (DataType::Utf8, DataType::LargeUtf8)
+            | (DataType::LargeUtf8, DataType::Utf8)
+            | (DataType::Binary, DataType::LargeBinary)
+            | (DataType::LargeBinary, DataType::Binary) => {
+                let mut projected = self.clone();
+                projected.logical_type = other.logical_type.clone();
+                Ok(projected)
+            }
             (dt, other_dt)
                 if (dt.is_primitive() && other_dt.is_primitive())
                     || (dt.is_binary_like() && other_dt.is_binary_like()) =>
@@ -247,6 +342,79 @@ impl Field {
         }
     }
 
+    /// Check whether the data of `other` field can be written to this field.
+    ///
+    /// See [`Schema::check_compatible()`](super::Schema::check_compatible).
+    pub(super) fn check_compatible(&self, other: &Self) -> Result<()> {
+        if self.id != other.id {
+            return Err(Error::Schema(format!(
+                "Field {} has id {}, but expected {}",
+                self.name, other.id, self.id
+            )));
+        }
+        if self.logical_type != other.logical_type {
+            return Err(Error::Schema(format!(
+                "Field {} has type {}, but expected {}",
+                self.name, other.logical_type, self.logical_type
+            )));
+        }
+        if other.nullable && !self.nullable {
+            return Err(Error::Schema(format!(
+                "Field {} is nullable, but expected to be non-nullable",
+                self.name
+            )));
+        }
+        for child in self.children.iter() {
+            let other_child = other.child(&child.name).ok_or_else(|| {
+                Error::Schema(format!(
+                    "Field {} is missing child {}",
+                    self.name, child.name
+                ))
+            })?;
+            child.check_compatible(other_child)?;
+        }
+        if let Some(extra) = other
+            .children
+            .iter()
+            .find(|c| self.child(&c.name).is_none())
+        {
+            return Err(Error::Schema(format!(
+                "Field {} has unexpected child {}",
+                self.name, extra.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Compare the names, types and nullability of two fields and their children,
+    /// ignoring the field ids and dictionaries.
+    ///
+    /// The children are matched by name.
+    pub(super) fn logically_equal(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.logical_type == other.logical_type
+            && self.nullable == other.nullable
+            && self.children.len() == other.children.len()
+            && self.children.iter().all(|child| {
+                other
+                    .child(&child.name)
+                    .map_or(false, |other_child| child.logically_equal(other_child))
+            })
+    }
+
+    /// Same as [`Field::logically_equal()`], except that `other` can have additional
+    /// children.
+    pub(super) fn is_subset_of(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.logical_type == other.logical_type
+            && self.nullable == other.nullable
+            && self.children.iter().all(|child| {
+                other
+                    .child(&child.name)
+                    .map_or(false, |other_child| child.is_subset_of(other_child))
+            })
+    }
+
     /// Intersection of two [`Field`]s.
     ///
     pub(super) fn intersection(&self, other: &Self) -> Result<Self> {
@@ -279,6 +447,7 @@ impl Field {
                 extension_name: self.extension_name.clone(),
                 encoding: self.encoding.clone(),
                 nullable: self.nullable,
+                metadata: self.metadata.clone(),
                 children,
                 dictionary: self.dictionary.clone(),
             };
@@ -323,6 +492,7 @@ impl Field {
                 extension_name: self.extension_name.clone(),
                 encoding: self.encoding.clone(),
                 nullable: self.nullable,
+                metadata: self.metadata.clone(),
                 children,
                 dictionary: self.dictionary.clone(),
             })
@@ -368,12 +538,16 @@ impl Field {
         Ok(())
     }
 
-    // Get the max field id of itself and all children.
-    pub(super) fn max_id(&self) -> i32 {
-        max(
-            self.id,
-            self.children.iter().map(|c| c.max_id()).max().unwrap_or(-1),
-        )
+    /// Visit this field and all its children in depth-first order.
+    pub(super) fn visit<F: FnMut(&Self)>(&self, f: &mut F) {
+        f(self);
+        self.children.iter().for_each(|c| c.visit(f));
+    }
+
+    /// Visit this field and all its children in depth-first order, allowing them to be modified.
+    pub(super) fn visit_mut<F: FnMut(&mut Self)>(&mut self, f: &mut F) {
+        f(self);
+        self.children.iter_mut().for_each(|c| c.visit_mut(f));
     }
 
     /// Recursively set field ID and parent ID for this field and all its children.
@@ -388,12 +562,130 @@ impl Field {
             .for_each(|f| f.set_id(self.id, id_seed));
     }
 
+    /// Recursively assign contiguous field IDs, from `id_seed`, in depth-first order.
+    ///
+    /// The old to new ID mapping is recorded in `id_map`.
+    pub(super) fn reassign_id(
+        &mut self,
+        parent_id: i32,
+        id_seed: &mut i32,
+        id_map: &mut HashMap<i32, i32>,
+    ) {
+        id_map.insert(self.id, *id_seed);
+        self.parent_id = parent_id;
+        self.id = *id_seed;
+        *id_seed += 1;
+        let id = self.id;
+        self.children
+            .iter_mut()
+            .for_each(|f| f.reassign_id(id, id_seed, id_map));
+    }
+
     /// Recursively reset field ID for this field and all its children.
     pub(super) fn reset_id(&mut self) {
         self.id = -1;
         self.children.iter_mut().for_each(Self::reset_id);
     }
 
+    /// JSON representation of this field and its children.
+    pub(super) fn to_json(&self) -> Value {
+        let encoding = self.encoding.as_ref().map(|e| match e {
+            Encoding::Plain => "plain",
+            Encoding::VarBinary => "var_binary",
+            Encoding::Dictionary => "dictionary",
+            Encoding::RLE => "rle",
+        });
+        let dictionary = self
+            .dictionary
+            .as_ref()
+            .map(|d| json!({"offset": d.offset, "length": d.length}));
+        json!({
+            "name": self.name,
+            "id": self.id,
+            "logical_type": self.logical_type.0,
+            "extension_name": self.extension_name,
+            "encoding": encoding,
+            "nullable": self.nullable,
+            "metadata": self.metadata,
+            "dictionary": dictionary,
+            "children": self.children.iter().map(Self::to_json).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Parse a field, and its children, from the JSON produced by [`Field::to_json`].
+    pub(super) fn from_json(value: &Value, parent_id: i32) -> Result<Self> {
+        let id = json_i64(value, "id")? as i32;
+        let encoding = match value.get("encoding") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(e)) => Some(match e.as_str() {
+                "plain" => Encoding::Plain,
+                "var_binary" => Encoding::VarBinary,
+                "dictionary" => Encoding::Dictionary,
+                "rle" => Encoding::RLE,
+                _ => return Err(Error::Schema(format!("Unknown field encoding: {e}"))),
+            }),
+            Some(e) => return Err(Error::Schema(format!("Invalid field encoding: {e}"))),
+        };
+        let dictionary = match value.get("dictionary") {
+            None | Some(Value::Null) => None,
+            Some(d) => Some(Dictionary {
+                offset: json_i64(d, "offset")? as usize,
+                length: json_i64(d, "length")? as usize,
+                values: None,
+            }),
+        };
+        let metadata = match value.get("metadata") {
+            None | Some(Value::Null) => HashMap::new(),
+            Some(Value::Object(metadata)) => metadata
+                .iter()
+                .map(|(k, v)| match v {
+                    Value::String(v) => Ok((k.clone(), v.clone())),
+                    _ => Err(Error::Schema(format!("Invalid field metadata: {k}={v}"))),
+                })
+                .collect::<Result<_>>()?,
+            Some(m) => return Err(Error::Schema(format!("Invalid field metadata: {m}"))),
+        };
+        let children = match value.get("children") {
+            None | Some(Value::Null) => vec![],
+            Some(Value::Array(children)) => children
+                .iter()
+                .map(|c| Self::from_json(c, id))
+                .collect::<Result<_>>()?,
+            Some(c) => return Err(Error::Schema(format!("Invalid field children: {c}"))),
+        };
+        Ok(Self {
+            name: json_str(value, "name")?.to_string(),
+            id,
+            parent_id,
+            logical_type: LogicalType::from(json_str(value, "logical_type")?),
+            extension_name: match value.get("extension_name") {
+                None | Some(Value::Null) => "".to_string(),
+                Some(_) => json_str(value, "extension_name")?.to_string(),
+            },
+            encoding,
+            nullable: value
+                .get("nullable")
+                .and_then(Value::as_bool)
+                .ok_or_else(|| Error::Schema(format!("Missing boolean \"nullable\" in {value}")))?,
+            metadata,
+            children,
+            dictionary,
+        })
+    }
+
+    // Find any nested child with a specific field id
+    pub(super) fn field_by_id(&self, id: i32) -> Option<&Self> {
+        for child in self.children.as_slice() {
+            if child.id == id {
+                return Some(child);
+            }
+            if let Some(grandchild) = child.field_by_id(id) {
+                return Some(grandchild);
+            }
+        }
+        None
+    }
+
     // Find any nested child with a specific field id
     pub(super) fn mut_field_by_id(&mut self, id: i32) -> Option<&mut Self> {
         for child in self.children.as_mut_slice() {
@@ -459,6 +751,20 @@ impl Field {
     }
 }
 
+fn json_str<'a>(value: &'a Value, key: &str) -> Result<&'a str> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::Schema(format!("Missing string \"{key}\" in {value}")))
+}
+
+fn json_i64(value: &Value, key: &str) -> Result<i64> {
+    value
+        .get(key)
+        .and_then(Value::as_i64)
+        .ok_or_else(|| Error::Schema(format!("Missing integer \"{key}\" in {value}")))
+}
+
 impl fmt::Display for Field {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -497,6 +803,7 @@ impl TryFrom<&ArrowField> for Field {
             },
             extension_name: "".to_string(),
             nullable: field.is_nullable(),
+            metadata: field.metadata().clone(),
             children,
             dictionary: None,
         })
@@ -514,6 +821,7 @@ impl TryFrom<ArrowField> for Field {
 impl From<&Field> for ArrowField {
     fn from(field: &Field) -> Self {
         Self::new(&field.name, field.data_type(), field.nullable)
+            .with_metadata(field.metadata.clone())
     }
 }
 
@@ -533,6 +841,11 @@ impl From<&pb::Field> for Field {
                 _ => None,
             },
             nullable: field.nullable,
+            metadata: field
+                .metadata
+                .iter()
+                .map(|(k, v)| (k.clone(), String::from_utf8_lossy(v).to_string()))
+                .collect(),
             children: vec![],
             dictionary: field.dictionary.as_ref().map(Dictionary::from),
         }
@@ -556,6 +869,11 @@ impl From<&Field> for pb::Field {
             nullable: field.nullable,
             dictionary: field.dictionary.as_ref().map(pb::Dictionary::from),
             extension_name: field.extension_name.clone(),
+            metadata: field
+                .metadata
+                .iter()
+                .map(|(k, v)| (k.clone(), v.as_bytes().to_vec()))
+                .collect(),
             r#type: 0,
         }
     }
@@ -573,7 +891,9 @@ impl From<&Field> for Vec<pb::Field> {
 mod tests {
     use super::*;
 
-    use arrow_schema::{DataType, Fields, TimeUnit};
+    use arrow_schema::{DataType, Fields, Schema as ArrowSchema, TimeUnit};
+
+    use crate::datatypes::Schema;
 
     #[test]
     fn arrow_field_to_field() {
@@ -708,6 +1028,24 @@ mod tests {
         assert!(f1.project_by_field(&f4).is_err());
     }
 
+    #[test]
+    fn test_project_by_field_offset_width() {
+        let f1: Field = ArrowField::new("a", DataType::Utf8, true)
+            .try_into()
+            .unwrap();
+        let f2: Field = ArrowField::new("a", DataType::LargeUtf8, true)
+            .try_into()
+            .unwrap();
+        let projected = f1.project_by_field(&f2).unwrap();
+        assert_eq!(projected.id, f1.id);
+        assert_eq!(projected.data_type(), DataType::LargeUtf8);
+
+        let f3: Field = ArrowField::new("a", DataType::LargeBinary, true)
+            .try_into()
+            .unwrap();
+        assert!(f1.project_by_field(&f3).is_err());
+    }
+
     #[test]
     fn test_field_intersection() {
         let f1: Field = ArrowField::new("a", DataType::Int32, true)
@@ -763,4 +1101,78 @@ mod tests {
         .unwrap();
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_project_struct_field() {
+        let schema = Schema::try_from(&ArrowSchema::new(vec![
+            ArrowField::new(
+                "a",
+                DataType::Struct(Fields::from(vec![
+                    ArrowField::new("c", DataType::Int32, false),
+                    ArrowField::new("d", DataType::Utf8, true),
+                ])),
+                true,
+            ),
+            ArrowField::new(
+                "l",
+                DataType::List(Arc::new(ArrowField::new(
+                    "item",
+                    DataType::Struct(Fields::from(vec![
+                        ArrowField::new("c", DataType::Int32, true),
+                        ArrowField::new("d", DataType::Utf8, true),
+                    ])),
+                    true,
+                ))),
+                true,
+            ),
+        ]))
+        .unwrap();
+
+        let field = schema.field("a").unwrap();
+        let projected = field.project(&["c"]).unwrap();
+        assert_eq!(projected.id, field.id);
+        assert_eq!(projected.children.len(), 1);
+        assert_eq!(&projected.children[0], field.child("c").unwrap());
+        assert_eq!(
+            projected.data_type(),
+            DataType::Struct(Fields::from(vec![ArrowField::new(
+                "c",
+                DataType::Int32,
+                false
+            )]))
+        );
+
+        // The children are in the requested order.
+        let projected = field.project(&["d", "c", "d"]).unwrap();
+        assert_eq!(
+            projected
+                .children
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["d", "c"]
+        );
+
+        let field = schema.field("l").unwrap();
+        let projected = field.project(&["d"]).unwrap();
+        assert_eq!(projected.children[0].children.len(), 1);
+        assert_eq!(
+            &projected.children[0].children[0],
+            field.children[0].child("d").unwrap()
+        );
+
+        assert!(matches!(
+            schema.field("a").unwrap().project(&["x"]),
+            Err(Error::Schema(_))
+        ));
+        assert!(matches!(
+            schema
+                .field("a")
+                .unwrap()
+                .child("c")
+                .unwrap()
+                .project(&["c"]),
+            Err(Error::Schema(_))
+        ));
+    }
 }
@@ -20,12 +20,61 @@ use std::{
 };
 
 use arrow_array::RecordBatch;
-use arrow_schema::{Field as ArrowField, Schema as ArrowSchema};
+use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+use serde_json::{json, Value};
 
 use super::field::Field;
 use crate::arrow::*;
 use crate::{format::pb, io::object_reader::ObjectReader, Error, Result};
 
+/// Differences between two schemas, by qualified field names, i.e., `parent.child`.
+///
+/// See [`Schema::diff`].
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct SchemaDiff {
+    /// Fields only in the new schema.
+    pub added: Vec<String>,
+    /// Fields only in the old schema.
+    pub removed: Vec<String>,
+    /// Fields in both schemas, with different data types.
+    pub type_changed: Vec<String>,
+}
+
+impl SchemaDiff {
+    /// Returns true if the schemas have the same fields and types.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.type_changed.is_empty()
+    }
+
+    fn diff_fields(&mut self, prefix: &str, new: &[Field], old: &[Field]) {
+        let qualified = |name: &str| {
+            if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{prefix}.{name}")
+            }
+        };
+        for field in new {
+            let name = qualified(&field.name);
+            match old.iter().find(|f| f.name == field.name) {
+                None => self.added.push(name),
+                Some(old_field) => match (field.data_type(), old_field.data_type()) {
+                    (DataType::Struct(_), DataType::Struct(_)) => {
+                        self.diff_fields(&name, &field.children, &old_field.children)
+                    }
+                    (dt, old_dt) if dt != old_dt => self.type_changed.push(name),
+                    _ => {}
+                },
+            }
+        }
+        for field in old {
+            if !new.iter().any(|f| f.name == field.name) {
+                self.removed.push(qualified(&field.name));
+            }
+        }
+    }
+}
+
 /// Lance Schema.
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct Schema {
@@ -48,7 +97,7 @@ impl Schema {
             let split = col.as_ref().split('.').collect::<Vec<_>>();
             let first = split[0];
             if let Some(field) = self.field(first) {
-                let projected_field = field.project(&split[1..])?;
+                let projected_field = field.project_path(&split[1..])?;
                 if let Some(candidate_field) = candidates.iter_mut().find(|f| f.name == first) {
                     candidate_field.merge(&projected_field)?;
                 } else {
@@ -82,6 +131,66 @@ impl Schema {
         Ok(true)
     }
 
+    /// Check whether the data of `other` schema can be appended to this schema.
+    ///
+    /// The fields are matched by name, and must have the same field ids and data types.
+    /// A field of `other` can be non-nullable when it is nullable in this schema,
+    /// but not the other way around.
+    ///
+    /// Returns [Error::Schema] describing the first incompatible field.
+    pub fn check_compatible(&self, other: &Self) -> Result<()> {
+        for field in self.fields.iter() {
+            let other_field = other
+                .field(&field.name)
+                .ok_or_else(|| Error::Schema(format!("Field {} is missing", field.name)))?;
+            field.check_compatible(other_field)?;
+        }
+        if let Some(extra) = other.fields.iter().find(|f| self.field(&f.name).is_none()) {
+            return Err(Error::Schema(format!(
+                "Field {} does not exist in the schema",
+                extra.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Compare the names, types and nullability of all the fields, recursively.
+    ///
+    /// Unlike `==`, the field ids, dictionaries and the order of the fields are ignored.
+    pub fn logically_equal(&self, other: &Self) -> bool {
+        self.fields.len() == other.fields.len()
+            && self.fields.iter().all(|field| {
+                other
+                    .field(&field.name)
+                    .map_or(false, |other_field| field.logically_equal(other_field))
+            })
+    }
+
+    /// Same as `==`, except that the field-level metadata is ignored.
+    ///
+    /// Datasets written before the field metadata was stored have none in their schema.
+    pub(crate) fn eq_ignore_field_metadata(&self, other: &Self) -> bool {
+        let without_field_metadata = |schema: &Self| {
+            let mut schema = schema.clone();
+            schema.visit_fields_mut(|f| f.metadata.clear());
+            schema
+        };
+        without_field_metadata(self) == without_field_metadata(other)
+    }
+
+    /// Returns true if every field of this schema, including the nested ones, exists in
+    /// `other` with the same type and nullability.
+    ///
+    /// Like [`Schema::logically_equal()`], the field ids, dictionaries and the order of
+    /// the fields are ignored.
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        self.fields.iter().all(|field| {
+            other
+                .field(&field.name)
+                .map_or(false, |other_field| field.is_subset_of(other_field))
+        })
+    }
+
     /// Intersection between two [`Schema`].
     pub fn intersection(&self, other: &Self) -> Result<Self> {
         let mut candidates: Vec<Field> = vec![];
@@ -171,9 +280,37 @@ impl Schema {
 
     /// Recursively collect all the field IDs,
     pub(crate) fn field_ids(&self) -> Vec<i32> {
-        // TODO: make a tree traversal iterator.
-        let protos: Vec<pb::Field> = self.into();
-        protos.iter().map(|f| f.id).collect()
+        let mut ids = vec![];
+        self.visit_fields(|f| ids.push(f.id));
+        ids
+    }
+
+    /// Visit all the fields, including the nested struct and list children, in
+    /// depth-first order.
+    ///
+    /// A parent field is visited before its children.
+    pub fn visit_fields<F: FnMut(&Field)>(&self, mut f: F) {
+        self.fields.iter().for_each(|field| field.visit(&mut f));
+    }
+
+    /// Visit all the fields mutably, in the same order as [`Schema::visit_fields`].
+    pub fn visit_fields_mut<F: FnMut(&mut Field)>(&mut self, mut f: F) {
+        self.fields
+            .iter_mut()
+            .for_each(|field| field.visit_mut(&mut f));
+    }
+
+    /// Get a (potentially nested) field by its field ID.
+    pub fn field_by_id(&self, id: i32) -> Option<&Field> {
+        for field in self.fields.as_slice() {
+            if field.id == id {
+                return Some(field);
+            }
+            if let Some(grandchild) = field.field_by_id(id) {
+                return Some(grandchild);
+            }
+        }
+        None
     }
 
     pub(crate) fn mut_field_by_id(&mut self, id: i32) -> Option<&mut Field> {
@@ -189,7 +326,9 @@ impl Schema {
     }
 
     pub(crate) fn max_field_id(&self) -> Option<i32> {
-        self.fields.iter().map(|f| f.max_id()).max()
+        let mut max_id = None;
+        self.visit_fields(|f| max_id = max_id.max(Some(f.id)));
+        max_id
     }
 
     /// Load dictionary value array from manifest files.
@@ -225,10 +364,77 @@ impl Schema {
         self.fields.iter_mut().for_each(|f| f.reset_id());
     }
 
+    /// Reassign contiguous field IDs, starting from 0, in depth-first order.
+    ///
+    /// It keeps `max_field_id() + 1` equal to the number of fields, after merging or
+    /// projecting schemas. Returns the mapping from the old to the new field IDs.
+    /// If the old IDs had duplicates, the mapping keeps the last field in depth-first order.
+    pub fn reassign_ids(&mut self) -> HashMap<i32, i32> {
+        let mut id_map = HashMap::new();
+        let mut current_id = 0;
+        self.fields
+            .iter_mut()
+            .for_each(|f| f.reassign_id(-1, &mut current_id, &mut id_map));
+        id_map
+    }
+
+    /// Convert to an Arrow schema, with the schema and field metadata, and the nested and
+    /// dictionary types of the fields.
+    pub fn to_arrow(&self) -> ArrowSchema {
+        ArrowSchema::from(self)
+    }
+
+    /// JSON representation of the schema.
+    ///
+    /// Unlike the Arrow schema JSON, it includes the Lance field ids, encodings and
+    /// dictionary locations, so it can be used to compare the schemas of Lance files.
+    /// Dictionary values are not included.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "fields": self.fields.iter().map(Field::to_json).collect::<Vec<_>>(),
+            "metadata": self.metadata,
+        })
+    }
+
+    /// Parse a schema from the JSON produced by [`Schema::to_json`].
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let fields = value
+            .get("fields")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::Schema(format!("Missing \"fields\" in schema JSON: {value}")))?
+            .iter()
+            .map(|f| Field::from_json(f, -1))
+            .collect::<Result<_>>()?;
+        let metadata = match value.get("metadata") {
+            None | Some(Value::Null) => HashMap::new(),
+            Some(Value::Object(metadata)) => metadata
+                .iter()
+                .map(|(k, v)| match v {
+                    Value::String(v) => Ok((k.clone(), v.clone())),
+                    _ => Err(Error::Schema(format!("Invalid schema metadata: {k}={v}"))),
+                })
+                .collect::<Result<_>>()?,
+            Some(m) => return Err(Error::Schema(format!("Invalid schema metadata: {m}"))),
+        };
+        Ok(Self { fields, metadata })
+    }
+
+    /// Compare this schema, as the new schema, with the `other` old schema.
+    ///
+    /// Fields are matched by name, not by field id. Struct fields are compared child by
+    /// child, so a changed nested child is reported as `parent.child`.
+    pub fn diff(&self, other: &Self) -> SchemaDiff {
+        let mut diff = SchemaDiff::default();
+        diff.diff_fields("", &self.fields, &other.fields);
+        diff
+    }
+
     /// Merge this schema from the other schema.
     ///
     /// After merging, the field IDs from `other` schema will be reassigned,
     /// following the fields in `self`.
+    ///
+    /// Returns [Err] if a field exists in both schemas with incompatible data types.
     pub fn merge<S: TryInto<Self, Error = Error>>(&self, other: S) -> Result<Self> {
         let mut other: Self = other.try_into()?;
         other.reset_id();
@@ -353,6 +559,8 @@ mod tests {
         DataType, Field as ArrowField, Fields as ArrowFields, Schema as ArrowSchema,
     };
 
+    use crate::datatypes::Dictionary;
+
     #[test]
     fn test_schema_projection() {
         let arrow_schema = ArrowSchema::new(vec![
@@ -506,6 +714,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reassign_ids() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new(
+                "b",
+                DataType::Struct(ArrowFields::from(vec![
+                    ArrowField::new("f1", DataType::Utf8, true),
+                    ArrowField::new("f2", DataType::Boolean, false),
+                ])),
+                true,
+            ),
+            ArrowField::new("c", DataType::Float64, false),
+        ]);
+        let mut schema = Schema::try_from(&arrow_schema).unwrap();
+        // Sparse field ids, i.e., after projection.
+        schema.fields[0].id = 10;
+        schema.fields[1].id = 3;
+        schema.fields[1].children[0].id = 7;
+        schema.fields[1].children[1].id = 20;
+        schema.fields[2].id = 1;
+
+        let id_map = schema.reassign_ids();
+        assert_eq!(
+            id_map,
+            HashMap::from([(10, 0), (3, 1), (7, 2), (20, 3), (1, 4)])
+        );
+
+        let protos: Vec<pb::Field> = (&schema).into();
+        assert_eq!(
+            protos
+                .iter()
+                .map(|p| (p.name.as_str(), p.id, p.parent_id))
+                .collect::<Vec<_>>(),
+            vec![
+                ("a", 0, -1),
+                ("b", 1, -1),
+                ("f1", 2, 1),
+                ("f2", 3, 1),
+                ("c", 4, -1)
+            ]
+        );
+        assert_eq!(schema.max_field_id(), Some(4));
+    }
+
     #[test]
     fn test_get_nested_field() {
         let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
@@ -523,6 +776,174 @@ mod tests {
         assert_eq!(field.data_type(), DataType::Boolean);
     }
 
+    #[test]
+    fn test_schema_diff() {
+        let old = Schema::try_from(&ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new(
+                "b",
+                DataType::Struct(ArrowFields::from(vec![
+                    ArrowField::new("f1", DataType::Utf8, true),
+                    ArrowField::new("f2", DataType::Int32, true),
+                ])),
+                true,
+            ),
+            ArrowField::new("c", DataType::Float64, false),
+        ]))
+        .unwrap();
+        let new = Schema::try_from(&ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new(
+                "b",
+                DataType::Struct(ArrowFields::from(vec![
+                    ArrowField::new("f1", DataType::Utf8, true),
+                    ArrowField::new("f2", DataType::Int64, true),
+                ])),
+                true,
+            ),
+            ArrowField::new("d", DataType::Binary, true),
+        ]))
+        .unwrap();
+
+        let diff = new.diff(&old);
+        assert_eq!(
+            diff,
+            SchemaDiff {
+                added: vec!["d".to_string()],
+                removed: vec!["c".to_string()],
+                type_changed: vec!["b.f2".to_string()],
+            }
+        );
+        assert!(!diff.is_empty());
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_visit_fields() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new(
+                "b",
+                DataType::Struct(ArrowFields::from(vec![
+                    ArrowField::new("f1", DataType::Utf8, true),
+                    ArrowField::new(
+                        "f2",
+                        DataType::List(Arc::new(ArrowField::new("item", DataType::Float32, true))),
+                        false,
+                    ),
+                ])),
+                true,
+            ),
+            ArrowField::new("c", DataType::Float64, false),
+        ]);
+        let mut schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let mut names = vec![];
+        schema.visit_fields(|f| names.push(f.name.clone()));
+        assert_eq!(names, vec!["a", "b", "f1", "f2", "item", "c"]);
+        assert_eq!(schema.field_ids(), vec![0, 1, 2, 3, 4, 5]);
+
+        schema.visit_fields_mut(|f| f.nullable = true);
+        let mut nullables = vec![];
+        schema.visit_fields(|f| nullables.push(f.nullable));
+        assert!(nullables.into_iter().all(|n| n));
+    }
+
+    #[test]
+    fn test_to_arrow_roundtrip() {
+        let field_metadata = |k: &str| HashMap::from([(k.to_string(), format!("{k}-value"))]);
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false).with_metadata(field_metadata("a")),
+            ArrowField::new(
+                "b",
+                DataType::Struct(ArrowFields::from(vec![
+                    ArrowField::new("f1", DataType::Utf8, true).with_metadata(field_metadata("f1")),
+                    ArrowField::new(
+                        "f2",
+                        DataType::List(Arc::new(
+                            ArrowField::new("item", DataType::Float32, true)
+                                .with_metadata(field_metadata("item")),
+                        )),
+                        false,
+                    ),
+                ])),
+                true,
+            )
+            .with_metadata(field_metadata("b")),
+            ArrowField::new(
+                "c",
+                DataType::Dictionary(Box::new(DataType::UInt16), Box::new(DataType::LargeUtf8)),
+                true,
+            )
+            .with_metadata(field_metadata("c")),
+        ])
+        .with_metadata(HashMap::from([("k".to_string(), "v".to_string())]));
+
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        assert_eq!(schema.field("b").unwrap().metadata, field_metadata("b"));
+        let roundtripped = schema.to_arrow();
+        assert_eq!(roundtripped, arrow_schema);
+        assert_eq!(roundtripped.metadata(), arrow_schema.metadata());
+        for (actual, expected) in roundtripped.fields().iter().zip(arrow_schema.fields()) {
+            assert_eq!(actual.metadata(), expected.metadata());
+        }
+        assert_eq!(
+            roundtripped.field(2).data_type(),
+            &DataType::Dictionary(Box::new(DataType::UInt16), Box::new(DataType::LargeUtf8))
+        );
+
+        // The field metadata is also kept by the JSON representation.
+        assert_eq!(Schema::from_json(&schema.to_json()).unwrap(), schema);
+    }
+
+    #[test]
+    fn test_schema_json_roundtrip() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new(
+                "b",
+                DataType::Struct(ArrowFields::from(vec![
+                    ArrowField::new("f1", DataType::Utf8, true),
+                    ArrowField::new(
+                        "f2",
+                        DataType::List(Arc::new(ArrowField::new("item", DataType::Float32, true))),
+                        false,
+                    ),
+                ])),
+                true,
+            ),
+            ArrowField::new(
+                "c",
+                DataType::Dictionary(Box::new(DataType::UInt16), Box::new(DataType::Utf8)),
+                true,
+            ),
+        ])
+        .with_metadata(HashMap::from([("k".to_string(), "v".to_string())]));
+        let mut schema = Schema::try_from(&arrow_schema).unwrap();
+        schema.mut_field_by_id(5).unwrap().dictionary = Some(Dictionary {
+            offset: 1024,
+            length: 16,
+            values: None,
+        });
+
+        let json = schema.to_json();
+        assert_eq!(json["fields"][1]["children"][1]["children"][0]["id"], 4);
+        assert_eq!(json["fields"][2]["encoding"], "dictionary");
+        assert_eq!(json["fields"][2]["dictionary"]["offset"], 1024);
+
+        let roundtripped = Schema::from_json(&json).unwrap();
+        assert_eq!(roundtripped, schema);
+        assert_eq!(
+            Vec::<pb::Field>::from(&roundtripped),
+            Vec::<pb::Field>::from(&schema)
+        );
+
+        assert!(matches!(
+            Schema::from_json(&json!({"metadata": {}})),
+            Err(Error::Schema(_))
+        ));
+    }
+
     #[test]
     fn test_exclude_fields() {
         let arrow_schema = ArrowSchema::new(vec![
@@ -739,4 +1160,150 @@ mod tests {
         let result = schema1.merge(&schema2).unwrap();
         assert_eq!(result, expected_schema);
     }
+
+    #[test]
+    fn test_merge_incompatible_fields() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            ArrowField::new(
+                "b",
+                DataType::Struct(ArrowFields::from(vec![ArrowField::new(
+                    "f1",
+                    DataType::Utf8,
+                    true,
+                )])),
+                true,
+            ),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let other = ArrowSchema::new(vec![ArrowField::new("a", DataType::Utf8, false)]);
+        assert!(matches!(schema.merge(&other), Err(Error::Schema(_))));
+
+        let other = ArrowSchema::new(vec![ArrowField::new(
+            "b",
+            DataType::Struct(ArrowFields::from(vec![ArrowField::new(
+                "f1",
+                DataType::Int64,
+                true,
+            )])),
+            true,
+        )]);
+        assert!(matches!(schema.merge(&other), Err(Error::Schema(_))));
+    }
+
+    #[test]
+    fn test_logically_equal() {
+        let struct_field = ArrowField::new(
+            "b",
+            DataType::Struct(ArrowFields::from(vec![
+                ArrowField::new("f1", DataType::Utf8, true),
+                ArrowField::new("f2", DataType::Boolean, false),
+            ])),
+            true,
+        );
+        let schema = Schema::try_from(&ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, true),
+            struct_field.clone(),
+        ]))
+        .unwrap();
+
+        // Different field order, hence different field ids.
+        let other = Schema::try_from(&ArrowSchema::new(vec![
+            struct_field.clone(),
+            ArrowField::new("a", DataType::Int32, true),
+        ]))
+        .unwrap();
+        assert_ne!(schema, other);
+        assert_ne!(schema.field("a").unwrap().id, other.field("a").unwrap().id);
+        assert!(schema.logically_equal(&other));
+        assert!(schema.is_subset_of(&other));
+        assert!(other.is_subset_of(&schema));
+
+        let projected = schema.project(&["b.f2"]).unwrap();
+        assert!(!projected.logically_equal(&schema));
+        assert!(projected.is_subset_of(&schema));
+        assert!(!schema.is_subset_of(&projected));
+
+        // Nullability differs in a nested field.
+        let other = Schema::try_from(&ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, true),
+            ArrowField::new(
+                "b",
+                DataType::Struct(ArrowFields::from(vec![
+                    ArrowField::new("f1", DataType::Utf8, true),
+                    ArrowField::new("f2", DataType::Boolean, true),
+                ])),
+                true,
+            ),
+        ]))
+        .unwrap();
+        assert!(!schema.logically_equal(&other));
+        assert!(!schema.is_subset_of(&other));
+
+        // Type differs.
+        let other = Schema::try_from(&ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int64, true),
+            struct_field,
+        ]))
+        .unwrap();
+        assert!(!schema.logically_equal(&other));
+    }
+
+    #[test]
+    fn test_check_compatible() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, true),
+            ArrowField::new(
+                "b",
+                DataType::Struct(ArrowFields::from(vec![
+                    ArrowField::new("f1", DataType::Utf8, true),
+                    ArrowField::new("f2", DataType::Boolean, false),
+                ])),
+                true,
+            ),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        assert!(schema.check_compatible(&schema).is_ok());
+
+        // Non-nullable data can be written to a nullable field.
+        let other = Schema::try_from(&ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int32, false),
+            arrow_schema.field(1).clone(),
+        ]))
+        .unwrap();
+        assert!(schema.check_compatible(&other).is_ok());
+
+        // Type mismatch.
+        let other = Schema::try_from(&ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int64, true),
+            arrow_schema.field(1).clone(),
+        ]))
+        .unwrap();
+        let err = schema.check_compatible(&other).unwrap_err();
+        assert!(matches!(err, Error::Schema(_)));
+        assert!(err.to_string().contains("Field a has type int64"), "{err}");
+
+        // Nullable data can not be written to a non-nullable nested field.
+        let other = Schema::try_from(&ArrowSchema::new(vec![
+            arrow_schema.field(0).clone(),
+            ArrowField::new(
+                "b",
+                DataType::Struct(ArrowFields::from(vec![
+                    ArrowField::new("f1", DataType::Utf8, true),
+                    ArrowField::new("f2", DataType::Boolean, true),
+                ])),
+                true,
+            ),
+        ]))
+        .unwrap();
+        let err = schema.check_compatible(&other).unwrap_err();
+        assert!(err.to_string().contains("Field f2 is nullable"), "{err}");
+
+        // Missing field.
+        let other =
+            Schema::try_from(&ArrowSchema::new(vec![arrow_schema.field(0).clone()])).unwrap();
+        let err = schema.check_compatible(&other).unwrap_err();
+        assert!(err.to_string().contains("Field b is missing"), "{err}");
+    }
 }
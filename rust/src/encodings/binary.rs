@@ -193,6 +193,21 @@ impl<'a, T: ByteArrayType> BinaryDecoder<'a, T> {
         let start = positions.value(range.start);
         let end = positions.value(range.end);
 
+        // Casting the offsets to i32 would silently turn the overflowed ones into nulls.
+        if !T::Offset::IS_LARGE && end - start > i32::MAX as i64 {
+            let large_type = if T::DATA_TYPE == DataType::Utf8 {
+                "large_string"
+            } else {
+                "large_binary"
+            };
+            return Err(crate::Error::IO(format!(
+                "{} column exceeds 2GiB ({} bytes) in one batch; use {}",
+                T::DATA_TYPE,
+                end - start,
+                large_type
+            )));
+        }
+
         let slice = positions.slice(range.start, range.len() + 1);
         let position_slice: &Int64Array = as_primitive_array(slice.borrow());
         let offset_data = if T::Offset::IS_LARGE {
@@ -441,6 +456,27 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_offsets_overflow() {
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let arr = StringArray::from(vec!["a", "b"]);
+        let pos = write_test_data(&store, &path, &[&arr]).await.unwrap();
+        let reader = store.open(&path).await.unwrap();
+
+        // Positions of a batch with more than 2GiB of strings. The check fails before
+        // reading the bytes.
+        let positions = Int64Array::from(vec![0, 10, 10 + i32::MAX as i64 + 1]);
+        let decoder = BinaryDecoder::<Utf8Type>::new(reader.as_ref(), pos, 2, true);
+        let err = decoder.get_range(&positions, 0..2).await.unwrap_err();
+        assert!(err.to_string().contains("use large_string"));
+        assert!(decoder.get_range(&positions, 1..2).await.is_err());
+
+        let decoder = BinaryDecoder::<LargeUtf8Type>::new(reader.as_ref(), pos, 2, true);
+        let positions = Int64Array::from(vec![0, 1, 2]);
+        assert!(decoder.get_range(&positions, 0..2).await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_write_binary_data() {
         test_round_trips(&[&StringArray::from(vec!["a", "b", "cd", "efg"])]).await;
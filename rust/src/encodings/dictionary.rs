@@ -26,7 +26,8 @@ use arrow_array::types::{
     ArrowDictionaryKeyType, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type,
     UInt64Type, UInt8Type,
 };
-use arrow_array::{Array, ArrayRef, DictionaryArray, PrimitiveArray, UInt32Array};
+use arrow_array::{Array, ArrayRef, DictionaryArray, PrimitiveArray, UInt32Array, UInt64Array};
+use arrow_cast::cast::cast;
 use arrow_schema::DataType;
 use async_trait::async_trait;
 
@@ -135,19 +136,51 @@ impl<'a> DictionaryDecoder<'a> {
         }
     }
 
-    async fn decode_impl(&self, params: impl Into<ReadBatchParams>) -> Result<ArrayRef> {
-        let index_type = if let DataType::Dictionary(key_type, _) = &self.data_type {
+    fn index_type(&self) -> Result<&DataType> {
+        if let DataType::Dictionary(key_type, _) = &self.data_type {
             assert!(key_type.as_ref().is_dictionary_key_type());
-            key_type.as_ref()
+            Ok(key_type.as_ref())
         } else {
-            return Err(Error::Arrow(format!(
+            Err(Error::Arrow(format!(
                 "Not a dictionary type: {}",
                 self.data_type
-            )));
-        };
+            )))
+        }
+    }
 
+    /// Read the key array only, without looking up the dictionary values.
+    async fn decode_keys(&self, params: impl Into<ReadBatchParams>) -> Result<ArrayRef> {
+        let index_type = self.index_type()?;
         let decoder = PlainDecoder::new(self.reader, index_type, self.position, self.length)?;
-        let keys = decoder.get(params.into()).await?;
+        decoder.get(params.into()).await
+    }
+
+    /// Count the occurrences of each dictionary value in this batch.
+    ///
+    /// The returned array is indexed by the dictionary key, and only the keys
+    /// are read from the file.
+    pub async fn value_counts(&self) -> Result<UInt64Array> {
+        let keys = self.decode_keys(..).await?;
+        let keys = cast(&keys, &DataType::UInt64)?;
+        let keys: &UInt64Array = as_primitive_array(keys.as_ref());
+
+        let mut counts = vec![0_u64; self.value_arr.len()];
+        for key in keys.iter().flatten() {
+            let count = counts.get_mut(key as usize).ok_or_else(|| {
+                Error::IO(format!(
+                    "Dictionary key {} is out of range of {} values",
+                    key,
+                    self.value_arr.len()
+                ))
+            })?;
+            *count += 1;
+        }
+        Ok(UInt64Array::from(counts))
+    }
+
+    async fn decode_impl(&self, params: impl Into<ReadBatchParams>) -> Result<ArrayRef> {
+        let index_type = self.index_type()?;
+        let keys = self.decode_keys(params).await?;
 
         match index_type {
             DataType::Int8 => self.make_dict_array::<Int8Type>(keys).await,
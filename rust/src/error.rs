@@ -22,7 +22,15 @@ pub enum Error {
     Arrow(String),
     Schema(String),
     IO(String),
+    /// The target of a conditional write, i.e., a commit, already exists.
+    AlreadyExists(String),
     Index(String),
+    /// The requested item, i.e., a batch or a page, does not exist.
+    NotFound(String),
+    /// The requested rows or bytes are out of the bounds of the data.
+    OutOfRange(String),
+    /// The operation does not support the data, i.e., its data type.
+    Unsupported(String),
     /// Stream early stop
     Stop(),
 }
@@ -35,7 +43,11 @@ impl std::fmt::Display for Error {
             Self::Arrow(s) => ("Arrow", s.as_str()),
             Self::Schema(s) => ("Schema", s.as_str()),
             Self::IO(s) => ("I/O", s.as_str()),
+            Self::AlreadyExists(s) => ("Already exists", s.as_str()),
             Self::Index(s) => ("Index", s.as_str()),
+            Self::NotFound(s) => ("Not found", s.as_str()),
+            Self::OutOfRange(s) => ("Out of range", s.as_str()),
+            Self::Unsupported(s) => ("Unsupported", s.as_str()),
             Self::Stop() => ("Early stop", ""),
         };
         write!(f, "LanceError({catalog}): {message}")
@@ -78,6 +90,13 @@ impl From<tokio::task::JoinError> for Error {
     }
 }
 
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for Error {
+    fn from(e: parquet::errors::ParquetError) -> Self {
+        Self::IO(e.to_string())
+    }
+}
+
 impl From<object_store::path::Error> for Error {
     fn from(e: object_store::path::Error) -> Self {
         Self::IO(e.to_string())
@@ -97,8 +116,12 @@ impl From<Error> for ArrowError {
         match value {
             Error::Arrow(err) => Self::IoError(err), // we lose the error type converting to LanceError
             Error::IO(err) => Self::IoError(err),
+            Error::AlreadyExists(err) => Self::IoError(err),
             Error::Schema(err) => Self::SchemaError(err),
             Error::Index(err) => Self::IoError(err),
+            Error::NotFound(err) => Self::IoError(err),
+            Error::OutOfRange(err) => Self::InvalidArgumentError(err),
+            Error::Unsupported(err) => Self::NotYetImplemented(err),
             Error::Stop() => Self::IoError("early stop".to_string()),
         }
     }
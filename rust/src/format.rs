@@ -9,12 +9,16 @@ mod index;
 mod manifest;
 mod metadata;
 mod page_table;
+mod row_id;
+mod statistics;
 use crate::{Error, Result};
 pub use fragment::*;
 pub use index::Index;
 pub use manifest::Manifest;
-pub use metadata::Metadata;
-pub use page_table::{PageInfo, PageTable};
+pub use metadata::{BatchOffsets, Metadata};
+pub use page_table::{PageChecksum, PageChecksums, PageInfo, PageTable};
+pub use row_id::{decode_row_id, row_id};
+pub use statistics::{ColumnStats, Statistics};
 
 /// Protobuf definitions
 pub mod pb {
@@ -29,6 +29,12 @@ pub struct Metadata {
 
     /// The file position of the manifest block in the file.
     pub manifest_position: Option<usize>,
+
+    /// The file position of the page checksums, if the pages are checksummed.
+    pub page_checksums_position: Option<usize>,
+
+    /// The file position of the column statistics, if the file has statistics.
+    pub statistics_position: Option<usize>,
 }
 
 impl ProtoStruct for Metadata {
@@ -41,6 +47,8 @@ impl From<&Metadata> for pb::Metadata {
             batch_offsets: m.batch_offsets.clone(),
             page_table_position: m.page_table_position as u64,
             manifest_position: m.manifest_position.unwrap_or(0) as u64,
+            page_checksums_position: m.page_checksums_position.unwrap_or(0) as u64,
+            statistics_position: m.statistics_position.unwrap_or(0) as u64,
         }
     }
 }
@@ -51,10 +59,19 @@ impl From<pb::Metadata> for Metadata {
             batch_offsets: m.batch_offsets.clone(),
             page_table_position: m.page_table_position as usize,
             manifest_position: Some(m.manifest_position as usize),
+            page_checksums_position: match m.page_checksums_position {
+                0 => None,
+                pos => Some(pos as usize),
+            },
+            statistics_position: match m.statistics_position {
+                0 => None,
+                pos => Some(pos as usize),
+            },
         }
     }
 }
 
+/// Row offsets within one batch.
 #[derive(Debug, PartialEq)]
 pub struct BatchOffsets {
     pub batch_id: i32,
@@ -143,13 +160,16 @@ impl Metadata {
     ///
     /// It returns a list of (batch_id, in_batch_range) tuples.
     pub(crate) fn range_to_batches(&self, range: Range<usize>) -> Result<Vec<(i32, Range<usize>)>> {
-        if range.end > *(self.batch_offsets.last().unwrap()) as usize {
+        if range.end > self.len() {
             return Err(Error::IO(format!(
                 "Range {:?} is out of bounds {}",
                 range,
-                self.batch_offsets.last().unwrap()
+                self.len()
             )));
         }
+        if range.is_empty() {
+            return Ok(vec![]);
+        }
         let offsets = self.batch_offsets.as_slice();
         let mut batch_id = offsets
             .binary_search(&(range.start as i32))
@@ -215,5 +235,12 @@ mod tests {
 
         let batches = metadata.range_to_batches(14..33).unwrap();
         assert_eq!(batches, vec![(1, 9..10), (2, 0..15), (3, 0..3)]);
+
+        assert!(metadata.range_to_batches(7..7).unwrap().is_empty());
+        assert!(Metadata::default()
+            .range_to_batches(0..0)
+            .unwrap()
+            .is_empty());
+        assert!(Metadata::default().range_to_batches(0..1).is_err());
     }
 }
@@ -24,6 +24,7 @@ use tokio::io::AsyncWriteExt;
 use crate::encodings::plain::PlainDecoder;
 use crate::encodings::Decoder;
 use crate::error::Result;
+use crate::format::{pb, ProtoStruct};
 use crate::io::object_reader::ObjectReader;
 use crate::io::object_writer::ObjectWriter;
 
@@ -125,6 +126,88 @@ impl PageTable {
             .map(|c_map| c_map.get(&batch))
             .flatten()
     }
+
+    /// Iterate over the pages of field `field_id`, ordered by batch.
+    pub fn field_pages(&self, field_id: i32) -> impl Iterator<Item = &PageInfo> {
+        self.pages
+            .get(&field_id)
+            .into_iter()
+            .flat_map(|c_map| c_map.values())
+    }
+
+    /// Iterate over all the pages in the table.
+    pub fn pages(&self) -> impl Iterator<Item = &PageInfo> {
+        self.pages.values().flat_map(|c_map| c_map.values())
+    }
+}
+
+/// CRC32C checksum of the bytes of one page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PageChecksum {
+    /// The file position of the first byte of the page.
+    pub position: usize,
+    /// Number of bytes of the page.
+    pub length: usize,
+    pub crc32c: u32,
+}
+
+/// Page checksums of a data file, keyed by `(field_id, batch_id)`.
+#[derive(Debug, Default, PartialEq)]
+pub struct PageChecksums {
+    checksums: BTreeMap<(i32, i32), PageChecksum>,
+}
+
+impl PageChecksums {
+    pub fn set(&mut self, field_id: i32, batch_id: i32, checksum: PageChecksum) {
+        self.checksums.insert((field_id, batch_id), checksum);
+    }
+
+    pub fn get(&self, field_id: i32, batch_id: i32) -> Option<&PageChecksum> {
+        self.checksums.get(&(field_id, batch_id))
+    }
+}
+
+impl ProtoStruct for PageChecksums {
+    type Proto = pb::PageChecksums;
+}
+
+impl From<&PageChecksums> for pb::PageChecksums {
+    fn from(c: &PageChecksums) -> Self {
+        Self {
+            checksums: c
+                .checksums
+                .iter()
+                .map(|((field_id, batch_id), checksum)| pb::PageChecksum {
+                    field_id: *field_id,
+                    batch_id: *batch_id,
+                    position: checksum.position as u64,
+                    length: checksum.length as u64,
+                    crc32c: checksum.crc32c,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<pb::PageChecksums> for PageChecksums {
+    fn from(c: pb::PageChecksums) -> Self {
+        Self {
+            checksums: c
+                .checksums
+                .iter()
+                .map(|checksum| {
+                    (
+                        (checksum.field_id, checksum.batch_id),
+                        PageChecksum {
+                            position: checksum.position as usize,
+                            length: checksum.length as usize,
+                            crc32c: checksum.crc32c,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,46 @@
+// Copyright 2023 Lance Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Row ID
+//!
+//! A row id is a `u64` whose upper 32 bits are the fragment id and whose
+//! lower 32 bits are the offset of the row within that fragment.
+
+/// Compute the row id from `fragment_id` and the `offset` of the row in the fragment.
+pub fn row_id(fragment_id: u32, offset: u32) -> u64 {
+    ((fragment_id as u64) << 32) | offset as u64
+}
+
+/// Split a row id into its `(fragment_id, offset)`.
+pub fn decode_row_id(id: u64) -> (u32, u32) {
+    ((id >> 32) as u32, id as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_id_round_trip() {
+        for fragment_id in [0, 1, 123, u32::MAX - 1, u32::MAX] {
+            for offset in [0, 1, 1024, u32::MAX - 1, u32::MAX] {
+                let id = row_id(fragment_id, offset);
+                assert_eq!(decode_row_id(id), (fragment_id, offset));
+            }
+        }
+        assert_eq!(row_id(0, 0), 0);
+        assert_eq!(row_id(1, 0), 1 << 32);
+        assert_eq!(row_id(u32::MAX, u32::MAX), u64::MAX);
+    }
+}
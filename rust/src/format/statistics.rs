@@ -0,0 +1,407 @@
+// Copyright 2023 Lance Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Column statistics
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use arrow_array::{
+    cast::{as_boolean_array, as_generic_binary_array, as_largestring_array, as_string_array},
+    make_array, Array, ArrayRef, BinaryArray, BooleanArray, LargeBinaryArray, LargeStringArray,
+    StringArray,
+};
+use arrow_buffer::Buffer;
+use arrow_data::ArrayData;
+use arrow_schema::DataType;
+use datafusion::logical_expr::Accumulator;
+use datafusion::physical_plan::expressions::{MaxAccumulator, MinAccumulator};
+use datafusion::scalar::ScalarValue;
+
+use crate::arrow::*;
+use crate::format::{pb, ProtoStruct};
+use crate::{Error, Result};
+
+/// Max number of bytes of the min and max values of the binary and string columns.
+///
+/// Longer values are truncated, so the statistics of a column of large values, i.e.,
+/// blobs, stay small.
+pub const MAX_BINARY_STATS_LEN: usize = 64;
+
+/// Statistics of one column in one batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    /// Number of null values.
+    pub null_count: usize,
+
+    /// The min non-null value, or `None` if all the values are null.
+    ///
+    /// The binary and string values are truncated to [MAX_BINARY_STATS_LEN] bytes.
+    pub min: Option<ScalarValue>,
+
+    /// The max non-null value, or `None` if all the values are null.
+    ///
+    /// The binary and string values are truncated to [MAX_BINARY_STATS_LEN] bytes and
+    /// rounded up, so it is greater than the actual max. It is also `None` if the
+    /// truncated value can not be rounded up, i.e., it is all `0xFF` bytes.
+    pub max: Option<ScalarValue>,
+}
+
+impl ColumnStats {
+    /// Returns true if statistics can be collected for columns of `data_type`.
+    pub fn is_supported(data_type: &DataType) -> bool {
+        use DataType::*;
+        matches!(
+            data_type,
+            Boolean
+                | Int8
+                | Int16
+                | Int32
+                | Int64
+                | UInt8
+                | UInt16
+                | UInt32
+                | UInt64
+                | Float32
+                | Float64
+                | Date32
+                | Date64
+                | Timestamp(_, _)
+                | Utf8
+                | LargeUtf8
+                | Binary
+                | LargeBinary
+        )
+    }
+
+    /// Compute the statistics over `arrays`, which are stored as one page.
+    pub fn try_new(arrays: &[&dyn Array]) -> Result<Self> {
+        assert!(!arrays.is_empty());
+        let data_type = arrays[0].data_type();
+        let arrays = arrays
+            .iter()
+            .map(|a| make_array(a.data().clone()))
+            .collect::<Vec<ArrayRef>>();
+
+        let mut min = MinAccumulator::try_new(data_type)?;
+        let mut max = MaxAccumulator::try_new(data_type)?;
+        min.update_batch(&arrays)?;
+        max.update_batch(&arrays)?;
+        let non_null = |v: ScalarValue| if v.is_null() { None } else { Some(v) };
+
+        Ok(Self {
+            null_count: arrays.iter().map(|a| a.null_count()).sum(),
+            min: non_null(min.evaluate()?).map(truncate_min),
+            max: non_null(max.evaluate()?).and_then(truncate_max),
+        })
+    }
+}
+
+/// Truncate a binary or string `value` to a prefix, which is not greater than it.
+fn truncate_min(value: ScalarValue) -> ScalarValue {
+    use ScalarValue::*;
+    match value {
+        Utf8(Some(s)) if s.len() > MAX_BINARY_STATS_LEN => Utf8(Some(str_prefix(&s).to_string())),
+        LargeUtf8(Some(s)) if s.len() > MAX_BINARY_STATS_LEN => {
+            LargeUtf8(Some(str_prefix(&s).to_string()))
+        }
+        Binary(Some(b)) if b.len() > MAX_BINARY_STATS_LEN => {
+            Binary(Some(b[..MAX_BINARY_STATS_LEN].to_vec()))
+        }
+        LargeBinary(Some(b)) if b.len() > MAX_BINARY_STATS_LEN => {
+            LargeBinary(Some(b[..MAX_BINARY_STATS_LEN].to_vec()))
+        }
+        v => v,
+    }
+}
+
+/// Truncate a binary or string `value` to a prefix, rounded up to be greater than it.
+///
+/// Returns `None` if the prefix can not be rounded up.
+fn truncate_max(value: ScalarValue) -> Option<ScalarValue> {
+    use ScalarValue::*;
+    match value {
+        Utf8(Some(s)) if s.len() > MAX_BINARY_STATS_LEN => round_up_str(&s).map(|s| Utf8(Some(s))),
+        LargeUtf8(Some(s)) if s.len() > MAX_BINARY_STATS_LEN => {
+            round_up_str(&s).map(|s| LargeUtf8(Some(s)))
+        }
+        Binary(Some(b)) if b.len() > MAX_BINARY_STATS_LEN => {
+            round_up_bytes(&b).map(|b| Binary(Some(b)))
+        }
+        LargeBinary(Some(b)) if b.len() > MAX_BINARY_STATS_LEN => {
+            round_up_bytes(&b).map(|b| LargeBinary(Some(b)))
+        }
+        v => Some(v),
+    }
+}
+
+/// The longest prefix of `s` within [MAX_BINARY_STATS_LEN] bytes.
+fn str_prefix(s: &str) -> &str {
+    let mut end = MAX_BINARY_STATS_LEN;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// The smallest prefix greater than all the bytes starting with the prefix of `bytes`.
+fn round_up_bytes(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut prefix = bytes[..MAX_BINARY_STATS_LEN].to_vec();
+    while let Some(last) = prefix.pop() {
+        if last < u8::MAX {
+            prefix.push(last + 1);
+            return Some(prefix);
+        }
+    }
+    None
+}
+
+/// Same as [round_up_bytes], over the chars of the prefix of `s`.
+///
+/// The rounded up char may be longer, so the result may exceed [MAX_BINARY_STATS_LEN]
+/// by up to 3 bytes.
+fn round_up_str(s: &str) -> Option<String> {
+    let mut prefix = str_prefix(s).chars().collect::<Vec<_>>();
+    while let Some(last) = prefix.pop() {
+        // `from_u32` skips the surrogates, which are not chars.
+        if let Some(next) = (last as u32 + 1..=char::MAX as u32).find_map(char::from_u32) {
+            prefix.push(next);
+            return Some(prefix.into_iter().collect());
+        }
+    }
+    None
+}
+
+/// Column statistics of a data file, keyed by `(field_id, batch_id)`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Statistics {
+    columns: BTreeMap<(i32, i32), pb::ColumnStatistics>,
+}
+
+impl Statistics {
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    pub fn set(&mut self, field_id: i32, batch_id: i32, stats: &ColumnStats) -> Result<()> {
+        let encode = |v: &Option<ScalarValue>| v.as_ref().map(encode_scalar).transpose();
+        self.columns.insert(
+            (field_id, batch_id),
+            pb::ColumnStatistics {
+                field_id,
+                batch_id,
+                null_count: stats.null_count as u64,
+                min_value: encode(&stats.min)?,
+                max_value: encode(&stats.max)?,
+            },
+        );
+        Ok(())
+    }
+
+    /// Get the statistics of a column of `data_type`.
+    pub fn get(
+        &self,
+        field_id: i32,
+        batch_id: i32,
+        data_type: &DataType,
+    ) -> Result<Option<ColumnStats>> {
+        let Some(stats) = self.columns.get(&(field_id, batch_id)) else {
+            return Ok(None);
+        };
+        let decode = |v: &Option<Vec<u8>>| {
+            v.as_ref()
+                .map(|bytes| decode_scalar(bytes, data_type))
+                .transpose()
+        };
+        Ok(Some(ColumnStats {
+            null_count: stats.null_count as usize,
+            min: decode(&stats.min_value)?,
+            max: decode(&stats.max_value)?,
+        }))
+    }
+}
+
+impl ProtoStruct for Statistics {
+    type Proto = pb::Statistics;
+}
+
+impl From<&Statistics> for pb::Statistics {
+    fn from(s: &Statistics) -> Self {
+        Self {
+            columns: s.columns.values().cloned().collect(),
+        }
+    }
+}
+
+impl From<pb::Statistics> for Statistics {
+    fn from(s: pb::Statistics) -> Self {
+        Self {
+            columns: s
+                .columns
+                .into_iter()
+                .map(|c| ((c.field_id, c.batch_id), c))
+                .collect(),
+        }
+    }
+}
+
+/// Encode a non-null scalar value to bytes.
+fn encode_scalar(value: &ScalarValue) -> Result<Vec<u8>> {
+    let arr = value.to_array();
+    let bytes = match arr.data_type() {
+        DataType::Boolean => vec![as_boolean_array(&arr).value(0) as u8],
+        DataType::Utf8 => as_string_array(&arr).value(0).as_bytes().to_vec(),
+        DataType::LargeUtf8 => as_largestring_array(&arr).value(0).as_bytes().to_vec(),
+        DataType::Binary => as_generic_binary_array::<i32>(&arr).value(0).to_vec(),
+        DataType::LargeBinary => as_generic_binary_array::<i64>(&arr).value(0).to_vec(),
+        dt if ColumnStats::is_supported(dt) => {
+            let width = dt.byte_width();
+            let start = arr.offset() * width;
+            arr.data().buffers()[0].as_slice()[start..start + width].to_vec()
+        }
+        dt => {
+            return Err(Error::Schema(format!(
+                "Column statistics are not supported for type {dt}"
+            )))
+        }
+    };
+    Ok(bytes)
+}
+
+/// Decode a scalar value of `data_type` encoded by [`encode_scalar`].
+fn decode_scalar(bytes: &[u8], data_type: &DataType) -> Result<ScalarValue> {
+    let arr: ArrayRef = match data_type {
+        DataType::Boolean => Arc::new(BooleanArray::from(vec![bytes[0] != 0])),
+        DataType::Utf8 => Arc::new(StringArray::from(vec![
+            std::str::from_utf8(bytes).map_err(|e| Error::IO(e.to_string()))?
+        ])),
+        DataType::LargeUtf8 => Arc::new(LargeStringArray::from(vec![std::str::from_utf8(bytes)
+            .map_err(|e| {
+            Error::IO(e.to_string())
+        })?])),
+        DataType::Binary => Arc::new(BinaryArray::from(vec![bytes])),
+        DataType::LargeBinary => Arc::new(LargeBinaryArray::from(vec![bytes])),
+        dt if ColumnStats::is_supported(dt) => {
+            if bytes.len() != dt.byte_width() {
+                return Err(Error::IO(format!(
+                    "Invalid statistics value for type {dt}: {} bytes",
+                    bytes.len()
+                )));
+            }
+            let data = ArrayData::builder(dt.clone())
+                .len(1)
+                .add_buffer(Buffer::from_slice_ref(bytes))
+                .build()?;
+            make_array(data)
+        }
+        dt => {
+            return Err(Error::Schema(format!(
+                "Column statistics are not supported for type {dt}"
+            )))
+        }
+    };
+    Ok(ScalarValue::try_from_array(&arr, 0)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::{Float64Array, Int32Array};
+    use arrow_schema::TimeUnit;
+
+    #[test]
+    fn test_column_stats() {
+        let a = Int32Array::from(vec![Some(5), None, Some(-3)]);
+        let b = Int32Array::from(vec![Some(10), None]);
+        let stats = ColumnStats::try_new(&[&a as &dyn Array, &b]).unwrap();
+        assert_eq!(
+            stats,
+            ColumnStats {
+                null_count: 2,
+                min: Some(ScalarValue::Int32(Some(-3))),
+                max: Some(ScalarValue::Int32(Some(10))),
+            }
+        );
+
+        let nulls = Float64Array::from(vec![None, None]);
+        let stats = ColumnStats::try_new(&[&nulls as &dyn Array]).unwrap();
+        assert_eq!(stats.null_count, 2);
+        assert_eq!(stats.min, None);
+        assert_eq!(stats.max, None);
+    }
+
+    #[test]
+    fn test_truncate_binary_stats() {
+        let strings = StringArray::from(vec!["a".repeat(100), "b".repeat(100)]);
+        let stats = ColumnStats::try_new(&[&strings as &dyn Array]).unwrap();
+        assert_eq!(stats.min, Some(ScalarValue::Utf8(Some("a".repeat(64)))));
+        assert_eq!(
+            stats.max,
+            Some(ScalarValue::Utf8(Some("b".repeat(63) + "c")))
+        );
+
+        // Truncated at a char boundary.
+        let strings = StringArray::from(vec!["\u{e9}".repeat(50)]);
+        let stats = ColumnStats::try_new(&[&strings as &dyn Array]).unwrap();
+        assert_eq!(
+            stats.min,
+            Some(ScalarValue::Utf8(Some("\u{e9}".repeat(32))))
+        );
+        assert_eq!(
+            stats.max,
+            Some(ScalarValue::Utf8(Some("\u{e9}".repeat(31) + "\u{ea}")))
+        );
+
+        let blobs = BinaryArray::from(vec![[1_u8; 1000].as_slice(), [0xFF; 1000].as_slice()]);
+        let stats = ColumnStats::try_new(&[&blobs as &dyn Array]).unwrap();
+        assert_eq!(stats.min, Some(ScalarValue::Binary(Some(vec![1; 64]))));
+        // The max can not be rounded up.
+        assert_eq!(stats.max, None);
+
+        let blobs = BinaryArray::from(vec![[7_u8; 1000].as_slice()]);
+        let stats = ColumnStats::try_new(&[&blobs as &dyn Array]).unwrap();
+        let mut max = vec![7_u8; 64];
+        max[63] = 8;
+        assert_eq!(stats.max, Some(ScalarValue::Binary(Some(max))));
+
+        for value in [stats.min.as_ref(), stats.max.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            assert!(encode_scalar(value).unwrap().len() <= MAX_BINARY_STATS_LEN);
+        }
+    }
+
+    #[test]
+    fn test_encode_decode_scalar() {
+        for value in [
+            ScalarValue::Boolean(Some(true)),
+            ScalarValue::Int64(Some(-42)),
+            ScalarValue::UInt8(Some(7)),
+            ScalarValue::Float32(Some(1.5)),
+            ScalarValue::TimestampMicrosecond(Some(1_000_000), None),
+            ScalarValue::Utf8(Some("lance".to_string())),
+            ScalarValue::LargeBinary(Some(b"\x00\x01".to_vec())),
+        ] {
+            let bytes = encode_scalar(&value).unwrap();
+            let decoded = decode_scalar(&bytes, &value.get_datatype()).unwrap();
+            assert_eq!(decoded, value);
+        }
+        assert!(matches!(
+            decode_scalar(&[1, 2], &DataType::Timestamp(TimeUnit::Second, None)),
+            Err(Error::IO(_))
+        ));
+    }
+}
@@ -52,10 +52,7 @@ use crate::{
         object_reader::{read_message, ObjectReader},
         read_message_from_buf, read_metadata_offset,
     },
-    linalg::{
-        cosine::{cosine_distance, cosine_distance_batch},
-        l2::{l2_distance, l2_distance_batch},
-    },
+    linalg::{cosine::cosine_distance, distance_batch, l2::l2_distance},
     Error, Result,
 };
 pub use traits::*;
@@ -101,10 +98,10 @@ impl MetricType {
     pub fn batch_func(
         &self,
     ) -> Arc<dyn Fn(&[f32], &[f32], usize) -> Arc<Float32Array> + Send + Sync + 'static> {
-        match self {
-            Self::L2 => Arc::new(l2_distance_batch),
-            Self::Cosine => Arc::new(cosine_distance_batch),
-        }
+        let metric_type = *self;
+        Arc::new(move |from: &[f32], to: &[f32], dimension: usize| {
+            distance_batch(metric_type, from, to, dimension)
+        })
     }
 
     /// Returns the distance function between two vectors.
@@ -24,6 +24,8 @@ use prost::bytes::Bytes;
 use prost::Message;
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 
+#[cfg(any(feature = "csv", feature = "parquet"))]
+pub mod convert;
 pub(crate) mod exec;
 pub mod local;
 pub mod object_reader;
@@ -36,8 +38,9 @@ mod writer;
 use crate::format::{ProtoStruct, INDEX_MAGIC, MAGIC};
 
 pub use self::object_store::ObjectStore;
-pub use reader::read_manifest;
-pub use reader::FileReader;
+pub use reader::{read_manifest, read_manifest_bytes};
+pub use reader::{row_id_field, ROW_ID_FIELD_NAME};
+pub use reader::{FileReader, ReadOptions};
 pub use stream::RecordBatchStream;
 pub use writer::*;
 
@@ -84,7 +87,7 @@ pub fn read_struct_from_buf<M: Message + Default, T: ProtoStruct<Proto = M> + Fr
 
 /// Parameter to be used to read a batch.
 #[derive(Debug, Clone)]
-pub(crate) enum ReadBatchParams {
+pub enum ReadBatchParams {
     Range(Range<usize>),
 
     RangeFull,
@@ -96,6 +99,37 @@ pub(crate) enum ReadBatchParams {
     Indices(UInt32Array),
 }
 
+impl ReadBatchParams {
+    /// The smallest row range, within a batch of `batch_length` rows, that covers
+    /// all the rows selected by this parameter.
+    pub(crate) fn covering_range(&self, batch_length: usize) -> Range<usize> {
+        match self {
+            Self::Range(r) => r.clone(),
+            Self::RangeFull => 0..batch_length,
+            Self::RangeTo(r) => 0..r.end,
+            Self::RangeFrom(r) => r.start..batch_length,
+            Self::Indices(indices) => {
+                if indices.is_empty() {
+                    0..0
+                } else {
+                    let values = indices.values();
+                    let min = *values.iter().min().unwrap() as usize;
+                    let max = *values.iter().max().unwrap() as usize;
+                    min..max + 1
+                }
+            }
+        }
+    }
+
+    /// Number of rows selected by this parameter, from a batch of `batch_length` rows.
+    pub(crate) fn num_rows(&self, batch_length: usize) -> usize {
+        match self {
+            Self::Indices(indices) => indices.len(),
+            _ => self.covering_range(batch_length).len(),
+        }
+    }
+}
+
 /// Default of ReadBatchParams is reading the full batch.
 impl Default for ReadBatchParams {
     fn default() -> Self {
@@ -0,0 +1,206 @@
+// Copyright 2023 Lance Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Convert other file formats to Lance datasets.
+
+use std::fs::File;
+#[cfg(feature = "csv")]
+use std::io::Seek;
+use std::path::Path as StdPath;
+#[cfg(feature = "csv")]
+use std::sync::Arc;
+
+use arrow_array::RecordBatchReader;
+#[cfg(feature = "csv")]
+use arrow_csv::reader::{Format, ReaderBuilder};
+#[cfg(feature = "parquet")]
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use crate::dataset::{Dataset, WriteParams};
+use crate::datatypes::Schema;
+use crate::Result;
+
+/// Convert a Parquet file to a Lance dataset at `lance_uri`.
+///
+/// Returns [Err] if the Parquet schema contains data types that Lance does not support.
+#[cfg(feature = "parquet")]
+pub async fn parquet_to_lance(
+    parquet_path: impl AsRef<StdPath>,
+    lance_uri: &str,
+    params: WriteParams,
+) -> Result<()> {
+    let file = File::open(parquet_path)?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+    // Fail before creating the dataset if the schema can not be stored in Lance.
+    Schema::try_from(reader.schema().as_ref())?;
+
+    let mut batches: Box<dyn RecordBatchReader> = Box::new(reader);
+    Dataset::write(&mut batches, lance_uri, Some(params)).await?;
+    Ok(())
+}
+
+/// Convert a CSV file to a Lance dataset at `lance_uri`.
+///
+/// The schema is inferred from the CSV records. If `has_header` is true, the first line
+/// is used as the column names.
+#[cfg(feature = "csv")]
+pub async fn csv_to_lance(
+    csv_path: impl AsRef<StdPath>,
+    lance_uri: &str,
+    has_header: bool,
+    params: WriteParams,
+) -> Result<()> {
+    let mut file = File::open(csv_path)?;
+    let (arrow_schema, _) = Format::default()
+        .with_header(has_header)
+        .infer_schema(&mut file, None)?;
+    // Fail before creating the dataset if the schema can not be stored in Lance.
+    Schema::try_from(&arrow_schema)?;
+    file.rewind()?;
+
+    let reader = ReaderBuilder::new(Arc::new(arrow_schema))
+        .has_header(has_header)
+        .build(file)?;
+    let mut batches: Box<dyn RecordBatchReader> = Box::new(reader);
+    Dataset::write(&mut batches, lance_uri, Some(params)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "csv")]
+    use std::io::Write;
+    use std::sync::Arc;
+
+    #[cfg(feature = "csv")]
+    use arrow_array::Int64Array;
+    use arrow_array::{Float64Array, StringArray};
+    #[cfg(feature = "parquet")]
+    use arrow_array::{Int32Array, RecordBatch};
+    use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+    use arrow_select::concat::concat_batches;
+    use futures::TryStreamExt;
+    #[cfg(feature = "parquet")]
+    use parquet::arrow::ArrowWriter;
+    use tempfile::tempdir;
+
+    #[cfg(feature = "parquet")]
+    #[tokio::test]
+    async fn test_parquet_to_lance() {
+        let test_dir = tempdir().unwrap();
+        let parquet_path = test_dir.path().join("data.parquet");
+        let lance_uri = test_dir.path().join("data.lance");
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, false),
+            ArrowField::new("f", DataType::Float64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..100)),
+                Arc::new(Float64Array::from_iter_values((0..100).map(|v| v as f64))),
+                Arc::new(StringArray::from_iter_values(
+                    (0..100).map(|v| format!("s-{v}")),
+                )),
+            ],
+        )
+        .unwrap();
+
+        let mut writer =
+            ArrowWriter::try_new(File::create(&parquet_path).unwrap(), schema.clone(), None)
+                .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        parquet_to_lance(
+            &parquet_path,
+            lance_uri.to_str().unwrap(),
+            WriteParams::default(),
+        )
+        .await
+        .unwrap();
+
+        let dataset = Dataset::open(lance_uri.to_str().unwrap()).await.unwrap();
+        let batches = dataset
+            .scan()
+            .try_into_stream()
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(concat_batches(&schema, &batches).unwrap(), batch);
+    }
+
+    #[cfg(feature = "csv")]
+    #[tokio::test]
+    async fn test_csv_to_lance() {
+        let test_dir = tempdir().unwrap();
+        let csv_path = test_dir.path().join("data.csv");
+        let lance_uri = test_dir.path().join("data.lance");
+
+        let mut file = File::create(&csv_path).unwrap();
+        writeln!(file, "i,f,s").unwrap();
+        for v in 0..10 {
+            writeln!(file, "{v},{v}.5,s-{v}").unwrap();
+        }
+        drop(file);
+
+        csv_to_lance(
+            &csv_path,
+            lance_uri.to_str().unwrap(),
+            true,
+            WriteParams::default(),
+        )
+        .await
+        .unwrap();
+
+        let dataset = Dataset::open(lance_uri.to_str().unwrap()).await.unwrap();
+        let schema = Arc::new(ArrowSchema::from(dataset.schema()));
+        assert_eq!(
+            schema.as_ref(),
+            &ArrowSchema::new(vec![
+                ArrowField::new("i", DataType::Int64, true),
+                ArrowField::new("f", DataType::Float64, true),
+                ArrowField::new("s", DataType::Utf8, true),
+            ])
+        );
+
+        let batches = dataset
+            .scan()
+            .try_into_stream()
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let batch = concat_batches(&schema, &batches).unwrap();
+        assert_eq!(
+            batch.column_by_name("i").unwrap().as_ref(),
+            &Int64Array::from_iter_values(0..10)
+        );
+        assert_eq!(
+            batch.column_by_name("f").unwrap().as_ref(),
+            &Float64Array::from_iter_values((0..10).map(|v| v as f64 + 0.5))
+        );
+        assert_eq!(
+            batch.column_by_name("s").unwrap().as_ref(),
+            &StringArray::from_iter_values((0..10).map(|v| format!("s-{v}")))
+        );
+    }
+}
@@ -18,11 +18,16 @@
 use std::cmp::min;
 
 use std::ops::Range;
+use std::ptr::NonNull;
+use std::sync::Arc;
 
 use arrow_array::{
+    make_array,
     types::{BinaryType, LargeBinaryType, LargeUtf8Type, Utf8Type},
     ArrayRef,
 };
+use arrow_buffer::Buffer;
+use arrow_data::ArrayDataBuilder;
 use arrow_schema::DataType;
 use async_trait::async_trait;
 use byteorder::{ByteOrder, LittleEndian};
@@ -97,12 +102,15 @@ impl ObjectReader for CloudObjectReader {
 }
 
 /// Read a protobuf message at file position 'pos'.
-pub(crate) async fn read_message<M: Message + Default>(
+///
+/// The message is prefixed by its length, as a little-endian `u32`, as written by
+/// [`ObjectWriter::write_protobuf`](crate::io::object_writer::ObjectWriter::write_protobuf).
+pub async fn read_message<M: Message + Default>(
     reader: &dyn ObjectReader,
     pos: usize,
 ) -> Result<M> {
     let file_size = reader.size().await?;
-    if pos > file_size {
+    if pos + 4 > file_size {
         return Err(Error::IO("file size is too small".to_string()));
     }
 
@@ -154,6 +162,50 @@ pub(crate) async fn read_fixed_stride_array(
     decoder.get(params.into()).await
 }
 
+/// Read a fixed stride array from disk, sharing the buffer returned by [`ObjectReader::get_range()`].
+///
+/// When the reader serves the range from memory, i.e., the memory store, the returned array
+/// is backed by that memory without copying. It falls back to [read_fixed_stride_array] to
+/// take indices or read non-primitive types, and copies the bytes if they are not aligned
+/// to the data type.
+pub(crate) async fn read_fixed_stride_array_zerocopy(
+    reader: &dyn ObjectReader,
+    data_type: &DataType,
+    position: usize,
+    length: usize,
+    params: impl Into<ReadBatchParams>,
+) -> Result<ArrayRef> {
+    let params = params.into();
+    let range = match &params {
+        ReadBatchParams::Indices(_) => None,
+        p => Some(p.covering_range(length)),
+    };
+    let range = match range {
+        Some(r) if DataType::is_primitive(data_type) && r.end <= length => r,
+        _ => return read_fixed_stride_array(reader, data_type, position, length, params).await,
+    };
+
+    let byte_width = data_type.byte_width();
+    let bytes = reader
+        .get_range(position + range.start * byte_width..position + range.end * byte_width)
+        .await?;
+    let ptr = NonNull::new(bytes.as_ptr() as *mut u8);
+    let buf = match ptr {
+        Some(ptr) if ptr.as_ptr().align_offset(byte_width) == 0 => {
+            let len = bytes.len();
+            // Safety: `bytes` owns the memory of `ptr..ptr + len`, and is kept alive by the buffer.
+            unsafe { Buffer::from_custom_allocation(ptr, len, Arc::new(bytes)) }
+        }
+        _ => Buffer::from(bytes.as_ref()),
+    };
+    let array_data = ArrayDataBuilder::new(data_type.clone())
+        .len(range.len())
+        .null_count(0)
+        .add_buffer(buf)
+        .build()?;
+    Ok(make_array(array_data))
+}
+
 pub(crate) async fn read_binary_array(
     reader: &dyn ObjectReader,
     data_type: &DataType,
@@ -183,4 +235,119 @@ pub(crate) async fn read_binary_array(
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    use arrow_array::{cast::as_primitive_array, types::UInt8Type, Array, Int32Array, UInt8Array};
+    use arrow_buffer::ToByteSlice;
+    use object_store::path::Path;
+    use tokio::io::AsyncWriteExt;
+
+    /// A custom block, not defined in the Lance format.
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct CustomBlock {
+        #[prost(string, tag = "1")]
+        name: String,
+        #[prost(uint64, repeated, tag = "2")]
+        offsets: Vec<u64>,
+    }
+
+    #[tokio::test]
+    async fn test_read_message() {
+        let store = ObjectStore::memory();
+        let path = Path::from("/custom_block");
+        let small = CustomBlock {
+            name: "small".to_string(),
+            offsets: vec![1, 2, 3],
+        };
+        // Larger than the first read of read_message().
+        let large = CustomBlock {
+            name: "large".to_string(),
+            offsets: (0..10_000).collect(),
+        };
+
+        let mut writer = store.create(&path).await.unwrap();
+        writer.write_all(b"prefix").await.unwrap();
+        let small_pos = writer.write_protobuf(&small).await.unwrap();
+        let large_pos = writer.write_protobuf(&large).await.unwrap();
+        writer.shutdown().await.unwrap();
+        assert_eq!(small_pos, 6);
+
+        let reader = store.open(&path).await.unwrap();
+        let msg: CustomBlock = read_message(reader.as_ref(), small_pos).await.unwrap();
+        assert_eq!(msg, small);
+        let msg: CustomBlock = read_message(reader.as_ref(), large_pos).await.unwrap();
+        assert_eq!(msg, large);
+
+        let size = reader.size().await.unwrap();
+        assert!(read_message::<CustomBlock>(reader.as_ref(), size - 2)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_fixed_stride_array_zerocopy() {
+        let store = ObjectStore::memory();
+        let path = Path::from("/zerocopy");
+        let data = Bytes::from((0..100_u8).collect::<Vec<_>>());
+        store.inner.put(&path, data.clone()).await.unwrap();
+        let reader = store.open(&path).await.unwrap();
+
+        let arr =
+            read_fixed_stride_array_zerocopy(reader.as_ref(), &DataType::UInt8, 10, 80, 5..20)
+                .await
+                .unwrap();
+        let values: &UInt8Array = as_primitive_array::<UInt8Type>(arr.as_ref());
+        assert_eq!(values, &UInt8Array::from_iter_values(15..30));
+        // Shares the allocation of the in-memory object.
+        assert_eq!(values.values().as_ptr(), data[15..].as_ptr());
+
+        // Falls back to copying path for indices.
+        let arr = read_fixed_stride_array_zerocopy(
+            reader.as_ref(),
+            &DataType::UInt8,
+            10,
+            80,
+            [1_u32, 3].as_slice(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(arr.as_ref(), &UInt8Array::from(vec![11, 13]) as &dyn Array);
+
+        // Out of range.
+        assert!(read_fixed_stride_array_zerocopy(
+            reader.as_ref(),
+            &DataType::UInt8,
+            10,
+            80,
+            70..90
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_fixed_stride_array_zerocopy_int32() {
+        let store = ObjectStore::memory();
+        let path = Path::from("/zerocopy_int32");
+        let values = Int32Array::from_iter_values(0..100);
+        store
+            .inner
+            .put(
+                &path,
+                Bytes::copy_from_slice(values.values().to_byte_slice()),
+            )
+            .await
+            .unwrap();
+        let reader = store.open(&path).await.unwrap();
+
+        let arr = read_fixed_stride_array_zerocopy(reader.as_ref(), &DataType::Int32, 0, 100, ..)
+            .await
+            .unwrap();
+        assert_eq!(arr.as_ref(), &values as &dyn Array);
+        let arr = read_fixed_stride_array_zerocopy(reader.as_ref(), &DataType::Int32, 0, 100, 90..)
+            .await
+            .unwrap();
+        assert_eq!(arr.as_ref(), Array::slice(&values, 90, 10).as_ref());
+    }
+}
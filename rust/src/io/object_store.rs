@@ -15,13 +15,14 @@
 //! Wraps [ObjectStore](object_store::ObjectStore)
 
 use std::ops::Deref;
-use std::path::Path as StdPath;
+use std::path::{Component, Path as StdPath};
 use std::sync::Arc;
+use std::time::Duration;
 
 use ::object_store::{
-    aws::AmazonS3Builder, memory::InMemory, path::Path, ObjectStore as OSObjectStore,
+    aws::AmazonS3Builder, memory::InMemory, path::Path, ObjectMeta, ObjectStore as OSObjectStore,
 };
-use futures::{future, TryFutureExt};
+use futures::{future, stream, Stream, TryFutureExt, TryStreamExt};
 use object_store::gcp::GoogleCloudStorageBuilder;
 use object_store::local::LocalFileSystem;
 use object_store::ClientOptions;
@@ -36,6 +37,16 @@ use crate::io::object_writer::ObjectWriter;
 use super::local::LocalObjectReader;
 use super::object_reader::ObjectReader;
 
+mod cache;
+mod retry;
+#[cfg(test)]
+pub(crate) mod testing;
+mod timeout;
+
+use cache::CachedObjectStore;
+use retry::RetryObjectStore;
+use timeout::TimeoutObjectStore;
+
 /// Wraps [ObjectStore](object_store::ObjectStore)
 #[derive(Debug, Clone)]
 pub struct ObjectStore {
@@ -137,6 +148,73 @@ impl ObjectStore {
         }
     }
 
+    /// Parse a URI pointing to a file into the [ObjectStore] that holds it, and the
+    /// path of the file within that store.
+    ///
+    /// Supported URIs are `s3://bucket/key`, `gs://bucket/key`, `file:///path/to/file`,
+    /// `memory:///path/to/file` and local file system paths.
+    ///
+    /// Returns [Error::IO] if the URI scheme is not supported.
+    pub async fn from_uri(uri: &str) -> Result<(Self, Path)> {
+        let url = match Url::parse(uri) {
+            // Single letter schemes are Windows drive letters, i.e., `C:\path`.
+            Ok(url) if url.scheme().len() > 1 => url,
+            _ => return Self::from_local_path(uri),
+        };
+        // The path of the URL is percent-encoded, i.e., a space is `%20`.
+        let path = Path::from_url_path(url.path())
+            .map_err(|e| Error::IO(format!("Invalid path in URI {uri}: {e}")))?;
+        match url.scheme() {
+            "s3" | "gs" => {
+                let mut store = Self::new_from_url(url).await?;
+                // `path` is relative to the root of the bucket.
+                store.base_path = Path::from(object_store::path::DELIMITER);
+                Ok((store, path))
+            }
+            "file" => {
+                let file_path = url
+                    .to_file_path()
+                    .map_err(|_| Error::IO(format!("Invalid file URI {uri}")))?;
+                Self::from_local_path(&file_path.to_string_lossy())
+            }
+            "memory" => Ok((Self::memory(), path)),
+            s => Err(Error::IO(format!("Unsupported scheme {}", s))),
+        }
+    }
+
+    /// The local file system, and the absolute path of `str_path` on it.
+    fn from_local_path(str_path: &str) -> Result<(Self, Path)> {
+        let expanded = tilde(str_path).to_string();
+        let expanded_path = StdPath::new(&expanded);
+        let absolute = if expanded_path.is_absolute() {
+            expanded_path.to_path_buf()
+        } else {
+            std::env::current_dir()?.join(expanded_path)
+        };
+
+        // Normalize the path lexically, as the file does not need to exist yet.
+        let mut parts: Vec<String> = vec![];
+        for component in absolute.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+                Component::ParentDir => {
+                    parts.pop();
+                }
+                _ => {}
+            }
+        }
+
+        Ok((
+            Self {
+                inner: Arc::new(LocalFileSystem::new()),
+                scheme: String::from("file"),
+                base_path: Path::from(object_store::path::DELIMITER),
+                block_size: 4 * 1024, // 4KB block size
+            },
+            Path::from_iter(parts),
+        ))
+    }
+
     /// Create a in-memory object store directly.
     pub(crate) fn memory() -> Self {
         Self {
@@ -147,6 +225,43 @@ impl ObjectStore {
         }
     }
 
+    /// Retry the reads on transient errors, up to `max` times.
+    ///
+    /// The delay before each retry starts at `backoff`, and doubles after every retry.
+    /// Errors like a missing file are returned without retrying.
+    ///
+    /// The readers opened from this store inherit the retries, except the local files,
+    /// which are not read through the object store.
+    pub fn with_retries(mut self, max: usize, backoff: Duration) -> Self {
+        self.inner = Arc::new(RetryObjectStore::new(self.inner, max, backoff));
+        self
+    }
+
+    /// Fail the reads, i.e., `get_range` and `head`, that take longer than `timeout` with
+    /// [Error::IO].
+    ///
+    /// The readers opened from this store inherit the timeout, except the local files,
+    /// which are not read through the object store. Called before
+    /// [`ObjectStore::with_retries()`], each attempt is bounded and a timed out request
+    /// is retried.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.inner = Arc::new(TimeoutObjectStore::new(self.inner, timeout));
+        self
+    }
+
+    /// Cache the range reads in memory, up to `capacity_bytes` bytes.
+    ///
+    /// Repeated reads of the same range of a file, i.e., the metadata at the tail of a
+    /// file, are served from the cache. The cached ranges of a file are invalidated when
+    /// it is written or deleted through this store.
+    ///
+    /// The readers opened from this store share the cache, except the local files, which
+    /// are not read through the object store.
+    pub fn with_cache(mut self, capacity_bytes: usize) -> Self {
+        self.inner = Arc::new(CachedObjectStore::new(self.inner, capacity_bytes));
+        self
+    }
+
     pub fn block_size(&self) -> usize {
         self.block_size
     }
@@ -189,6 +304,47 @@ impl ObjectStore {
     pub async fn size(&self, path: &Path) -> Result<usize> {
         Ok(self.inner.head(path).await?.size)
     }
+
+    /// Atomically rename `from` to `to`, only if `to` does not exist yet.
+    ///
+    /// Returns [Error::AlreadyExists] if `to` exists, in which case neither file is changed.
+    /// Of several concurrent renames to the same target, at most one succeeds.
+    ///
+    /// S3 can not copy an object only if the target does not exist, so this returns
+    /// [Error::Unsupported] on S3.
+    pub async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        match self.inner.rename_if_not_exists(from, to).await {
+            Err(object_store::Error::AlreadyExists { path, source: _ }) => {
+                Err(Error::AlreadyExists(path))
+            }
+            Err(object_store::Error::NotImplemented) => Err(Error::Unsupported(format!(
+                "rename_if_not_exists is not supported by the {} object store",
+                self.scheme
+            ))),
+            result => Ok(result?),
+        }
+    }
+
+    /// List all the files under `prefix`, recursively.
+    pub fn list<'a>(&'a self, prefix: &'a Path) -> impl Stream<Item = Result<ObjectMeta>> + 'a {
+        stream::once(self.inner.list(Some(prefix)))
+            .try_flatten()
+            .map_err(Error::from)
+    }
+
+    /// List all the Lance data files, with the `.lance` extension, under `prefix`.
+    pub fn list_lance_files<'a>(
+        &'a self,
+        prefix: &'a Path,
+    ) -> impl Stream<Item = Result<ObjectMeta>> + 'a {
+        self.list(prefix).try_filter(|meta| {
+            future::ready(
+                meta.location
+                    .filename()
+                    .map_or(false, |name| name.ends_with(".lance")),
+            )
+        })
+    }
 }
 
 #[cfg(test)]
@@ -197,6 +353,8 @@ mod tests {
     use std::env::set_current_dir;
     use std::fs::write;
 
+    use tokio::io::AsyncWriteExt;
+
     fn write_to_fs_file(path_str: String, contents: String) -> std::io::Result<()> {
         let expanded = tilde(&path_str).to_string();
         let path = StdPath::new(&expanded);
@@ -266,6 +424,156 @@ mod tests {
         assert_eq!(contents, "TILDE");
     }
 
+    #[tokio::test]
+    async fn test_from_uri_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let tmp_path = tmp_dir.path().to_str().unwrap().to_owned();
+        write_to_fs_file(tmp_path.clone() + "/bar/data.lance", "FILE_URI".to_string()).unwrap();
+        write_to_fs_file(
+            tmp_path.clone() + "/b a r/data.lance",
+            "FILE_URI".to_string(),
+        )
+        .unwrap();
+
+        for uri in &[
+            format!("file://{tmp_path}/bar/data.lance"),
+            format!("{tmp_path}/bar/data.lance"),
+            format!("{tmp_path}/bar/../bar/./data.lance"),
+            format!("file://{tmp_path}/b%20a%20r/data.lance"),
+        ] {
+            let (store, path) = ObjectStore::from_uri(uri).await.unwrap();
+            assert_eq!(path.filename(), Some("data.lance"));
+            let contents = read_from_store(store, &path).await.unwrap();
+            assert_eq!(contents, "FILE_URI");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_from_uri_memory() {
+        let (store, path) = ObjectStore::from_uri("memory:///foo/data.lance")
+            .await
+            .unwrap();
+        assert_eq!(path, Path::from("foo/data.lance"));
+
+        let (_, path) = ObjectStore::from_uri("memory:///foo%20bar/data.lance")
+            .await
+            .unwrap();
+        assert_eq!(path, Path::from("foo bar/data.lance"));
+
+        let mut writer = store.create(&path).await.unwrap();
+        writer.write_all(b"MEMORY").await.unwrap();
+        writer.shutdown().await.unwrap();
+        let contents = read_from_store(store, &path).await.unwrap();
+        assert_eq!(contents, "MEMORY");
+    }
+
+    #[tokio::test]
+    async fn test_from_uri_unsupported_scheme() {
+        let result = ObjectStore::from_uri("unknown://bucket/data.lance").await;
+        assert!(matches!(result, Err(Error::IO(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list() {
+        let store = ObjectStore::memory();
+        for path in [
+            "data/a.lance",
+            "data/b.lance",
+            "data/sub/c.lance",
+            "data/notes.txt",
+            "other/d.lance",
+        ] {
+            store
+                .inner
+                .put(&Path::from(path), "DATA".into())
+                .await
+                .unwrap();
+        }
+
+        let prefix = Path::from("data");
+        let mut files = store
+            .list(&prefix)
+            .map_ok(|meta| meta.location.to_string())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                "data/a.lance",
+                "data/b.lance",
+                "data/notes.txt",
+                "data/sub/c.lance"
+            ]
+        );
+
+        let mut files = store
+            .list_lance_files(&prefix)
+            .map_ok(|meta| meta.location.to_string())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec!["data/a.lance", "data/b.lance", "data/sub/c.lance"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        use testing::MockObjectStore;
+
+        let path = Path::from("foo");
+        let mock = MockObjectStore::new().with_delay(Duration::from_millis(200));
+        mock.put(&path, "0123456789".into()).await.unwrap();
+        let store = ObjectStore {
+            inner: Arc::new(mock),
+            ..ObjectStore::memory()
+        };
+
+        let slow = store.clone().with_timeout(Duration::from_millis(10));
+        let reader = slow.open(&path).await.unwrap();
+        let err = reader.get_range(0..4).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::IO(msg) if msg.contains("object store request timed out")),
+            "{err}"
+        );
+        assert!(matches!(slow.size(&path).await, Err(Error::IO(_))));
+
+        let patient = store.with_timeout(Duration::from_secs(10));
+        let reader = patient.open(&path).await.unwrap();
+        assert_eq!(reader.get_range(0..4).await.unwrap(), "0123".as_bytes());
+        assert_eq!(patient.size(&path).await.unwrap(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_rename_if_not_exists() {
+        let store = ObjectStore::memory();
+        let from1 = Path::from("1.manifest.tmp");
+        let from2 = Path::from("2.manifest.tmp");
+        let to = Path::from("1.manifest");
+        store.inner.put(&from1, "FIRST".into()).await.unwrap();
+        store.inner.put(&from2, "SECOND".into()).await.unwrap();
+
+        let (result1, result2) = futures::join!(
+            store.rename_if_not_exists(&from1, &to),
+            store.rename_if_not_exists(&from2, &to)
+        );
+        assert!(result1.is_ok() != result2.is_ok());
+        let (winner, loser) = if result1.is_ok() {
+            ("FIRST", &from2)
+        } else {
+            ("SECOND", &from1)
+        };
+        assert!(matches!(result1.and(result2), Err(Error::AlreadyExists(_))));
+
+        assert_eq!(read_from_store(store.clone(), &to).await.unwrap(), winner);
+        // The source of the failed rename is kept.
+        assert!(store.exists(loser).await.unwrap());
+    }
+
     #[tokio::test]
     #[cfg(windows)]
     async fn test_windows_paths() {
@@ -0,0 +1,298 @@
+// Copyright 2023 Lance Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cache the range reads of an [ObjectStore] in memory.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, GetResult, ListResult, MultipartId, ObjectMeta, ObjectStore, Result,
+};
+use tokio::io::AsyncWrite;
+
+type CacheKey = (Path, Range<usize>);
+
+/// LRU cache of byte ranges, bounded by the total size of the cached bytes.
+#[derive(Debug)]
+struct RangeCache {
+    /// The maximum number of bytes to cache.
+    capacity: usize,
+
+    /// The number of bytes cached.
+    size: usize,
+
+    /// Increases on every access, to order the entries from least to most recently used.
+    tick: u64,
+
+    entries: HashMap<CacheKey, (Bytes, u64)>,
+
+    /// Access tick to key.
+    lru: BTreeMap<u64, CacheKey>,
+
+    /// Last seen metadata of each file, to detect modifications.
+    metas: HashMap<Path, ObjectMeta>,
+}
+
+impl RangeCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            size: 0,
+            tick: 0,
+            entries: HashMap::new(),
+            lru: BTreeMap::new(),
+            metas: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<Bytes> {
+        self.tick += 1;
+        let (bytes, tick) = self.entries.get_mut(key)?;
+        let key = self.lru.remove(tick)?;
+        *tick = self.tick;
+        self.lru.insert(self.tick, key);
+        Some(bytes.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, bytes: Bytes) {
+        if bytes.len() > self.capacity || self.entries.contains_key(&key) {
+            return;
+        }
+        self.tick += 1;
+        self.size += bytes.len();
+        self.lru.insert(self.tick, key.clone());
+        self.entries.insert(key, (bytes, self.tick));
+
+        while self.size > self.capacity {
+            let oldest = *self.lru.keys().next().unwrap();
+            let key = self.lru.remove(&oldest).unwrap();
+            self.remove(&key);
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some((bytes, tick)) = self.entries.remove(key) {
+            self.lru.remove(&tick);
+            self.size -= bytes.len();
+        }
+    }
+
+    /// Remove all the cached ranges of a file.
+    fn invalidate(&mut self, path: &Path) {
+        let keys = self
+            .entries
+            .keys()
+            .filter(|(p, _)| p == path)
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in keys.iter() {
+            self.remove(key);
+        }
+        self.metas.remove(path);
+    }
+
+    /// Record the latest metadata of a file, and invalidate its cached ranges if the file
+    /// was modified since last seen.
+    fn update_meta(&mut self, meta: &ObjectMeta) {
+        if let Some(prev) = self.metas.get(&meta.location) {
+            if prev.size != meta.size || prev.last_modified != meta.last_modified {
+                self.invalidate(&meta.location);
+            }
+        }
+        self.metas.insert(meta.location.clone(), meta.clone());
+    }
+}
+
+/// Wraps an [ObjectStore], and serves repeated `get_range` requests of the same
+/// `(path, range)` from an in-memory LRU cache.
+///
+/// The cached ranges of a file are invalidated when it is written, copied to, or deleted
+/// through this store, or when `head` reports that it was modified.
+#[derive(Debug)]
+pub(crate) struct CachedObjectStore {
+    inner: Arc<dyn ObjectStore>,
+
+    cache: Mutex<RangeCache>,
+}
+
+impl CachedObjectStore {
+    /// Create a cache holding up to `capacity` bytes.
+    pub(crate) fn new(inner: Arc<dyn ObjectStore>, capacity: usize) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(RangeCache::new(capacity)),
+        }
+    }
+
+    fn invalidate(&self, path: &Path) {
+        self.cache.lock().unwrap().invalidate(path);
+    }
+}
+
+impl Display for CachedObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cached({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for CachedObjectStore {
+    async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+        let result = self.inner.put(location, bytes).await;
+        self.invalidate(location);
+        result
+    }
+
+    async fn put_multipart(
+        &self,
+        location: &Path,
+    ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        // The upload completes after this returns, so a read racing with the upload can
+        // still be cached. It is then invalidated by the next `head` on this file.
+        self.invalidate(location);
+        self.inner.put_multipart(location).await
+    }
+
+    async fn abort_multipart(&self, location: &Path, multipart_id: &MultipartId) -> Result<()> {
+        self.inner.abort_multipart(location, multipart_id).await
+    }
+
+    async fn get(&self, location: &Path) -> Result<GetResult> {
+        self.inner.get(location).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        let key = (location.clone(), range.clone());
+        if let Some(bytes) = self.cache.lock().unwrap().get(&key) {
+            return Ok(bytes);
+        }
+        let bytes = self.inner.get_range(location, range).await?;
+        self.cache.lock().unwrap().insert(key, bytes.clone());
+        Ok(bytes)
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        let meta = self.inner.head(location).await?;
+        self.cache.lock().unwrap().update_meta(&meta);
+        Ok(meta)
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let result = self.inner.delete(location).await;
+        self.invalidate(location);
+        result
+    }
+
+    async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+        self.inner.list(prefix).await
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let result = self.inner.copy(from, to).await;
+        self.invalidate(to);
+        result
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let result = self.inner.copy_if_not_exists(from, to).await;
+        self.invalidate(to);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+
+    use crate::datatypes::Schema;
+    use crate::format::Manifest;
+    use crate::io::object_store::testing::MockObjectStore;
+    use crate::io::{read_manifest, write_manifest, ObjectStore as LanceObjectStore};
+
+    #[tokio::test]
+    async fn test_cached_read_manifest() {
+        let mock = Arc::new(MockObjectStore::new());
+        let mut store = LanceObjectStore::memory();
+        store.inner = mock.clone();
+        let store = store.with_cache(1024 * 1024);
+        let path = Path::from("/_latest.manifest");
+
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new("i", DataType::Int64, false)]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        let mut manifest = Manifest::new(&schema, Arc::new(vec![]));
+        let mut writer = store.create(&path).await.unwrap();
+        let pos = write_manifest(&mut writer, &mut manifest, None)
+            .await
+            .unwrap();
+        writer.write_magics(pos).await.unwrap();
+        writer.shutdown().await.unwrap();
+
+        assert_eq!(read_manifest(&store, &path).await.unwrap(), manifest);
+        let range_requests = mock.range_requests();
+        assert!(range_requests > 0);
+
+        assert_eq!(read_manifest(&store, &path).await.unwrap(), manifest);
+        assert_eq!(mock.range_requests(), range_requests);
+
+        // Overwriting the file invalidates the cache.
+        let mut writer = store.create(&path).await.unwrap();
+        let pos = write_manifest(&mut writer, &mut manifest, None)
+            .await
+            .unwrap();
+        writer.write_magics(pos).await.unwrap();
+        writer.shutdown().await.unwrap();
+        assert_eq!(read_manifest(&store, &path).await.unwrap(), manifest);
+        assert!(mock.range_requests() > range_requests);
+    }
+
+    #[tokio::test]
+    async fn test_cache_eviction() {
+        let mock = Arc::new(MockObjectStore::new());
+        let path = Path::from("foo");
+        mock.put(&path, Bytes::from("0123456789")).await.unwrap();
+        let store = CachedObjectStore::new(mock.clone(), 8);
+
+        assert_eq!(store.get_range(&path, 0..4).await.unwrap(), "0123");
+        assert_eq!(store.get_range(&path, 4..8).await.unwrap(), "4567");
+        assert_eq!(store.get_range(&path, 0..4).await.unwrap(), "0123");
+        assert_eq!(mock.range_requests(), 2);
+
+        // Evicts the least recently used range 4..8.
+        assert_eq!(store.get_range(&path, 6..10).await.unwrap(), "6789");
+        assert_eq!(store.get_range(&path, 0..4).await.unwrap(), "0123");
+        assert_eq!(mock.range_requests(), 3);
+        assert_eq!(store.get_range(&path, 4..8).await.unwrap(), "4567");
+        assert_eq!(mock.range_requests(), 4);
+
+        // Larger than the capacity.
+        store.get_range(&path, 0..10).await.unwrap();
+        store.get_range(&path, 0..10).await.unwrap();
+        assert_eq!(mock.range_requests(), 6);
+
+        store.delete(&path).await.unwrap();
+        assert!(store.get_range(&path, 0..4).await.is_err());
+    }
+}
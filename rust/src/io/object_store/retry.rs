@@ -0,0 +1,181 @@
+// Copyright 2023 Lance Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Retry the reads of an [ObjectStore] on transient errors.
+
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, Error, GetResult, ListResult, MultipartId, ObjectMeta, ObjectStore, Result,
+};
+use tokio::io::AsyncWrite;
+
+/// Wraps an [ObjectStore], and retries `get_range`, `get_ranges` and `head` with
+/// exponential backoff when they fail with a retriable error.
+///
+/// Other operations are passed through to the inner store.
+#[derive(Debug)]
+pub(crate) struct RetryObjectStore {
+    inner: Arc<dyn ObjectStore>,
+
+    /// Maximum number of retries after the first attempt.
+    max_retries: usize,
+
+    /// Delay before the first retry, doubled on every following retry.
+    backoff: Duration,
+}
+
+impl RetryObjectStore {
+    pub(crate) fn new(inner: Arc<dyn ObjectStore>, max_retries: usize, backoff: Duration) -> Self {
+        Self {
+            inner,
+            max_retries,
+            backoff,
+        }
+    }
+
+    async fn retry<T, F, Fut>(&self, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Err(e) if attempt < self.max_retries && is_retriable(&e) => {
+                    let delay = self
+                        .backoff
+                        .saturating_mul(2_u32.saturating_pow(attempt as u32));
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Only the errors from the underlying service, i.e., HTTP 5xx or connection reset, are
+/// retriable. Errors such as [Error::NotFound] will not succeed by retrying.
+fn is_retriable(err: &Error) -> bool {
+    matches!(err, Error::Generic { .. })
+}
+
+impl Display for RetryObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Retry({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for RetryObjectStore {
+    async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+        self.inner.put(location, bytes).await
+    }
+
+    async fn put_multipart(
+        &self,
+        location: &Path,
+    ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        self.inner.put_multipart(location).await
+    }
+
+    async fn abort_multipart(&self, location: &Path, multipart_id: &MultipartId) -> Result<()> {
+        self.inner.abort_multipart(location, multipart_id).await
+    }
+
+    async fn get(&self, location: &Path) -> Result<GetResult> {
+        self.inner.get(location).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.retry(move || self.inner.get_range(location, range.clone()))
+            .await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<usize>]) -> Result<Vec<Bytes>> {
+        self.retry(move || self.inner.get_ranges(location, ranges))
+            .await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.retry(move || self.inner.head(location)).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+        self.inner.list(prefix).await
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::io::object_store::testing::MockObjectStore;
+
+    #[tokio::test]
+    async fn test_retry_transient_errors() {
+        let path = Path::from("foo");
+        let mock = Arc::new(MockObjectStore::with_failures(2));
+        mock.put(&path, Bytes::from("0123456789")).await.unwrap();
+
+        let store = RetryObjectStore::new(mock.clone(), 3, Duration::from_millis(1));
+        assert_eq!(
+            store.get_range(&path, 2..5).await.unwrap(),
+            Bytes::from("234")
+        );
+        assert_eq!(mock.range_requests(), 3);
+
+        // Not enough retries.
+        let mock = Arc::new(MockObjectStore::with_failures(2));
+        mock.put(&path, Bytes::from("0123456789")).await.unwrap();
+        let store = RetryObjectStore::new(mock.clone(), 1, Duration::from_millis(1));
+        assert!(store.head(&path).await.is_err());
+        assert_eq!(mock.head_requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_not_found() {
+        let mock = Arc::new(MockObjectStore::new());
+        let store = RetryObjectStore::new(mock.clone(), 3, Duration::from_millis(1));
+        assert!(matches!(
+            store.head(&Path::from("missing")).await,
+            Err(Error::NotFound { .. })
+        ));
+        assert_eq!(mock.head_requests(), 1);
+    }
+}
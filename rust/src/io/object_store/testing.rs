@@ -0,0 +1,150 @@
+// Copyright 2023 Lance Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mock [ObjectStore] for testing the object store decorators.
+
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    memory::InMemory, path::Path, Error, GetResult, ListResult, MultipartId, ObjectMeta,
+    ObjectStore, Result,
+};
+use tokio::io::AsyncWrite;
+
+/// In-memory store which counts the read requests.
+///
+/// The first `failures` calls to `get_range` or `head` fail with a transient error.
+/// Each call to `get_range` or `head` takes at least `delay`.
+#[derive(Debug)]
+pub(crate) struct MockObjectStore {
+    inner: InMemory,
+    failures: usize,
+    delay: Duration,
+    reads: AtomicUsize,
+    range_requests: AtomicUsize,
+    head_requests: AtomicUsize,
+}
+
+impl MockObjectStore {
+    pub(crate) fn new() -> Self {
+        Self::with_failures(0)
+    }
+
+    pub(crate) fn with_failures(failures: usize) -> Self {
+        Self {
+            inner: InMemory::new(),
+            failures,
+            delay: Duration::ZERO,
+            reads: AtomicUsize::new(0),
+            range_requests: AtomicUsize::new(0),
+            head_requests: AtomicUsize::new(0),
+        }
+    }
+
+    /// Delay each `get_range` and `head` call by `delay`.
+    pub(crate) fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Number of `get_range` calls, including the failed ones.
+    pub(crate) fn range_requests(&self) -> usize {
+        self.range_requests.load(Ordering::SeqCst)
+    }
+
+    /// Number of `head` calls, including the failed ones.
+    pub(crate) fn head_requests(&self) -> usize {
+        self.head_requests.load(Ordering::SeqCst)
+    }
+
+    async fn maybe_fail(&self) -> Result<()> {
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        if self.reads.fetch_add(1, Ordering::SeqCst) < self.failures {
+            Err(Error::Generic {
+                store: "mock",
+                source: "connection reset".into(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Display for MockObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MockObjectStore")
+    }
+}
+
+#[async_trait]
+impl ObjectStore for MockObjectStore {
+    async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+        self.inner.put(location, bytes).await
+    }
+
+    async fn put_multipart(
+        &self,
+        location: &Path,
+    ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        self.inner.put_multipart(location).await
+    }
+
+    async fn abort_multipart(&self, location: &Path, multipart_id: &MultipartId) -> Result<()> {
+        self.inner.abort_multipart(location, multipart_id).await
+    }
+
+    async fn get(&self, location: &Path) -> Result<GetResult> {
+        self.inner.get(location).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.range_requests.fetch_add(1, Ordering::SeqCst);
+        self.maybe_fail().await?;
+        self.inner.get_range(location, range).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.head_requests.fetch_add(1, Ordering::SeqCst);
+        self.maybe_fail().await?;
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+        self.inner.list(prefix).await
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
@@ -38,8 +38,12 @@ pub struct ObjectWriter {
     // TODO: wrap writer with a BufWriter.
     #[pin]
     writer: Box<dyn AsyncWrite + Unpin + Send>,
+    path: Path,
     multipart_id: MultipartId,
     cursor: usize,
+
+    /// Running CRC32C of the bytes written since [`ObjectWriter::start_checksum`].
+    checksum: Option<u32>,
 }
 
 impl ObjectWriter {
@@ -49,8 +53,10 @@ impl ObjectWriter {
         Ok(Self {
             store: object_store.clone(),
             writer,
+            path: path.clone(),
             multipart_id,
             cursor: 0,
+            checksum: None,
         })
     }
 
@@ -59,6 +65,17 @@ impl ObjectWriter {
         self.cursor
     }
 
+    /// Start computing the CRC32C of the bytes written from now on.
+    pub fn start_checksum(&mut self) {
+        self.checksum = Some(0);
+    }
+
+    /// Stop computing the checksum, and return the CRC32C of the bytes written since
+    /// [`ObjectWriter::start_checksum`].
+    pub fn take_checksum(&mut self) -> Option<u32> {
+        self.checksum.take()
+    }
+
     /// Write a protobuf message to the object, and returns the file position of the protobuf.
     pub async fn write_protobuf(&mut self, msg: &impl Message) -> Result<usize> {
         let offset = self.tell();
@@ -99,6 +116,17 @@ impl ObjectWriter {
     pub async fn shutdown(&mut self) -> Result<()> {
         Ok(self.writer.shutdown().await?)
     }
+
+    /// Discard the written data, without creating the object.
+    ///
+    /// The writer must not be used after this call.
+    pub async fn abort(&mut self) -> Result<()> {
+        Ok(self
+            .store
+            .inner
+            .abort_multipart(&self.path, &self.multipart_id)
+            .await?)
+    }
 }
 
 impl AsyncWrite for ObjectWriter {
@@ -110,6 +138,9 @@ impl AsyncWrite for ObjectWriter {
         let mut this = self.project();
         this.writer.as_mut().poll_write(cx, buf).map_ok(|n| {
             *this.cursor += n;
+            if let Some(checksum) = this.checksum.as_mut() {
+                *checksum = crc32c::crc32c_append(*checksum, &buf[..n]);
+            }
             n
         })
     }
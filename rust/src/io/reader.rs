@@ -15,47 +15,80 @@
 //! Lance Data File Reader
 
 // Standard
+use std::collections::{HashMap, HashSet};
 use std::ops::{Range, RangeTo};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use arrow::array::PrimitiveBuilder;
-use arrow::datatypes::{Int32Type, Int64Type};
+use arrow::datatypes::{Float32Type, Int32Type, Int64Type};
 use arrow_arith::arithmetic::subtract_scalar;
-use arrow_array::cast::as_primitive_array;
+use arrow_arith::boolean::is_not_null;
+use arrow_array::cast::{as_boolean_array, as_primitive_array};
 use arrow_array::{
-    ArrayRef, ArrowNativeTypeOp, ArrowNumericType, GenericListArray, NullArray, OffsetSizeTrait,
-    PrimitiveArray, RecordBatch, StructArray, UInt32Array, UInt64Array,
+    make_array, new_empty_array, Array, ArrayRef, ArrowNativeTypeOp, ArrowNumericType,
+    BooleanArray, GenericListArray, Int64Array, NullArray, OffsetSizeTrait, PrimitiveArray,
+    RecordBatch, StructArray, UInt32Array, UInt64Array,
 };
-use arrow_buffer::ArrowNativeType;
-use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+use arrow_buffer::{ArrowNativeType, Buffer, MutableBuffer};
+use arrow_cast::cast::cast;
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema, SchemaRef};
 use arrow_select::concat::{concat, concat_batches};
+use arrow_select::take::take;
+use arrow_select::zip::zip;
 use async_recursion::async_recursion;
 use byteorder::{ByteOrder, LittleEndian};
 use bytes::{Bytes, BytesMut};
-use futures::stream::{self, TryStreamExt};
+use datafusion::scalar::ScalarValue;
+use futures::stream::{self, Stream, TryStreamExt};
 use futures::StreamExt;
 use object_store::path::Path;
 use prost::Message;
+use roaring::RoaringBitmap;
+use tokio_util::sync::CancellationToken;
 
 use super::ReadBatchParams;
 use crate::arrow::*;
-use crate::encodings::{dictionary::DictionaryDecoder, AsyncIndex};
+use crate::dataset::ROW_ID;
+use crate::encodings::{dictionary::DictionaryDecoder, plain::PlainDecoder, AsyncIndex};
 use crate::error::{Error, Result};
 use crate::format::Manifest;
-use crate::format::{pb, Metadata, PageTable};
-use crate::io::object_reader::{read_fixed_stride_array, read_struct, ObjectReader};
+use crate::format::{
+    pb, row_id, BatchOffsets, ColumnStats, Metadata, PageChecksums, PageTable, Statistics,
+};
+use crate::io::object_reader::{
+    read_fixed_stride_array, read_fixed_stride_array_zerocopy, read_struct, ObjectReader,
+};
 use crate::io::{read_metadata_offset, read_struct_from_buf};
 use crate::{
-    datatypes::{Field, Schema},
+    datatypes::{Field, LogicalType, Schema},
     format::PageInfo,
 };
 
 use super::object_store::ObjectStore;
 
+/// Name of the row id column, appended to the batches when row ids are requested.
+pub const ROW_ID_FIELD_NAME: &str = ROW_ID;
+
+/// The row id column, appended to the batches when row ids are requested.
+pub fn row_id_field() -> ArrowField {
+    ArrowField::new(ROW_ID_FIELD_NAME, DataType::UInt64, false)
+}
+
 /// Read Manifest on URI.
 ///
 /// This only reads manifest files. It does not read data files.
 pub async fn read_manifest(object_store: &ObjectStore, path: &Path) -> Result<Manifest> {
+    let buf = read_manifest_bytes(object_store, path).await?;
+    let proto = pb::Manifest::decode(buf)?;
+    Ok(Manifest::from(proto))
+}
+
+/// Read the serialized protobuf bytes of the Manifest on URI, without decoding them.
+///
+/// The length prefix and the footer are trimmed, so the returned bytes can be decoded
+/// directly as a [`pb::Manifest`].
+pub async fn read_manifest_bytes(object_store: &ObjectStore, path: &Path) -> Result<Bytes> {
     let file_size = object_store.inner.head(path).await?.size;
     const PREFETCH_SIZE: usize = 64 * 1024;
     let initial_start = std::cmp::max(file_size as i64 - PREFETCH_SIZE as i64, 0) as usize;
@@ -74,7 +107,15 @@ pub async fn read_manifest(object_store: &ObjectStore, path: &Path) -> Result<Ma
             "Invalid format: magic number does not match".to_string(),
         ));
     }
-    let manifest_pos = LittleEndian::read_i64(&buf[buf.len() - 16..buf.len() - 8]) as usize;
+    let manifest_pos = LittleEndian::read_i64(&buf[buf.len() - 16..buf.len() - 8]);
+    // The manifest starts with its length (4 bytes), and is followed by the 16 bytes tail.
+    if manifest_pos < 0 || manifest_pos as usize + 4 + 16 > file_size {
+        return Err(Error::IO(format!(
+            "Invalid format: manifest position {} is out of bounds of file {} ({} bytes)",
+            manifest_pos, path, file_size
+        )));
+    }
+    let manifest_pos = manifest_pos as usize;
     let manifest_len = file_size - manifest_pos;
 
     let buf: Bytes = if manifest_len <= buf.len() {
@@ -100,6 +141,15 @@ pub async fn read_manifest(object_store: &ObjectStore, path: &Path) -> Result<Ma
     };
 
     let recorded_length = LittleEndian::read_u32(&buf[0..4]) as usize;
+    if recorded_length > buf.len() - 4 - 16 {
+        return Err(Error::IO(format!(
+            "Invalid format: manifest length {} at position {} exceeds the {} bytes left in file {}",
+            recorded_length,
+            manifest_pos,
+            buf.len() - 4 - 16,
+            path
+        )));
+    }
     // Need to trim the magic number at end and message length at beginning
     let buf = buf.slice(4..buf.len() - 16);
 
@@ -111,13 +161,37 @@ pub async fn read_manifest(object_store: &ObjectStore, path: &Path) -> Result<Ma
         )));
     }
 
-    let proto = pb::Manifest::decode(buf)?;
-    Ok(Manifest::from(proto))
+    Ok(buf)
+}
+
+/// Options of one read, see [`FileReader::read_batch_opts()`].
+///
+/// They override the settings of the [FileReader] for that read only.
+#[derive(Debug, Clone)]
+pub struct ReadOptions {
+    /// The schema to read, or the schema of the reader if `None`.
+    pub projection: Option<Schema>,
+
+    /// Append the row id column, `_rowid`.
+    pub with_row_id: bool,
+
+    /// Number of top-level columns decoded concurrently.
+    pub io_parallelism: usize,
+
+    /// Values to replace the nulls of the top-level fields with, by field id, in addition
+    /// to the ones set by [`FileReader::with_null_fill()`].
+    pub null_fill: HashMap<i32, ScalarValue>,
 }
 
-/// Compute row id from `fragment_id` and the `offset` of the row in the fragment.
-fn compute_row_id(fragment_id: u64, offset: i32) -> u64 {
-    (fragment_id << 32) + offset as u64
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            projection: None,
+            with_row_id: false,
+            io_parallelism: num_cpus::get(),
+            null_fill: HashMap::new(),
+        }
+    }
 }
 
 /// Lance File Reader.
@@ -136,6 +210,34 @@ pub struct FileReader {
     /// If set true, returns the row ID from the dataset alongside with the
     /// actual data.
     with_row_id: bool,
+
+    /// Number of bytes read from the end of the file to load the metadata.
+    tail_read_size: usize,
+
+    /// Checksums of the pages, if the file stores them.
+    page_checksums: Option<PageChecksums>,
+
+    /// Column statistics, if the file stores them.
+    statistics: Option<Statistics>,
+
+    /// Verify the page checksums when reading.
+    verify_checksums: bool,
+
+    /// Arrow schema and pages of the last projection read by
+    /// [`FileReader::read_batch()`].
+    projection_cache: RwLock<Option<Arc<ProjectionCache>>>,
+
+    /// Values to replace the nulls of the fields with, by field id.
+    null_fills: HashMap<i32, ScalarValue>,
+
+    /// Number of top-level columns decoded concurrently when reading a batch.
+    read_parallelism: usize,
+
+    /// Ids of the string fields read as dictionaries.
+    dict_encode_fields: HashSet<i32>,
+
+    /// Aborts the reads once cancelled, checked before reading each batch.
+    cancellation: Option<CancellationToken>,
 }
 
 impl std::fmt::Debug for FileReader {
@@ -158,12 +260,48 @@ impl FileReader {
         manifest: Option<&Manifest>,
     ) -> Result<FileReader> {
         let object_reader = object_store.open(path).await?;
+        Self::open(
+            object_reader.into(),
+            fragment_id,
+            manifest.map(|m| &m.schema),
+            object_store.block_size(),
+        )
+        .await
+    }
+
+    /// Open a file reader from an already opened [ObjectReader], i.e., a cached one.
+    ///
+    /// If `manifest` is given, its schema is used instead of reading the one in the file.
+    pub async fn try_new_from_object_reader(
+        object_reader: Arc<dyn ObjectReader>,
+        fragment_id: u64,
+        manifest: Option<&Manifest>,
+    ) -> Result<FileReader> {
+        let tail_read_size = object_reader.block_size();
+        Self::open(
+            object_reader,
+            fragment_id,
+            manifest.map(|m| &m.schema),
+            tail_read_size,
+        )
+        .await
+    }
 
+    /// Load the metadata and the page table of an opened file.
+    ///
+    /// It reads the last `tail_read_size` bytes of the file in one request, and only reads
+    /// the metadata again if it does not fit in those bytes.
+    async fn open(
+        object_reader: Arc<dyn ObjectReader>,
+        fragment_id: u64,
+        schema: Option<&Schema>,
+        tail_read_size: usize,
+    ) -> Result<Self> {
         let file_size = object_reader.size().await?;
-        let begin = if file_size < object_store.block_size() {
+        let begin = if file_size < tail_read_size {
             0
         } else {
-            file_size - object_store.block_size()
+            file_size - tail_read_size
         };
         let tail_bytes = object_reader.get_range(begin..file_size).await?;
         let metadata_pos = read_metadata_offset(&tail_bytes)?;
@@ -176,8 +314,8 @@ impl FileReader {
             read_struct_from_buf(&tail_bytes.slice(offset..))?
         };
 
-        let (projection, num_columns) = if let Some(m) = manifest {
-            (m.schema.clone(), m.schema.max_field_id().unwrap() + 1)
+        let (projection, num_columns) = if let Some(schema) = schema {
+            (schema.clone(), schema.max_field_id().unwrap() + 1)
         } else {
             let mut m: Manifest =
                 read_struct(object_reader.as_ref(), metadata.manifest_position.unwrap()).await?;
@@ -192,14 +330,31 @@ impl FileReader {
             metadata.num_batches() as i32,
         )
         .await?;
+        let page_checksums = match metadata.page_checksums_position {
+            Some(pos) => Some(read_struct(object_reader.as_ref(), pos).await?),
+            None => None,
+        };
+        let statistics = match metadata.statistics_position {
+            Some(pos) => Some(read_struct(object_reader.as_ref(), pos).await?),
+            None => None,
+        };
 
         Ok(Self {
-            object_reader: object_reader.into(),
+            object_reader,
             metadata,
             projection: Some(projection),
             page_table,
             fragment_id,
             with_row_id: false,
+            tail_read_size,
+            page_checksums,
+            statistics,
+            verify_checksums: false,
+            projection_cache: RwLock::new(None),
+            null_fills: HashMap::new(),
+            read_parallelism: num_cpus::get(),
+            dict_encode_fields: HashSet::new(),
+            cancellation: None,
         })
     }
 
@@ -208,6 +363,210 @@ impl FileReader {
         Self::try_new_with_fragment(object_store, path, 0, None).await
     }
 
+    /// Open many data files of a dataset concurrently, in the order of `paths`.
+    ///
+    /// The schema of `manifest` is shared by all the readers, so the manifests stored in
+    /// the files are not read. Like [`FileReader::try_new()`], the readers use fragment id 0.
+    pub async fn open_many(
+        object_store: &ObjectStore,
+        paths: &[Path],
+        manifest: Arc<Manifest>,
+    ) -> Result<Vec<FileReader>> {
+        stream::iter(paths)
+            .map(|path| {
+                let manifest = manifest.clone();
+                async move {
+                    Self::try_new_with_fragment(object_store, path, 0, Some(manifest.as_ref()))
+                        .await
+                }
+            })
+            .buffered(num_cpus::get())
+            .try_collect()
+            .await
+    }
+
+    /// Number of bytes read from the end of the file to load the metadata.
+    ///
+    /// It is the block size of the [ObjectStore] the file was opened from, unless it
+    /// is overridden by [`FileReader::with_tail_read_size()`].
+    pub fn block_size(&self) -> usize {
+        self.tail_read_size
+    }
+
+    /// Override the number of bytes read from the end of the file to load the metadata.
+    ///
+    /// Files with large metadata can be opened in one request with a larger size.
+    /// It only takes effect on the subsequent [`FileReader::reopen()`].
+    pub fn with_tail_read_size(mut self, bytes: usize) -> Self {
+        self.tail_read_size = bytes;
+        self
+    }
+
+    /// Open the same file again, reloading its metadata.
+    pub async fn reopen(&self) -> Result<Self> {
+        let mut reader = Self::open(
+            self.object_reader.clone(),
+            self.fragment_id,
+            self.projection.as_ref(),
+            self.tail_read_size,
+        )
+        .await?;
+        reader.with_row_id(self.with_row_id);
+        reader.verify_checksums = self.verify_checksums;
+        reader.read_parallelism = self.read_parallelism;
+        reader.null_fills = self.null_fills.clone();
+        reader.dict_encode_fields = self.dict_encode_fields.clone();
+        reader.cancellation = self.cancellation.clone();
+        Ok(reader)
+    }
+
+    /// Verify the CRC32C checksum of each page before decoding it.
+    ///
+    /// Reading a page whose checksum does not match returns [`Error::IO`]. Pages without a
+    /// stored checksum, i.e., written without [`crate::io::FileWriter::with_page_checksums`],
+    /// are not verified.
+    pub fn with_verify_checksums(mut self, verify: bool) -> Self {
+        self.verify_checksums = verify;
+        self
+    }
+
+    /// Set the number of top-level columns decoded concurrently by
+    /// [`FileReader::read_batch()`]. Defaults to the number of CPUs.
+    ///
+    /// The children of a struct or list column are read one after another, so a batch
+    /// has at most `parallelism` pending column reads. `1` reads the columns sequentially.
+    pub fn with_read_parallelism(mut self, parallelism: usize) -> Self {
+        self.read_parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Replace the nulls of field `field_id` with `value` when reading, so the field is
+    /// read without nulls.
+    ///
+    /// `value` must be a non-null scalar of the same type as the field.
+    pub fn with_null_fill(mut self, field_id: i32, value: ScalarValue) -> Result<Self> {
+        let field = self.field_by_id(field_id)?;
+        if value.is_null() || value.get_datatype() != field.data_type() {
+            return Err(Error::Schema(format!(
+                "Can not fill the nulls of field {} ({}) with {:?}",
+                field.name,
+                field.data_type(),
+                value
+            )));
+        }
+        self.null_fills.insert(field_id, value);
+        Ok(self)
+    }
+
+    /// Abort the reads of this reader once `token` is cancelled.
+    ///
+    /// The token is checked before reading each batch, so range reads,
+    /// [`FileReader::take()`] and the scan streams stop between batches with
+    /// `Error::IO("read cancelled")`. A cancelled scan stream ends after that error.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Returns an error if the reads have been cancelled.
+    fn check_cancelled(&self) -> Result<()> {
+        match &self.cancellation {
+            Some(token) if token.is_cancelled() => Err(Error::IO("read cancelled".to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// End `batches` after the error of a cancelled read, instead of yielding one error
+    /// for each remaining batch.
+    fn cancellable<'a, S>(&'a self, batches: S) -> impl Stream<Item = Result<RecordBatch>> + 'a
+    where
+        S: Stream<Item = Result<RecordBatch>> + 'a,
+    {
+        batches.scan(false, move |cancelled, batch| {
+            if *cancelled {
+                return futures::future::ready(None);
+            }
+            *cancelled = batch.is_err() && self.check_cancelled().is_err();
+            futures::future::ready(Some(batch))
+        })
+    }
+
+    /// Read the top-level string field `field_id` dictionary-encoded, i.e., as
+    /// `Dictionary(Int32, Utf8)`, or `Dictionary(Int32, LargeUtf8)` for a `LargeUtf8` field.
+    ///
+    /// This saves memory for low-cardinality columns that are stored as plain strings.
+    /// The dictionary is built for each batch read.
+    pub fn with_dict_encode(mut self, field_id: i32) -> Result<Self> {
+        let field = self.field_by_id(field_id)?;
+        if !matches!(field.data_type(), DataType::Utf8 | DataType::LargeUtf8) {
+            return Err(Error::Schema(format!(
+                "Can not dictionary encode field {} ({}), only Utf8 and LargeUtf8 are supported",
+                field.name,
+                field.data_type()
+            )));
+        }
+        if !self.schema().fields.iter().any(|f| f.id == field_id) {
+            return Err(Error::Schema(format!(
+                "Can not dictionary encode nested field {}",
+                field.name
+            )));
+        }
+        self.dict_encode_fields.insert(field_id);
+        *self.projection_cache.get_mut().unwrap() = None;
+        Ok(self)
+    }
+
+    /// Use `projection` as the schema of this reader, so it can be read with
+    /// [`FileReader::read_batch_projected()`], [`FileReader::read_range_projected()`] and
+    /// [`FileReader::take_projected()`] without passing the projection on each call.
+    ///
+    /// `projection` must be a projection of [`FileReader::schema()`] that keeps its field
+    /// ids, i.e., from [`Schema::project`]. The fields out of the projection are no longer
+    /// accessible from this reader.
+    pub fn project(mut self, projection: Schema) -> Result<Self> {
+        let resolved = self.schema().project_by_schema(&projection)?;
+        if resolved.field_ids() != projection.field_ids() {
+            return Err(Error::Schema(format!(
+                "FileReader::project: the field ids of the projection do not match the file: {projection}"
+            )));
+        }
+        self.projection = Some(projection);
+        Ok(self)
+    }
+
+    /// Consume the reader, returning the schema, the metadata, the page table, the
+    /// page checksums and the statistics of the file.
+    pub(crate) fn into_parts(
+        self,
+    ) -> (
+        Schema,
+        Metadata,
+        PageTable,
+        Option<PageChecksums>,
+        Option<Statistics>,
+    ) {
+        (
+            self.projection.unwrap(),
+            self.metadata,
+            self.page_table,
+            self.page_checksums,
+            self.statistics,
+        )
+    }
+
+    /// Statistics of the column `field_id` in batch `batch_id`.
+    ///
+    /// Returns `None` if the file does not store statistics for the column, i.e., for
+    /// nested, dictionary and other unsupported types (see [`ColumnStats::is_supported`]).
+    pub fn column_statistics(&self, field_id: i32, batch_id: i32) -> Option<ColumnStats> {
+        let field = self.field_by_id(field_id).ok()?;
+        self.statistics
+            .as_ref()?
+            .get(field_id, batch_id, &field.data_type())
+            .ok()
+            .flatten()
+    }
+
     /// Instruct the FileReader to return meta row id column.
     pub(crate) fn with_row_id(&mut self, v: bool) -> &mut Self {
         self.with_row_id = v;
@@ -219,10 +578,102 @@ impl FileReader {
         self.projection.as_ref().unwrap()
     }
 
+    /// Arrow schema of the RecordBatch returned by [`FileReader::read_batch()`].
+    ///
+    /// Unlike [`FileReader::schema()`], it includes the `_rowid` column when row ids
+    /// are requested.
+    pub fn output_schema(&self) -> ArrowSchema {
+        self.projection_output_schema(self.schema())
+    }
+
+    /// Arrow schema and pages of `projection`, reusing the previous ones if
+    /// `projection` has the same fields, so reading many batches with one projection
+    /// resolves it only once.
+    fn projection_cache(&self, projection: &Schema) -> Arc<ProjectionCache> {
+        if let Some(cache) = self.projection_cache.read().unwrap().as_ref() {
+            if cache.matches(projection) {
+                return cache.clone();
+            }
+        }
+        let mut key = vec![];
+        projection.visit_fields(|f| key.push((f.id, f.logical_type().clone())));
+        let num_batches = self.num_batches() as i32;
+        let pages = projection
+            .fields
+            .iter()
+            .map(|f| {
+                (0..num_batches)
+                    .map(|batch_id| self.page_table.get(f.id, batch_id).cloned())
+                    .collect()
+            })
+            .collect();
+        let cache = Arc::new(ProjectionCache {
+            key,
+            schema: Arc::new(self.to_output_arrow(projection)),
+            pages,
+        });
+        *self.projection_cache.write().unwrap() = Some(cache.clone());
+        cache
+    }
+
+    /// Arrow schema of `projection`, with the fields set by
+    /// [`FileReader::with_dict_encode()`] as dictionaries.
+    fn to_output_arrow(&self, projection: &Schema) -> ArrowSchema {
+        let schema = projection.to_arrow();
+        if self.dict_encode_fields.is_empty() {
+            return schema;
+        }
+        let fields = projection
+            .fields
+            .iter()
+            .zip(schema.fields().iter())
+            .map(|(field, arrow_field)| {
+                if self.dict_encode_fields.contains(&field.id) {
+                    ArrowField::new(
+                        arrow_field.name(),
+                        dict_encoded_type(arrow_field.data_type()),
+                        arrow_field.is_nullable(),
+                    )
+                    .with_metadata(arrow_field.metadata().clone())
+                } else {
+                    arrow_field.as_ref().clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        ArrowSchema::new_with_metadata(fields, schema.metadata().clone())
+    }
+
+    /// Arrow schema of the RecordBatch read with `projection`.
+    fn projection_output_schema(&self, projection: &Schema) -> ArrowSchema {
+        let schema = self.to_output_arrow(projection);
+        if self.with_row_id {
+            let mut fields: Vec<ArrowField> =
+                schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+            fields.push(row_id_field());
+            ArrowSchema::new_with_metadata(fields, schema.metadata().clone())
+        } else {
+            schema
+        }
+    }
+
     pub fn num_batches(&self) -> usize {
         self.metadata.num_batches()
     }
 
+    /// Map a range of row offsets in this file to the batches that contain them.
+    ///
+    /// It returns a list of `(batch_id, in_batch_range)` tuples, in batch order.
+    pub fn range_to_batches(&self, range: Range<usize>) -> Result<Vec<(i32, Range<usize>)>> {
+        self.metadata.range_to_batches(range)
+    }
+
+    /// Group row offsets in this file by the batches that contain them.
+    ///
+    /// The returned offsets are relative to the start of each batch, sorted.
+    pub fn group_indices_to_batches(&self, indices: &[u32]) -> Vec<BatchOffsets> {
+        self.metadata.group_indices_to_batches(indices)
+    }
+
     /// Get the number of rows in this batch
     pub fn num_rows_in_batch(&self, batch_id: i32) -> usize {
         self.metadata.get_batch_length(batch_id).unwrap_or_default() as usize
@@ -237,16 +688,203 @@ impl FileReader {
         self.metadata.is_empty()
     }
 
+    /// Count the number of rows in this file, same as [`FileReader::len()`].
+    pub fn num_rows(&self) -> usize {
+        self.len()
+    }
+
+    /// Number of rows in each batch, read from the metadata only.
+    pub fn batch_lengths(&self) -> Vec<usize> {
+        (0..self.num_batches() as i32)
+            .map(|batch_id| self.num_rows_in_batch(batch_id))
+            .collect()
+    }
+
+    /// The `(position, length)` of the page of a column in one batch.
+    ///
+    /// `position` is the offset of the page in the file, and `length` is the number
+    /// of values in the page.
+    pub fn page_info(&self, field_id: i32, batch_id: i32) -> Option<(usize, usize)> {
+        self.page_table
+            .get(field_id, batch_id)
+            .map(|page_info| (page_info.position, page_info.length))
+    }
+
+    /// Number of bytes of the data of the fields of [`FileReader::schema()`] in all the
+    /// batches.
+    ///
+    /// It counts the bytes of the values, i.e., the values of a binary column without its
+    /// positions, and the offsets and the values of a list column. The positions of the
+    /// first and the last value of each binary page are read to size it.
+    pub async fn total_data_bytes(&self) -> Result<usize> {
+        let mut total = 0;
+        for batch_id in 0..self.num_batches() as i32 {
+            for field in self.schema().fields.iter() {
+                total += page_data_bytes(self, field, batch_id).await?;
+            }
+        }
+        Ok(total)
+    }
+
     /// Read a batch of data from the file.
     ///
-    /// The schema of the returned [RecordBatch] is set by [`FileReader::schema()`].
-    pub(crate) async fn read_batch(
+    /// The schema of the returned [RecordBatch] is set by `projection`. Reading many
+    /// batches with the same projection reuses its Arrow schema and pages.
+    pub async fn read_batch(
         &self,
         batch_id: i32,
         params: impl Into<ReadBatchParams>,
         projection: &Schema,
     ) -> Result<RecordBatch> {
-        read_batch(self, &params.into(), projection, batch_id, self.with_row_id).await
+        read_batch(
+            self,
+            &params.into(),
+            projection,
+            batch_id,
+            self.with_row_id,
+            self.read_parallelism,
+            None,
+        )
+        .await
+    }
+
+    /// Read a batch of data from the file, configured by `options` instead of the
+    /// settings of this reader.
+    pub async fn read_batch_opts(
+        &self,
+        batch_id: i32,
+        params: impl Into<ReadBatchParams>,
+        options: &ReadOptions,
+    ) -> Result<RecordBatch> {
+        read_batch(
+            self,
+            &params.into(),
+            options.projection.as_ref().unwrap_or(self.schema()),
+            batch_id,
+            options.with_row_id,
+            options.io_parallelism.max(1),
+            Some(&options.null_fill),
+        )
+        .await
+    }
+
+    /// Read a batch like [`FileReader::read_batch()`], along with the null count of each
+    /// column.
+    ///
+    /// The null counts follow the fields of `projection` in order. A struct field is
+    /// followed by the null counts of its direct children. The row id column is not counted.
+    pub async fn read_batch_with_stats(
+        &self,
+        batch_id: i32,
+        params: impl Into<ReadBatchParams>,
+        projection: &Schema,
+    ) -> Result<(RecordBatch, Vec<usize>)> {
+        let batch = self.read_batch(batch_id, params, projection).await?;
+        let mut null_counts = vec![];
+        for column in batch.columns().iter().take(projection.fields.len()) {
+            null_counts.push(column.null_count());
+            if let Some(struct_arr) = column.as_any().downcast_ref::<StructArray>() {
+                null_counts.extend(struct_arr.columns().iter().map(|c| c.null_count()));
+            }
+        }
+        Ok((batch, null_counts))
+    }
+
+    /// Count the values of field `field_id` for which `predicate` is true.
+    ///
+    /// Only the column of `field_id` is read, one batch at a time. A null in the mask
+    /// returned by `predicate` is not counted.
+    pub async fn count_matching(
+        &self,
+        field_id: i32,
+        predicate: impl Fn(&ArrayRef) -> BooleanArray,
+    ) -> Result<usize> {
+        let field = self.field_by_id(field_id)?;
+        let mut count = 0;
+        for batch_id in 0..self.num_batches() as i32 {
+            let arr = read_array(self, field, batch_id, &ReadBatchParams::RangeFull).await?;
+            count += predicate(&arr).true_count();
+        }
+        Ok(count)
+    }
+
+    /// Read all the batches, skipping the rows at `excluded_offsets`, i.e., the deleted rows.
+    ///
+    /// The offsets are the row offsets within the file. Only the remaining rows of each
+    /// batch are read, and the row ids, if requested, are those of the remaining rows.
+    /// A batch whose rows are all excluded is returned empty, without being read.
+    pub fn scan_excluding<'a>(
+        &'a self,
+        projection: &'a Schema,
+        excluded_offsets: &'a RoaringBitmap,
+    ) -> impl Stream<Item = Result<RecordBatch>> + 'a {
+        let batches = stream::iter(0..self.num_batches() as i32).then(move |batch_id| async move {
+            let offset = self.metadata.get_offset(batch_id).unwrap_or_default() as u32;
+            let length = self.num_rows_in_batch(batch_id) as u32;
+            let num_excluded = excluded_offsets.range_cardinality(offset..offset + length);
+            if num_excluded == 0 {
+                self.read_batch(batch_id, .., projection).await
+            } else if num_excluded == length as u64 {
+                Ok(RecordBatch::new_empty(Arc::new(
+                    self.projection_output_schema(projection),
+                )))
+            } else {
+                let remaining = UInt32Array::from_iter_values(
+                    (0..length).filter(|o| !excluded_offsets.contains(offset + o)),
+                );
+                self.read_batch(batch_id, remaining, projection).await
+            }
+        });
+        self.cancellable(batches)
+    }
+
+    /// Read the batches that may contain values of `column` within `range`.
+    ///
+    /// Batches whose min/max statistics of `column` are disjoint from `range`, or whose
+    /// values are all null, are skipped without being read. The returned batches still
+    /// contain rows outside of `range`, so the caller must filter them.
+    ///
+    /// The bounds of `range` should have the same type as `column`. Batches without
+    /// statistics, or whose statistics can not be compared with `range`, are read.
+    pub fn scan_filtered<'a>(
+        &'a self,
+        projection: &'a Schema,
+        column: &str,
+        range: Range<ScalarValue>,
+    ) -> impl Stream<Item = Result<RecordBatch>> + 'a {
+        let candidates: Vec<Result<i32>> = match self.schema().field(column) {
+            Some(field) => (0..self.num_batches() as i32)
+                .filter(|batch_id| {
+                    self.column_statistics(field.id, *batch_id)
+                        .map_or(true, |stats| stats_may_overlap(&stats, &range))
+                })
+                .map(Ok)
+                .collect(),
+            None => vec![Err(Error::Schema(format!(
+                "FileReader::scan_filtered: column {column} does not exist"
+            )))],
+        };
+        let batches = stream::iter(candidates)
+            .then(move |batch_id| async move { self.read_batch(batch_id?, .., projection).await });
+        self.cancellable(batches)
+    }
+
+    /// Read the whole file in batches of `target_rows` rows, regardless of the batches
+    /// it was written with. The last batch may be smaller.
+    ///
+    /// Consecutive small batches are read together and concatenated, which amortizes
+    /// the overhead of reading many tiny batches.
+    pub fn scan_coalesced<'a>(
+        &'a self,
+        projection: &'a Schema,
+        target_rows: usize,
+    ) -> impl Stream<Item = Result<RecordBatch>> + 'a {
+        let num_rows = self.len();
+        let batches = stream::iter((0..num_rows).step_by(target_rows.max(1))).then(move |start| {
+            let end = std::cmp::min(start + target_rows.max(1), num_rows);
+            self.read_range(start..end, projection)
+        });
+        self.cancellable(batches)
     }
 
     /// Read a range of records into one batch.
@@ -267,17 +905,45 @@ impl FileReader {
                 .buffered(num_cpus::get())
                 .try_collect::<Vec<_>>()
                 .await?;
-        if batches.len() == 1 {
-            return Ok(batches[0].clone());
+        match batches.len() {
+            0 => Ok(RecordBatch::new_empty(Arc::new(
+                self.projection_output_schema(projection),
+            ))),
+            1 => Ok(batches[0].clone()),
+            _ => {
+                let schema = batches[0].schema();
+                Ok(concat_batches(&schema, &batches)?)
+            }
         }
-        let schema = batches[0].schema();
-        Ok(concat_batches(&schema, &batches)?)
+    }
+
+    /// Read a batch with the projection of this reader, see [`FileReader::project()`].
+    pub async fn read_batch_projected(
+        &self,
+        batch_id: i32,
+        params: impl Into<ReadBatchParams>,
+    ) -> Result<RecordBatch> {
+        self.read_batch(batch_id, params, self.schema()).await
+    }
+
+    /// Read a range of records with the projection of this reader, see [`FileReader::project()`].
+    pub async fn read_range_projected(&self, range: Range<usize>) -> Result<RecordBatch> {
+        self.read_range(range, self.schema()).await
+    }
+
+    /// Take records with the projection of this reader, see [`FileReader::project()`].
+    pub async fn take_projected(&self, indices: &[u32]) -> Result<RecordBatch> {
+        self.take(indices, self.schema()).await
     }
 
     /// Take by records by indices within the file.
     ///
     /// The indices must be sorted.
     pub async fn take(&self, indices: &[u32], projection: &Schema) -> Result<RecordBatch> {
+        let schema = Arc::new(self.projection_output_schema(projection));
+        if indices.is_empty() {
+            return Ok(RecordBatch::new_empty(schema));
+        }
         let indices_in_batches = self.metadata.group_indices_to_batches(indices);
         let batches = stream::iter(indices_in_batches)
             .map(|batch| async move {
@@ -287,236 +953,1115 @@ impl FileReader {
             .buffered(num_cpus::get() * 4)
             .try_collect::<Vec<_>>()
             .await?;
-        let schema = Arc::new(ArrowSchema::from(projection));
         Ok(concat_batches(&schema, &batches)?)
     }
-}
 
-/// Read a batch.
-async fn read_batch(
-    reader: &FileReader,
-    params: &ReadBatchParams,
-    schema: &Schema,
-    batch_id: i32,
-    with_row_id: bool,
-) -> Result<RecordBatch> {
-    let arrs = stream::iter(&schema.fields)
-        .then(|f| async move { read_array(reader, f, batch_id, params).await })
-        .try_collect::<Vec<_>>()
-        .await?;
-    let mut batch = RecordBatch::try_new(Arc::new(schema.into()), arrs)?;
-    if with_row_id {
-        let ids_in_batch: Vec<i32> = match params {
-            ReadBatchParams::Indices(indices) => {
-                indices.values().iter().map(|v| *v as i32).collect()
-            }
-            ReadBatchParams::Range(r) => r.clone().map(|v| v as i32).collect(),
-            ReadBatchParams::RangeFull => (0..batch.num_rows() as i32).collect(),
-            ReadBatchParams::RangeTo(r) => (0..r.end).map(|v| v as i32).collect(),
-            ReadBatchParams::RangeFrom(r) => (r.start..r.start + batch.num_rows())
-                .map(|v| v as i32)
-                .collect(),
-        };
-        let batch_offset = reader
-            .metadata
-            .get_offset(batch_id)
-            .ok_or_else(|| Error::IO(format!("batch {batch_id} does not exist")))?;
-        let row_id_arr = Arc::new(UInt64Array::from_iter_values(
-            ids_in_batch
-                .iter()
-                .map(|o| compute_row_id(reader.fragment_id, *o + batch_offset)),
-        ));
-        batch = batch.try_with_column(
-            ArrowField::new("_rowid", DataType::UInt64, false),
-            row_id_arr,
-        )?;
+    /// Take records by indices within the file, as a stream of batches of at most
+    /// `chunk_rows` rows.
+    ///
+    /// Unlike [`FileReader::take()`], the indices do not need to be sorted: the rows are
+    /// returned in the order of `indices`. Each chunk is read and yielded on its own, so
+    /// a large take does not build one big batch.
+    pub fn take_stream<'a>(
+        &'a self,
+        indices: &'a [u32],
+        projection: &'a Schema,
+        chunk_rows: usize,
+    ) -> impl Stream<Item = Result<RecordBatch>> + 'a {
+        let batches =
+            stream::iter(indices.chunks(chunk_rows.max(1))).then(move |chunk| async move {
+                if chunk.windows(2).all(|w| w[0] <= w[1]) {
+                    return self.take(chunk, projection).await;
+                }
+                // Read the rows in the order of the file, then restore the order of `chunk`.
+                let mut order = (0..chunk.len()).collect::<Vec<_>>();
+                order.sort_by_key(|&i| chunk[i]);
+                let sorted = order.iter().map(|&i| chunk[i]).collect::<Vec<_>>();
+                let batch = self.take(&sorted, projection).await?;
+
+                let mut positions = vec![0_u32; chunk.len()];
+                for (pos, &i) in order.iter().enumerate() {
+                    positions[i] = pos as u32;
+                }
+                let positions = UInt32Array::from(positions);
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .map(|c| take(c.as_ref(), &positions, None))
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(RecordBatch::try_new(batch.schema(), columns)?)
+            });
+        self.cancellable(batches)
     }
-    Ok(batch)
-}
 
-#[async_recursion]
-async fn read_array(
-    reader: &FileReader,
-    field: &Field,
-    batch_id: i32,
-    params: &ReadBatchParams,
-) -> Result<ArrayRef> {
-    let data_type = field.data_type();
+    /// Look up a field in the projection by its field ID.
+    fn field_by_id(&self, field_id: i32) -> Result<&Field> {
+        self.schema()
+            .field_by_id(field_id)
+            .ok_or_else(|| Error::Schema(format!("Field id {field_id} does not exist")))
+    }
 
-    use DataType::*;
+    /// Number of pages of field `field_id` in the page table, one per batch.
+    ///
+    /// Returns 0 if the field is not in the file.
+    pub fn num_pages(&self, field_id: i32) -> usize {
+        self.page_table.field_pages(field_id).count()
+    }
 
-    if data_type.is_fixed_stride() {
-        _read_fixed_stride_array(reader, field, batch_id, params).await
-    } else {
-        match data_type {
-            Null => read_null_array(reader, field, batch_id, params),
-            Utf8 | LargeUtf8 | Binary | LargeBinary => {
-                read_binary_array(reader, field, batch_id, params).await
-            }
-            Struct(_) => read_struct_array(reader, field, batch_id, params).await,
-            Dictionary(_, _) => read_dictionary_array(reader, field, batch_id, params).await,
-            List(_) => read_list_array::<Int32Type>(reader, field, batch_id, params).await,
-            LargeList(_) => read_list_array::<Int64Type>(reader, field, batch_id, params).await,
-            _ => {
-                unimplemented!("{}", format!("No support for {data_type} yet"));
+    /// File positions of the pages of field `field_id`, ordered by batch.
+    pub fn page_positions(&self, field_id: i32) -> Vec<usize> {
+        self.page_table
+            .field_pages(field_id)
+            .map(|page| page.position)
+            .collect()
+    }
+
+    /// Read field `field_id` from every batch into one array.
+    ///
+    /// `params_per_batch` is applied to each batch, i.e., `..10` reads the first 10 rows of
+    /// every batch. Returns [`Error::OutOfRange`] if it selects rows beyond the end of a
+    /// batch. It suits small columns, such as ids.
+    pub async fn read_column(
+        &self,
+        field_id: i32,
+        params_per_batch: ReadBatchParams,
+    ) -> Result<ArrayRef> {
+        let field = self.field_by_id(field_id)?;
+        if !matches!(params_per_batch, ReadBatchParams::RangeFull) {
+            for batch_id in 0..self.num_batches() as i32 {
+                let batch_length = self.num_rows_in_batch(batch_id);
+                let range = params_per_batch.covering_range(batch_length);
+                if range.end > batch_length {
+                    return Err(Error::OutOfRange(format!(
+                        "Rows {:?} are out of bounds {} in batch {}",
+                        range, batch_length, batch_id
+                    )));
+                }
             }
         }
+
+        let arrs = stream::iter(0..self.num_batches() as i32)
+            .map(|batch_id| read_array(self, field, batch_id, &params_per_batch))
+            .buffered(self.read_parallelism)
+            .try_collect::<Vec<_>>()
+            .await?;
+        if arrs.is_empty() {
+            return Ok(new_empty_array(&field.data_type()));
+        }
+        let arrs = arrs.iter().map(|a| a.as_ref()).collect::<Vec<_>>();
+        Ok(concat(&arrs)?)
     }
-}
 
-fn get_page_info<'a>(
-    page_table: &'a PageTable,
-    field: &'a Field,
-    batch_id: i32,
-) -> Result<&'a PageInfo> {
-    page_table.get(field.id, batch_id).ok_or_else(|| {
-        Error::IO(format!(
-            "No page info found for field: {}, field_id={} batch={}",
-            field.name, field.id, batch_id
-        ))
-    })
-}
+    /// Read one column of a batch by its name, which can be a dotted path to a nested
+    /// field, i.e., `"parent.child"`.
+    ///
+    /// Only the pages of that field, and of its children, are read.
+    pub async fn read_column_by_name(
+        &self,
+        name: &str,
+        batch_id: i32,
+        params: impl Into<ReadBatchParams>,
+    ) -> Result<ArrayRef> {
+        let field = self
+            .schema()
+            .field(name)
+            .ok_or_else(|| Error::Schema(format!("Column {name} does not exist")))?;
+        read_array(self, field, batch_id, &params.into()).await
+    }
 
-/// Read primitive array for batch `batch_idx`.
-async fn _read_fixed_stride_array(
-    reader: &FileReader,
-    field: &Field,
-    batch_id: i32,
-    params: &ReadBatchParams,
-) -> Result<ArrayRef> {
-    let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+    /// Read several columns of a batch, each with its own [ReadBatchParams].
+    ///
+    /// Each `(field_id, params)` pair in `specs` is read concurrently, and the arrays are
+    /// returned in the same order. As the params can select different rows, the arrays
+    /// can have different lengths.
+    ///
+    /// The indices do not need to be sorted: the rows are returned in the order of the
+    /// indices.
+    pub async fn read_columns(
+        &self,
+        batch_id: i32,
+        specs: &[(i32, ReadBatchParams)],
+    ) -> Result<Vec<ArrayRef>> {
+        stream::iter(specs)
+            .map(|(field_id, params)| async move {
+                let field = self.field_by_id(*field_id)?;
+                let indices = match params {
+                    ReadBatchParams::Indices(indices)
+                        if !indices.values().windows(2).all(|w| w[0] <= w[1]) =>
+                    {
+                        indices
+                    }
+                    _ => return read_array(self, field, batch_id, params).await,
+                };
+                // The decoders take sorted indices, so read the rows in the order of the
+                // file, then restore the order of `indices`.
+                let mut order = (0..indices.len()).collect::<Vec<_>>();
+                order.sort_by_key(|&i| indices.value(i));
+                let sorted = UInt32Array::from_iter_values(order.iter().map(|&i| indices.value(i)));
+                let arr = read_array(self, field, batch_id, &ReadBatchParams::from(sorted)).await?;
+                let mut positions = vec![0_u32; order.len()];
+                for (pos, &i) in order.iter().enumerate() {
+                    positions[i] = pos as u32;
+                }
+                Ok(take(arr.as_ref(), &UInt32Array::from(positions), None)?)
+            })
+            .buffered(num_cpus::get())
+            .try_collect::<Vec<_>>()
+            .await
+    }
 
-    read_fixed_stride_array(
-        reader.object_reader.as_ref(),
-        &field.data_type(),
-        page_info.position,
-        page_info.length,
-        params.clone(),
-    )
-    .await
+    /// The values of the dictionary field `field_id`, i.e., the vocabulary of the column.
+    ///
+    /// The values are loaded with the file metadata, so no data page is read.
+    pub fn dictionary_values(&self, field_id: i32) -> Result<ArrayRef> {
+        let field = self.field_by_id(field_id)?;
+        if !matches!(field.data_type(), DataType::Dictionary(_, _)) {
+            return Err(Error::Schema(format!(
+                "Field {} is not a dictionary field: {}",
+                field.name,
+                field.data_type()
+            )));
+        }
+        field
+            .dictionary
+            .as_ref()
+            .and_then(|d| d.values.clone())
+            .ok_or_else(|| {
+                Error::IO(format!(
+                    "Dictionary values of field {} are not loaded",
+                    field.name
+                ))
+            })
+    }
+
+    /// Read `byte_range` of the value at `row` of a binary or string column, without
+    /// reading the rest of the value.
+    ///
+    /// The value position is looked up in the offsets page, so it takes two small range
+    /// requests instead of reading the whole value. A null value is empty.
+    pub async fn read_binary_value_range(
+        &self,
+        field_id: i32,
+        batch_id: i32,
+        row: usize,
+        byte_range: Range<usize>,
+    ) -> Result<Bytes> {
+        let field = self.field_by_id(field_id)?;
+        if !matches!(
+            field.data_type(),
+            DataType::Binary | DataType::LargeBinary | DataType::Utf8 | DataType::LargeUtf8
+        ) {
+            return Err(Error::Schema(format!(
+                "Field {} is not a binary field: {}",
+                field.name,
+                field.data_type()
+            )));
+        }
+        let page_info = get_page_info(&self.page_table, field, batch_id)?;
+        if row >= page_info.length {
+            return Err(Error::OutOfRange(format!(
+                "Row {} is out of bounds {} in batch {}",
+                row, page_info.length, batch_id
+            )));
+        }
+
+        let positions = read_fixed_stride_array(
+            self.object_reader.as_ref(),
+            &DataType::Int64,
+            page_info.position,
+            page_info.length + 1,
+            row..row + 2,
+        )
+        .await?;
+        let positions = as_primitive_array::<Int64Type>(positions.as_ref());
+        let start = positions.value(0) as usize;
+        let value_length = positions.value(1) as usize - start;
+        if byte_range.start > byte_range.end || byte_range.end > value_length {
+            return Err(Error::OutOfRange(format!(
+                "Byte range {:?} is out of bounds {} of row {}",
+                byte_range, value_length, row
+            )));
+        }
+        self.object_reader
+            .get_range(start + byte_range.start..start + byte_range.end)
+            .await
+    }
+
+    /// Read the little-endian values of a fixed-stride column of a batch as one contiguous
+    /// [Buffer], without wrapping them in an Arrow array.
+    ///
+    /// The buffer holds `num_rows * byte_width` bytes. Null rows keep the bytes that were
+    /// written for them. Boolean columns are bit-packed, thus not supported.
+    pub async fn read_raw_fixed_stride(
+        &self,
+        field_id: i32,
+        batch_id: i32,
+        params: impl Into<ReadBatchParams>,
+    ) -> Result<Buffer> {
+        let field = self.field_by_id(field_id)?;
+        let data_type = field.data_type();
+        if !has_byte_stride(&data_type) {
+            return Err(Error::Schema(format!(
+                "Field {} is not a fixed-stride field: {}",
+                field.name, data_type
+            )));
+        }
+        if self.verify_checksums {
+            verify_page_checksum(self, field, batch_id).await?;
+        }
+        let page_info = self.page_table.get(field.id, batch_id);
+        let arr =
+            _read_fixed_stride_array(self, field, batch_id, page_info, &params.into()).await?;
+
+        // Locate the values of the (sliced) array in the buffer of the leaf values.
+        let mut data = arr.data();
+        let mut offset = 0;
+        while let DataType::FixedSizeList(_, list_size) = data.data_type() {
+            offset = (offset + data.offset()) * *list_size as usize;
+            data = &data.child_data()[0];
+        }
+        let start = (offset + data.offset()) * data.data_type().byte_width();
+        let num_bytes = arr.len() * data_type.byte_width();
+        let values = &data.buffers()[0];
+        if start == 0 && values.len() == num_bytes {
+            Ok(values.clone())
+        } else {
+            Ok(Buffer::from(&values.as_slice()[start..start + num_bytes]))
+        }
+    }
+
+    /// Read the offsets of a list, string or binary column of a batch, without reading
+    /// its values.
+    ///
+    /// For the `n` rows covered by `params`, it returns `n + 1` offsets, so the length of
+    /// the i-th row is `offsets[i + 1] - offsets[i]`. The offsets are not zero-based:
+    /// the binary ones are positions in the file. The offsets of `List` are widened to
+    /// `Int64`. [`ReadBatchParams::Indices`] reads the offsets of the range of rows that
+    /// covers the indices.
+    pub async fn read_offsets(
+        &self,
+        field_id: i32,
+        batch_id: i32,
+        params: impl Into<ReadBatchParams>,
+    ) -> Result<Int64Array> {
+        let field = self.field_by_id(field_id)?;
+        let page_info = get_page_info(&self.page_table, field, batch_id)?;
+        // The offsets pages of lists store `num_rows + 1` values, while the binary pages
+        // have `num_rows` values followed by the end position.
+        let (offset_type, num_rows, num_offsets) = match field.data_type() {
+            DataType::List(_) => (
+                DataType::Int32,
+                page_info.length.saturating_sub(1),
+                page_info.length,
+            ),
+            DataType::LargeList(_) => (
+                DataType::Int64,
+                page_info.length.saturating_sub(1),
+                page_info.length,
+            ),
+            dt if dt.is_binary_like() => (DataType::Int64, page_info.length, page_info.length + 1),
+            dt => {
+                return Err(Error::Schema(format!(
+                    "Field {} is not a list or binary field: {}",
+                    field.name, dt
+                )))
+            }
+        };
+        let range = params.into().covering_range(num_rows);
+        if range.end > num_rows {
+            return Err(Error::OutOfRange(format!(
+                "Rows {:?} are out of bounds {} in batch {}",
+                range, num_rows, batch_id
+            )));
+        }
+
+        let offsets = read_fixed_stride_array(
+            self.object_reader.as_ref(),
+            &offset_type,
+            page_info.position,
+            num_offsets,
+            range.start..range.end + 1,
+        )
+        .await?;
+        Ok(match offset_type {
+            DataType::Int32 => Int64Array::from_iter_values(
+                as_primitive_array::<Int32Type>(offsets.as_ref())
+                    .values()
+                    .iter()
+                    .map(|v| *v as i64),
+            ),
+            _ => as_primitive_array::<Int64Type>(offsets.as_ref()).clone(),
+        })
+    }
+
+    /// Read a `FixedSizeList<Float32>` column of a batch, i.e., embeddings, into one
+    /// [Vec] of `f32` per row.
+    ///
+    /// Null rows are returned as empty vectors.
+    pub async fn read_fixed_size_list_f32(
+        &self,
+        batch_id: i32,
+        field_id: i32,
+        params: impl Into<ReadBatchParams>,
+    ) -> Result<Vec<Vec<f32>>> {
+        let field = self.field_by_id(field_id)?;
+        match field.data_type() {
+            DataType::FixedSizeList(item, _) if item.data_type() == &DataType::Float32 => {}
+            dt => {
+                return Err(Error::Schema(format!(
+                    "Field {} is not a FixedSizeList<Float32> field: {}",
+                    field.name, dt
+                )))
+            }
+        }
+        let arr = read_array(self, field, batch_id, &params.into()).await?;
+        let list_arr = as_fixed_size_list_array(arr.as_ref());
+        Ok((0..list_arr.len())
+            .map(|i| {
+                if list_arr.is_null(i) {
+                    Vec::new()
+                } else {
+                    as_primitive_array::<Float32Type>(list_arr.value(i).as_ref())
+                        .values()
+                        .to_vec()
+                }
+            })
+            .collect())
+    }
+
+    /// Read one column of a batch, and serialize it as an Arrow IPC stream.
+    ///
+    /// The schema of the IPC stream has the single field from [`FileReader::schema()`],
+    /// with the type the column is read as, i.e., a dictionary if set by
+    /// [`FileReader::with_dict_encode()`].
+    pub async fn column_to_ipc(
+        &self,
+        batch_id: i32,
+        field_id: i32,
+        params: impl Into<ReadBatchParams>,
+    ) -> Result<Vec<u8>> {
+        let field = self.field_by_id(field_id)?;
+        let arr = read_array(self, field, batch_id, &params.into()).await?;
+        let arrow_field = ArrowField::from(field);
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            arrow_field.name(),
+            arr.data_type().clone(),
+            arrow_field.is_nullable(),
+        )
+        .with_metadata(arrow_field.metadata().clone())]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![arr])?;
+
+        let mut buf = vec![];
+        {
+            let mut writer = StreamWriter::try_new(&mut buf, &schema)?;
+            writer.write(&batch)?;
+            writer.finish()?;
+        }
+        Ok(buf)
+    }
+
+    /// Count the occurrences of each dictionary value of a dictionary column in one batch.
+    ///
+    /// The returned counts are indexed by the dictionary key. Only the keys are read,
+    /// the dictionary values are not materialized.
+    pub async fn dictionary_value_counts(
+        &self,
+        batch_id: i32,
+        field_id: i32,
+    ) -> Result<UInt64Array> {
+        let field = self.field_by_id(field_id)?;
+        let data_type = field.data_type();
+        if !data_type.is_dictionary() {
+            return Err(Error::Schema(format!(
+                "Field {} is not a dictionary field: {}",
+                field.name, data_type
+            )));
+        }
+        let page_info = get_page_info(&self.page_table, field, batch_id)?;
+        let values = field
+            .dictionary
+            .as_ref()
+            .and_then(|d| d.values.as_ref())
+            .ok_or_else(|| {
+                Error::IO(format!(
+                    "Lance field {} misses the dictionary value array",
+                    field.name
+                ))
+            })?;
+        let decoder = DictionaryDecoder::new(
+            self.object_reader.as_ref(),
+            page_info.position,
+            page_info.length,
+            &data_type,
+            values.clone(),
+        );
+        decoder.value_counts().await
+    }
+
+    /// Read only the validity of a column in one batch.
+    ///
+    /// The returned [BooleanArray] is `true` for the valid rows and `false` for nulls.
+    /// It reads as few bytes as the encoding of the column allows:
+    ///
+    /// - Null columns are all null, and non-nullable columns are all valid, without any I/O.
+    /// - Var-length binary columns only read the positions of the values.
+    pub async fn read_validity(
+        &self,
+        batch_id: i32,
+        field_id: i32,
+        params: impl Into<ReadBatchParams>,
+    ) -> Result<BooleanArray> {
+        let field = self.field_by_id(field_id)?;
+        let params = params.into();
+        let batch_length = self.num_rows_in_batch(batch_id);
+
+        match field.data_type() {
+            DataType::Null => Ok(BooleanArray::from(vec![
+                false;
+                params.num_rows(batch_length)
+            ])),
+            // Plain encoding does not store nulls.
+            dt if !field.nullable || dt.is_fixed_stride() => {
+                Ok(BooleanArray::from(vec![
+                    true;
+                    params.num_rows(batch_length)
+                ]))
+            }
+            dt if dt.is_binary_like() => {
+                read_binary_validity(self, field, batch_id, &params, batch_length).await
+            }
+            _ => {
+                let arr = read_array(self, field, batch_id, &params).await?;
+                Ok(BooleanArray::from_iter(
+                    (0..arr.len()).map(|i| Some(arr.is_valid(i))),
+                ))
+            }
+        }
+    }
+
+    /// Fetch the pages that `projection` touches in batch `batch_id`, without decoding them.
+    ///
+    /// This warms up the caches underneath the [ObjectStore] before the actual read,
+    /// i.e., [`FileReader::read_batch()`].
+    pub async fn prefetch(&self, batch_id: i32, projection: &Schema) -> Result<()> {
+        let mut ranges = vec![];
+        let mut binary_positions = vec![];
+        for field in projection.fields.iter() {
+            collect_page_ranges(self, field, batch_id, &mut ranges, &mut binary_positions)?;
+        }
+
+        // The values of the var-length binary pages are located by their positions.
+        let values_ranges = stream::iter(binary_positions)
+            .map(|range| async move {
+                let buf = self.object_reader.get_range(range).await?;
+                let start = LittleEndian::read_i64(&buf[..8]) as usize;
+                let end = LittleEndian::read_i64(&buf[buf.len() - 8..]) as usize;
+                Ok::<Range<usize>, Error>(start..end)
+            })
+            .buffer_unordered(num_cpus::get())
+            .try_collect::<Vec<_>>()
+            .await?;
+        ranges.extend(values_ranges.into_iter().filter(|r| !r.is_empty()));
+
+        stream::iter(ranges)
+            .map(|range| async move { self.object_reader.get_range(range).await })
+            .buffer_unordered(num_cpus::get())
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(())
+    }
 }
 
-fn read_null_array(
+/// Collect the byte ranges of the pages of `field` in batch `batch_id`.
+///
+/// The ranges of the var-length binary positions are collected to `binary_positions`,
+/// since they are needed to locate the values.
+fn collect_page_ranges(
     reader: &FileReader,
     field: &Field,
     batch_id: i32,
-    params: &ReadBatchParams,
-) -> Result<ArrayRef> {
-    let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+    ranges: &mut Vec<Range<usize>>,
+    binary_positions: &mut Vec<Range<usize>>,
+) -> Result<()> {
+    use DataType::*;
 
-    let length_output = match params {
-        ReadBatchParams::Indices(indices) => {
-            if indices.is_empty() {
-                0
-            } else {
-                let idx_max = *indices.values().iter().max().unwrap() as u64;
-                if idx_max >= page_info.length.try_into().unwrap() {
-                    return Err(Error::IO(format!(
-                        "NullArray Reader: request([{}]) out of range: [0..{}]",
-                        idx_max, page_info.length
-                    )));
+    let data_type = field.data_type();
+    match &data_type {
+        Null => {}
+        Struct(_) => {
+            if let Some(page_info) = reader.page_table.get(field.id, batch_id) {
+                let num_bytes = plain_page_bytes(&Boolean, page_info.length);
+                if num_bytes > 0 {
+                    ranges.push(page_info.position..page_info.position + num_bytes);
                 }
-                indices.len()
+            }
+            for child in field.children.iter() {
+                collect_page_ranges(reader, child, batch_id, ranges, binary_positions)?;
             }
         }
-        _ => {
-            let (idx_start, idx_end) = match params {
-                ReadBatchParams::Range(r) => (r.start, r.end),
-                ReadBatchParams::RangeFull => (0, page_info.length),
-                ReadBatchParams::RangeTo(r) => (0, r.end),
-                ReadBatchParams::RangeFrom(r) => (r.start, page_info.length),
-                _ => unreachable!(),
+        Utf8 | LargeUtf8 | Binary | LargeBinary => {
+            let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+            let num_bytes = (page_info.length + 1) * Int64.byte_width();
+            binary_positions.push(page_info.position..page_info.position + num_bytes);
+        }
+        Dictionary(key_type, _) => {
+            let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+            let num_bytes = plain_page_bytes(key_type, page_info.length);
+            ranges.push(page_info.position..page_info.position + num_bytes);
+        }
+        List(_) | LargeList(_) => {
+            let offset_type = if matches!(data_type, List(_)) {
+                Int32
+            } else {
+                Int64
             };
-            if idx_end > page_info.length {
-                return Err(Error::IO(format!(
-                    "NullArray Reader: request([{}..{}]) out of range: [0..{}]",
-                    idx_start, idx_end, page_info.length
-                )));
+            let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+            let num_bytes = plain_page_bytes(&offset_type, page_info.length);
+            ranges.push(page_info.position..page_info.position + num_bytes);
+            collect_page_ranges(
+                reader,
+                &field.children[0],
+                batch_id,
+                ranges,
+                binary_positions,
+            )?;
+        }
+        dt if dt.is_fixed_stride() => {
+            let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+            let num_bytes = plain_page_bytes(dt, page_info.length);
+            ranges.push(page_info.position..page_info.position + num_bytes);
+        }
+        _ => {
+            return Err(Error::Unsupported(format!(
+                "Prefetch does not support data type {data_type} yet"
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Number of data bytes of `field` and its children in batch `batch_id`.
+#[async_recursion]
+async fn page_data_bytes(reader: &FileReader, field: &Field, batch_id: i32) -> Result<usize> {
+    use DataType::*;
+
+    let data_type = field.data_type();
+    let size = match &data_type {
+        Null => 0,
+        Struct(_) => {
+            let mut size = reader
+                .page_table
+                .get(field.id, batch_id)
+                .map_or(0, |page_info| plain_page_bytes(&Boolean, page_info.length));
+            for child in field.children.iter() {
+                size += page_data_bytes(reader, child, batch_id).await?;
             }
-            idx_end - idx_start
+            size
+        }
+        Utf8 | LargeUtf8 | Binary | LargeBinary => {
+            let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+            let positions = read_fixed_stride_array(
+                reader.object_reader.as_ref(),
+                &Int64,
+                page_info.position,
+                page_info.length + 1,
+                [0, page_info.length as u32].as_slice(),
+            )
+            .await?;
+            let positions: &Int64Array = as_primitive_array(positions.as_ref());
+            (positions.value(1) - positions.value(0)) as usize
+        }
+        Dictionary(key_type, _) => {
+            let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+            plain_page_bytes(key_type, page_info.length)
+        }
+        List(_) | LargeList(_) => {
+            let offset_type = if matches!(data_type, List(_)) {
+                Int32
+            } else {
+                Int64
+            };
+            let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+            plain_page_bytes(&offset_type, page_info.length)
+                + page_data_bytes(reader, &field.children[0], batch_id).await?
+        }
+        dt if dt.is_fixed_stride() => {
+            let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+            plain_page_bytes(dt, page_info.length)
+        }
+        _ => {
+            return Err(Error::Unsupported(format!(
+                "Can not size data type {data_type} yet"
+            )))
         }
     };
-
-    Ok(Arc::new(NullArray::new(length_output)))
+    Ok(size)
 }
 
-async fn read_binary_array(
-    reader: &FileReader,
-    field: &Field,
-    batch_id: i32,
-    params: &ReadBatchParams,
-) -> Result<ArrayRef> {
-    let page_info = get_page_info(&reader.page_table, field, batch_id)?;
+/// Returns true if the values of `data_type` are stored with a whole number of bytes each.
+fn has_byte_stride(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Boolean => false,
+        DataType::FixedSizeList(item, _) => has_byte_stride(item.data_type()),
+        dt => dt.is_fixed_stride(),
+    }
+}
 
-    use crate::io::object_reader::read_binary_array;
-    read_binary_array(
-        reader.object_reader.as_ref(),
-        &field.data_type(),
-        field.nullable,
-        page_info.position,
-        page_info.length,
-        params,
-    )
-    .await
+/// Number of bytes of a plain encoded page of `length` values.
+fn plain_page_bytes(data_type: &DataType, length: usize) -> usize {
+    match data_type {
+        DataType::Boolean => (length + 7) / 8,
+        DataType::FixedSizeList(item, list_size) => {
+            plain_page_bytes(item.data_type(), length * *list_size as usize)
+        }
+        _ => length * data_type.byte_width(),
+    }
 }
 
-async fn read_dictionary_array(
+/// Read the validity of a var-length binary column from its positions.
+///
+/// An empty value in a nullable binary column is read back as null.
+async fn read_binary_validity(
     reader: &FileReader,
     field: &Field,
     batch_id: i32,
     params: &ReadBatchParams,
-) -> Result<ArrayRef> {
+    batch_length: usize,
+) -> Result<BooleanArray> {
     let page_info = get_page_info(&reader.page_table, field, batch_id)?;
-    let data_type = field.data_type();
-    let decoder = DictionaryDecoder::new(
+    let range = params.covering_range(batch_length);
+    if range.is_empty() {
+        return Ok(BooleanArray::from(Vec::<bool>::new()));
+    }
+
+    let position_decoder = PlainDecoder::new(
         reader.object_reader.as_ref(),
+        &DataType::Int64,
         page_info.position,
-        page_info.length,
-        &data_type,
-        field
-            .dictionary
-            .as_ref()
-            .unwrap()
-            .values
-            .as_ref()
-            .unwrap()
-            .clone(),
+        page_info.length + 1,
+    )?;
+    let positions = position_decoder.get(range.start..range.end + 1).await?;
+    let positions: &Int64Array = as_primitive_array(positions.as_ref());
+    let validity = BooleanArray::from(
+        positions
+            .values()
+            .windows(2)
+            .map(|w| w[0] != w[1])
+            .collect::<Vec<_>>(),
     );
-    decoder.get(params.clone()).await
-}
 
-async fn read_struct_array(
-    reader: &FileReader,
-    field: &Field,
-    batch_id: i32,
-    params: &ReadBatchParams,
-) -> Result<ArrayRef> {
-    // TODO: use tokio to make the reads in parallel.
-    let mut sub_arrays = vec![];
-    for child in field.children.as_slice() {
-        let arr = read_array(reader, child, batch_id, params).await?;
-        sub_arrays.push((child.into(), arr));
+    match params {
+        ReadBatchParams::Indices(indices) => {
+            let shifted = subtract_scalar(indices, range.start as u32)?;
+            Ok(as_boolean_array(take(&validity, &shifted, None)?.as_ref()).clone())
+        }
+        _ => Ok(validity),
     }
-
-    Ok(Arc::new(StructArray::from(sub_arrays)))
 }
 
-async fn take_list_array<T: ArrowNumericType>(
+/// Read a batch.
+///
+/// The nulls of the top-level fields in `null_fill` are replaced, after the ones of the
+/// reader.
+async fn read_batch(
     reader: &FileReader,
-    field: &Field,
+    params: &ReadBatchParams,
+    schema: &Schema,
     batch_id: i32,
-    positions: &PrimitiveArray<T>,
+    with_row_id: bool,
+    parallelism: usize,
+    null_fill: Option<&HashMap<i32, ScalarValue>>,
+) -> Result<RecordBatch> {
+    reader.check_cancelled()?;
+    let cache = reader.projection_cache(schema);
+    // `buffered` keeps the order of the fields.
+    let arrs = stream::iter(schema.fields.iter().zip(&cache.pages))
+        .map(|(f, pages)| async move {
+            let page_info = pages.get(batch_id as usize).and_then(Option::as_ref);
+            let arr = read_array_at(reader, f, batch_id, page_info, params).await?;
+            match null_fill.and_then(|fills| fills.get(&f.id)) {
+                Some(value) => fill_nulls(arr, value),
+                None => Ok(arr),
+            }
+        })
+        .buffered(parallelism)
+        .try_collect::<Vec<_>>()
+        .await?;
+    let mut batch = RecordBatch::try_new(cache.schema.clone(), arrs)?;
+    if with_row_id {
+        let ids_in_batch: Vec<usize> = match params {
+            ReadBatchParams::Indices(indices) => {
+                indices.values().iter().map(|v| *v as usize).collect()
+            }
+            ReadBatchParams::Range(r) => r.clone().collect(),
+            ReadBatchParams::RangeFull => (0..batch.num_rows()).collect(),
+            ReadBatchParams::RangeTo(r) => (0..r.end).collect(),
+            ReadBatchParams::RangeFrom(r) => (r.start..r.start + batch.num_rows()).collect(),
+        };
+        let batch_offset = reader
+            .metadata
+            .get_offset(batch_id)
+            .ok_or_else(|| Error::NotFound(format!("batch {batch_id} does not exist")))?;
+        debug_assert!(batch_offset >= 0, "negative batch offset {batch_offset}");
+        let batch_offset = batch_offset as u64;
+        debug_assert!(
+            reader.fragment_id <= u32::MAX as u64,
+            "fragment id {} does not fit in 32 bits",
+            reader.fragment_id
+        );
+        let fragment_id = reader.fragment_id as u32;
+        let row_id_arr = Arc::new(UInt64Array::from_iter_values(ids_in_batch.iter().map(
+            |o| {
+                let offset = batch_offset + *o as u64;
+                debug_assert!(
+                    offset <= u32::MAX as u64,
+                    "row offset {offset} does not fit in 32 bits"
+                );
+                row_id(fragment_id, offset as u32)
+            },
+        )));
+        batch = batch.try_with_column(row_id_field(), row_id_arr)?;
+    }
+    Ok(batch)
+}
+
+async fn read_array(
+    reader: &FileReader,
+    field: &Field,
+    batch_id: i32,
+    params: &ReadBatchParams,
+) -> Result<ArrayRef> {
+    let page_info = reader.page_table.get(field.id, batch_id);
+    read_array_at(reader, field, batch_id, page_info, params).await
+}
+
+/// Read `field` in `batch_id`, whose page is `page_info`.
+#[async_recursion]
+async fn read_array_at(
+    reader: &FileReader,
+    field: &Field,
+    batch_id: i32,
+    page_info: Option<&PageInfo>,
+    params: &ReadBatchParams,
+) -> Result<ArrayRef> {
+    if reader.verify_checksums {
+        verify_page_checksum(reader, field, batch_id).await?;
+    }
+
+    let data_type = field.data_type();
+
+    use DataType::*;
+
+    let arr = if data_type.is_fixed_stride() {
+        _read_fixed_stride_array(reader, field, batch_id, page_info, params).await
+    } else {
+        match data_type {
+            Null => read_null_array(field, batch_id, page_info, params),
+            Utf8 | LargeUtf8 | Binary | LargeBinary => {
+                read_binary_array(reader, field, batch_id, page_info, params).await
+            }
+            Struct(_) => read_struct_array(reader, field, batch_id, page_info, params).await,
+            Dictionary(_, _) => {
+                read_dictionary_array(reader, field, batch_id, page_info, params).await
+            }
+            List(_) => {
+                read_list_array::<Int32Type>(reader, field, batch_id, page_info, params).await
+            }
+            LargeList(_) => {
+                read_list_array::<Int64Type>(reader, field, batch_id, page_info, params).await
+            }
+            _ => Err(Error::Unsupported(format!(
+                "No support for {data_type} yet"
+            ))),
+        }
+    }?;
+
+    let arr = match reader.null_fills.get(&field.id) {
+        Some(value) => fill_nulls(arr, value)?,
+        None => arr,
+    };
+    if reader.dict_encode_fields.contains(&field.id) {
+        Ok(cast(&arr, &dict_encoded_type(arr.data_type()))?)
+    } else {
+        Ok(arr)
+    }
+}
+
+/// The dictionary type a string field is read as by [`FileReader::with_dict_encode()`].
+fn dict_encoded_type(value_type: &DataType) -> DataType {
+    DataType::Dictionary(Box::new(DataType::Int32), Box::new(value_type.clone()))
+}
+
+/// Replace the nulls in `arr` with `value`, and drop its validity bitmap.
+fn fill_nulls(arr: ArrayRef, value: &ScalarValue) -> Result<ArrayRef> {
+    if arr.null_count() == 0 {
+        return Ok(arr);
+    }
+    let fill = value.to_array_of_size(arr.len());
+    let filled = zip(&is_not_null(arr.as_ref())?, arr.as_ref(), fill.as_ref())?;
+    let data = filled.into_data().into_builder().nulls(None).build()?;
+    Ok(make_array(data))
+}
+
+/// Returns false if no value described by `stats` can be within `range`.
+fn stats_may_overlap(stats: &ColumnStats, range: &Range<ScalarValue>) -> bool {
+    use std::cmp::Ordering::*;
+
+    let Some(min) = stats.min.as_ref() else {
+        // All the values are null.
+        return false;
+    };
+    // No max of a truncated binary value means no upper bound.
+    let below = stats.max.as_ref().map_or(false, |max| {
+        matches!(max.partial_cmp(&range.start), Some(Less))
+    });
+    let above = matches!(min.partial_cmp(&range.end), Some(Greater | Equal));
+    !below && !above
+}
+
+/// Compare the bytes of the page of `field` in `batch_id` with its stored checksum.
+async fn verify_page_checksum(reader: &FileReader, field: &Field, batch_id: i32) -> Result<()> {
+    let Some(checksum) = reader
+        .page_checksums
+        .as_ref()
+        .and_then(|c| c.get(field.id, batch_id))
+    else {
+        return Ok(());
+    };
+    let bytes = reader
+        .object_reader
+        .get_range(checksum.position..checksum.position + checksum.length)
+        .await?;
+    if crc32c::crc32c(&bytes) != checksum.crc32c {
+        return Err(Error::IO(format!(
+            "page checksum mismatch for field {}",
+            field.name
+        )));
+    }
+    Ok(())
+}
+
+/// Resolved [`FileReader::read_batch()`] state of a projection.
+struct ProjectionCache {
+    /// Id and logical type of the fields of the projection, in depth-first order.
+    ///
+    /// The types are part of the key, as projections with the same field ids may
+    /// read the fields as different types, i.e., `Utf8` as `LargeUtf8`.
+    key: Vec<(i32, LogicalType)>,
+
+    /// Arrow schema of the projection.
+    schema: SchemaRef,
+
+    /// Pages of the top-level fields of the projection, by batch id.
+    pages: Vec<Vec<Option<PageInfo>>>,
+}
+
+impl ProjectionCache {
+    /// Whether `projection` has the fields this cache was resolved for.
+    fn matches(&self, projection: &Schema) -> bool {
+        let mut key = self.key.iter();
+        let mut same = true;
+        projection.visit_fields(|f| {
+            same &= key
+                .next()
+                .map_or(false, |(id, lt)| *id == f.id && lt == f.logical_type());
+        });
+        same && key.next().is_none()
+    }
+}
+
+fn get_page_info<'a>(
+    page_table: &'a PageTable,
+    field: &Field,
+    batch_id: i32,
+) -> Result<&'a PageInfo> {
+    require_page_info(page_table.get(field.id, batch_id), field, batch_id)
+}
+
+/// `page_info` of `field` in `batch_id`, or an error if there is no such page.
+fn require_page_info<'a>(
+    page_info: Option<&'a PageInfo>,
+    field: &Field,
+    batch_id: i32,
+) -> Result<&'a PageInfo> {
+    page_info.ok_or_else(|| {
+        Error::NotFound(format!(
+            "No page info found for field: {}, field_id={} batch={}",
+            field.name, field.id, batch_id
+        ))
+    })
+}
+
+/// Read primitive array for batch `batch_idx`.
+async fn _read_fixed_stride_array(
+    reader: &FileReader,
+    field: &Field,
+    batch_id: i32,
+    page_info: Option<&PageInfo>,
+    params: &ReadBatchParams,
+) -> Result<ArrayRef> {
+    let page_info = require_page_info(page_info, field, batch_id)?;
+
+    // Ranges of primitive values share the bytes read from the object store.
+    read_fixed_stride_array_zerocopy(
+        reader.object_reader.as_ref(),
+        &field.data_type(),
+        page_info.position,
+        page_info.length,
+        params.clone(),
+    )
+    .await
+}
+
+fn read_null_array(
+    field: &Field,
+    batch_id: i32,
+    page_info: Option<&PageInfo>,
+    params: &ReadBatchParams,
+) -> Result<ArrayRef> {
+    let page_info = require_page_info(page_info, field, batch_id)?;
+
+    let length_output = match params {
+        ReadBatchParams::Indices(indices) => {
+            if indices.is_empty() {
+                0
+            } else {
+                let idx_max = *indices.values().iter().max().unwrap() as u64;
+                if idx_max >= page_info.length.try_into().unwrap() {
+                    return Err(Error::OutOfRange(format!(
+                        "NullArray Reader: request([{}]) out of range: [0..{}]",
+                        idx_max, page_info.length
+                    )));
+                }
+                indices.len()
+            }
+        }
+        _ => {
+            let (idx_start, idx_end) = match params {
+                ReadBatchParams::Range(r) => (r.start, r.end),
+                ReadBatchParams::RangeFull => (0, page_info.length),
+                ReadBatchParams::RangeTo(r) => (0, r.end),
+                ReadBatchParams::RangeFrom(r) => (r.start, page_info.length),
+                _ => unreachable!(),
+            };
+            if idx_end > page_info.length {
+                return Err(Error::OutOfRange(format!(
+                    "NullArray Reader: request([{}..{}]) out of range: [0..{}]",
+                    idx_start, idx_end, page_info.length
+                )));
+            }
+            idx_end - idx_start
+        }
+    };
+
+    Ok(Arc::new(NullArray::new(length_output)))
+}
+
+async fn read_binary_array(
+    reader: &FileReader,
+    field: &Field,
+    batch_id: i32,
+    page_info: Option<&PageInfo>,
+    params: &ReadBatchParams,
+) -> Result<ArrayRef> {
+    let page_info = require_page_info(page_info, field, batch_id)?;
+
+    use crate::io::object_reader::read_binary_array;
+    read_binary_array(
+        reader.object_reader.as_ref(),
+        &field.data_type(),
+        field.nullable,
+        page_info.position,
+        page_info.length,
+        params,
+    )
+    .await
+}
+
+async fn read_dictionary_array(
+    reader: &FileReader,
+    field: &Field,
+    batch_id: i32,
+    page_info: Option<&PageInfo>,
+    params: &ReadBatchParams,
+) -> Result<ArrayRef> {
+    let page_info = require_page_info(page_info, field, batch_id)?;
+    let data_type = field.data_type();
+    let decoder = DictionaryDecoder::new(
+        reader.object_reader.as_ref(),
+        page_info.position,
+        page_info.length,
+        &data_type,
+        field
+            .dictionary
+            .as_ref()
+            .unwrap()
+            .values
+            .as_ref()
+            .unwrap()
+            .clone(),
+    );
+    decoder.get(params.clone()).await
+}
+
+async fn read_struct_array(
+    reader: &FileReader,
+    field: &Field,
+    batch_id: i32,
+    page_info: Option<&PageInfo>,
+    params: &ReadBatchParams,
+) -> Result<ArrayRef> {
+    // TODO: use tokio to make the reads in parallel.
+    let mut sub_arrays = vec![];
+    for child in field.children.as_slice() {
+        let arr = read_array(reader, child, batch_id, params).await?;
+        sub_arrays.push((child.into(), arr));
+    }
+
+    // Struct level validity, which is not written by the older versions.
+    match page_info {
+        Some(page_info) if field.nullable && page_info.length > 0 => {
+            let validity = read_fixed_stride_array(
+                reader.object_reader.as_ref(),
+                &DataType::Boolean,
+                page_info.position,
+                page_info.length,
+                params.clone(),
+            )
+            .await?;
+            let null_bitmap: Buffer = as_boolean_array(validity.as_ref())
+                .iter()
+                .map(|v| v == Some(true))
+                .collect::<MutableBuffer>()
+                .into();
+            Ok(Arc::new(StructArray::from((sub_arrays, null_bitmap))))
+        }
+        _ => Ok(Arc::new(StructArray::from(sub_arrays))),
+    }
+}
+
+/// Take the lists at `indices` of a batch.
+///
+/// `positions` are the list positions read from the row `positions_start` of the batch,
+/// covering all the `indices`.
+async fn take_list_array<T: ArrowNumericType>(
+    reader: &FileReader,
+    field: &Field,
+    batch_id: i32,
+    positions: &PrimitiveArray<T>,
+    positions_start: usize,
     indices: &UInt32Array,
 ) -> Result<ArrayRef>
 where
     T::Native: ArrowNativeTypeOp + OffsetSizeTrait,
 {
-    let first_idx = indices.value(0);
+    // The empty indices are handled by read_list_array().
+    debug_assert!(!indices.is_empty());
     // Range of values for each index
     let ranges = indices
         .values()
         .iter()
-        .map(|i| (*i - first_idx).as_usize())
+        .map(|i| i.as_usize() - positions_start)
         .map(|idx| positions.value(idx).as_usize()..positions.value(idx + 1).as_usize())
         .collect::<Vec<_>>();
     let field = field.clone();
@@ -554,22 +2099,41 @@ async fn read_list_array<T: ArrowNumericType>(
     reader: &FileReader,
     field: &Field,
     batch_id: i32,
+    page_info: Option<&PageInfo>,
     params: &ReadBatchParams,
 ) -> Result<ArrayRef>
 where
     T::Native: ArrowNativeTypeOp + OffsetSizeTrait,
 {
-    // Offset the position array by 1 in order to include the upper bound of the last element
+    if let ReadBatchParams::Indices(indices) = params {
+        if indices.is_empty() {
+            return Ok(new_empty_array(&field.data_type()));
+        }
+    }
+    let page_info = require_page_info(page_info, field, batch_id)?;
+
+    // Offset the position array by 1 in order to include the upper bound of the last element.
+    // Every variant is matched explicitly, so that a new variant must be handled here and
+    // in `value_params` below.
     let positions_params = match params {
         ReadBatchParams::Range(range) => ReadBatchParams::from(range.start..(range.end + 1)),
         ReadBatchParams::RangeTo(range) => ReadBatchParams::from(..range.end + 1),
-        ReadBatchParams::Indices(indices) => {
-            (indices.value(0).as_usize()..indices.value(indices.len() - 1).as_usize() + 2).into()
+        ReadBatchParams::RangeFrom(range) => ReadBatchParams::from(range.start..),
+        ReadBatchParams::RangeFull => ReadBatchParams::RangeFull,
+        ReadBatchParams::Indices(_) => {
+            let range = params.covering_range(page_info.length);
+            (range.start..range.end + 1).into()
         }
-        p => p.clone(),
     };
+    // The offsets page has one more value than the rows of the batch.
+    let positions_range = positions_params.covering_range(page_info.length);
+    if positions_range.start > positions_range.end || positions_range.end > page_info.length {
+        return Err(Error::IO(format!(
+            "Offsets {:?} of list field {} are out of the page of length {} in batch {}",
+            positions_range, field.name, page_info.length, batch_id
+        )));
+    }
 
-    let page_info = get_page_info(&reader.page_table, field, batch_id)?;
     let position_arr = read_fixed_stride_array(
         reader.object_reader.as_ref(),
         &T::DATA_TYPE,
@@ -580,21 +2144,31 @@ where
     .await?;
 
     let positions: &PrimitiveArray<T> = as_primitive_array(position_arr.as_ref());
+    // Large list offsets may not fit in usize, i.e., on 32-bit targets.
+    let position_at = |idx: usize| -> Result<usize> {
+        let position = positions.value(idx);
+        position.to_usize().ok_or_else(|| {
+            Error::IO(format!(
+                "Invalid offset {:?} of list field {} in batch {}",
+                position, field.name, batch_id
+            ))
+        })
+    };
 
     // Recompute params so they align with the offset array
     let value_params = match params {
-        ReadBatchParams::Range(range) => ReadBatchParams::from(
-            positions.value(0).as_usize()..positions.value(range.end - range.start).as_usize(),
-        ),
-        ReadBatchParams::RangeTo(RangeTo { end }) => {
-            ReadBatchParams::from(..positions.value(*end).as_usize())
-        }
-        ReadBatchParams::RangeFrom(_) => ReadBatchParams::from(positions.value(0).as_usize()..),
-        ReadBatchParams::RangeFull => ReadBatchParams::from(
-            positions.value(0).as_usize()..positions.value(positions.len() - 1).as_usize(),
-        ),
+        ReadBatchParams::Range(range) => {
+            ReadBatchParams::from(position_at(0)?..position_at(range.end - range.start)?)
+        }
+        ReadBatchParams::RangeTo(RangeTo { end }) => ReadBatchParams::from(..position_at(*end)?),
+        ReadBatchParams::RangeFrom(_) => ReadBatchParams::from(position_at(0)?..),
+        ReadBatchParams::RangeFull => {
+            ReadBatchParams::from(position_at(0)?..position_at(positions.len() - 1)?)
+        }
         ReadBatchParams::Indices(indices) => {
-            return take_list_array(reader, field, batch_id, positions, indices).await;
+            let positions_start = params.covering_range(page_info.length).start;
+            return take_list_array(reader, field, batch_id, positions, positions_start, indices)
+                .await;
         }
     };
 
@@ -616,605 +2190,2989 @@ mod tests {
         builder::{Int32Builder, ListBuilder, StringBuilder},
         cast::{as_primitive_array, as_string_array, as_struct_array},
         types::UInt8Type,
-        Array, DictionaryArray, Float32Array, Int64Array, LargeListArray, ListArray, NullArray,
+        Array, DictionaryArray, FixedSizeListArray, Float32Array, Int32Array, Int64Array,
+        LargeBinaryArray, LargeListArray, LargeStringArray, ListArray, NullArray,
         RecordBatchReader, StringArray, StructArray, UInt32Array, UInt8Array,
     };
+    use arrow_ipc::reader::StreamReader;
     use arrow_schema::{Field as ArrowField, Fields as ArrowFields, Schema as ArrowSchema};
     use rand::{distributions::Alphanumeric, Rng};
     use tempfile::tempdir;
     use tokio::io::AsyncWriteExt;
 
+    use crate::io::object_store::testing::MockObjectStore;
     use crate::io::{write_manifest, FileWriter};
 
     #[tokio::test]
-    async fn read_with_row_id() {
+    async fn read_with_row_id() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("f", DataType::Float32, false),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+
+        // Write 10 batches.
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        for batch_id in 0..10 {
+            let value_range = batch_id * 10..batch_id * 10 + 10;
+            let columns: Vec<ArrayRef> = vec![
+                Arc::new(Int64Array::from_iter(
+                    value_range.clone().collect::<Vec<_>>(),
+                )),
+                Arc::new(Float32Array::from_iter(
+                    value_range.map(|n| n as f32).collect::<Vec<_>>(),
+                )),
+            ];
+            let batch = RecordBatch::try_new(Arc::new(arrow_schema.clone()), columns).unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let fragment = 123;
+        let mut reader = FileReader::try_new_with_fragment(&store, &path, fragment, None)
+            .await
+            .unwrap();
+        reader.with_row_id(true);
+
+        for b in 0..10 {
+            let batch = reader.read_batch(b, .., reader.schema()).await.unwrap();
+            let row_ids_col = &batch["_rowid"];
+            // Do the same computation as `row_id`.
+            let start_pos = (fragment << 32) as u64 + 10 * b as u64;
+
+            assert_eq!(
+                &UInt64Array::from_iter_values(start_pos..start_pos + 10),
+                as_primitive_array(row_ids_col)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetch() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("b", DataType::Boolean, true),
+            ArrowField::new("s", DataType::Utf8, true),
+            ArrowField::new(
+                "l",
+                DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                true,
+            ),
+            ArrowField::new(
+                "st",
+                DataType::Struct(ArrowFields::from(vec![ArrowField::new(
+                    "f",
+                    DataType::Float32,
+                    true,
+                )])),
+                true,
+            ),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        // Count the range requests that are not served from the cache.
+        let mock = Arc::new(MockObjectStore::new());
+        let mut store = ObjectStore::memory();
+        store.inner = mock.clone();
+        let store = store.with_cache(1024 * 1024);
+        let path = Path::from("/prefetch");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        let mut list_builder = ListBuilder::new(Int32Builder::new());
+        for i in 0..20 {
+            list_builder
+                .values()
+                .append_slice(&[i, i][..i as usize % 3]);
+            list_builder.append(true);
+        }
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from_iter_values(0..20)),
+            Arc::new(BooleanArray::from_iter((0..20).map(|v| Some(v % 2 == 0)))),
+            Arc::new(StringArray::from_iter_values(
+                (0..20).map(|v| format!("s-{v}")),
+            )),
+            Arc::new(list_builder.finish()),
+            Arc::new(StructArray::from(vec![(
+                ArrowField::new("f", DataType::Float32, true),
+                Arc::new(Float32Array::from_iter_values((0..20).map(|v| v as f32))) as ArrayRef,
+            )])),
+        ];
+        let batch = RecordBatch::try_new(Arc::new(arrow_schema.clone()), columns).unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let opened = mock.range_requests();
+        reader.prefetch(0, reader.schema()).await.unwrap();
+        let prefetched = mock.range_requests();
+        assert!(prefetched > opened);
+
+        let actual = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        assert_eq!(actual, batch);
+        // All the pages read by read_batch are served from the prefetched ones.
+        assert_eq!(mock.range_requests(), prefetched);
+    }
+
+    #[tokio::test]
+    async fn test_page_info() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/page_info");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        for batch_id in 0..3 {
+            let batch = RecordBatch::try_new(
+                Arc::new(arrow_schema.clone()),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(
+                        batch_id * 10..batch_id * 10 + 10,
+                    )),
+                    Arc::new(StringArray::from_iter_values(
+                        (0..10).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let mut positions = vec![];
+        for batch_id in 0..3 {
+            for field_id in 0..2 {
+                let (position, length) = reader.page_info(field_id, batch_id).unwrap();
+                assert_eq!(length, 10);
+                positions.push(position);
+            }
+        }
+        // The first page starts at the beginning of the file, the rest follow it.
+        assert_eq!(positions[0], 0);
+        assert!(positions[1..].iter().all(|p| *p > 0));
+        assert!(reader.page_info(0, 3).is_none());
+        assert!(reader.page_info(5, 0).is_none());
+        // 10 Int64 values and 10 strings of 3 bytes in each of the 3 batches.
+        assert_eq!(
+            reader.total_data_bytes().await.unwrap(),
+            3 * (10 * 8 + 10 * 3)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tail_read_size() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/tail_read_size");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema.clone()),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..100)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..100).map(|v| format!("s-{v}")),
+                )),
+            ],
+        )
+        .unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert_eq!(reader.block_size(), store.block_size());
+
+        // Much larger than the file, and just large enough for the footer.
+        for tail_read_size in [64 * 1024 * 1024, 16] {
+            let reopened = FileReader::try_new(&store, &path)
+                .await
+                .unwrap()
+                .with_tail_read_size(tail_read_size)
+                .reopen()
+                .await
+                .unwrap();
+            assert_eq!(reopened.block_size(), tail_read_size);
+            assert_eq!(reopened.metadata, reader.metadata);
+            assert_eq!(reopened.schema(), reader.schema());
+            let actual = reopened.read_batch(0, .., reopened.schema()).await.unwrap();
+            assert_eq!(actual, batch);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_lengths() {
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new("i", DataType::Int64, true)]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/batch_lengths");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        for num_rows in [10, 3, 25] {
+            let batch = RecordBatch::try_new(
+                Arc::new(arrow_schema.clone()),
+                vec![Arc::new(Int64Array::from_iter_values(0..num_rows))],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert_eq!(reader.batch_lengths(), vec![10, 3, 25]);
+        assert_eq!(reader.batch_lengths().iter().sum::<usize>(), reader.len());
+        assert_eq!(reader.num_rows(), 38);
+    }
+
+    #[tokio::test]
+    async fn test_read_columns() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/read_columns");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema.clone()),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..20)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..20).map(|v| format!("s-{v}")),
+                )),
+            ],
+        )
+        .unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let arrays = reader
+            .read_columns(
+                0,
+                &[
+                    (0, ReadBatchParams::from(5..15)),
+                    // Unsorted indices.
+                    (1, ReadBatchParams::from([18_u32, 2, 7].as_slice())),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(arrays.len(), 2);
+        assert_eq!(
+            arrays[0].as_ref(),
+            &Int64Array::from_iter_values(5..15) as &dyn Array
+        );
+        assert_eq!(
+            arrays[1].as_ref(),
+            &StringArray::from(vec!["s-18", "s-2", "s-7"]) as &dyn Array
+        );
+
+        assert!(reader
+            .read_columns(0, &[(10, ReadBatchParams::RangeFull)])
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_fixed_size_list_f32() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new(
+                "vec",
+                DataType::FixedSizeList(
+                    Arc::new(ArrowField::new("item", DataType::Float32, true)),
+                    4,
+                ),
+                true,
+            ),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/read_fixed_size_list_f32");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        let values = Float32Array::from_iter_values((0..12).map(|v| v as f32));
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema.clone()),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..3)),
+                Arc::new(FixedSizeListArray::try_new_from_values(values, 4).unwrap()),
+            ],
+        )
+        .unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let vectors = reader.read_fixed_size_list_f32(0, 1, ..).await.unwrap();
+        assert_eq!(
+            vectors,
+            vec![
+                vec![0.0, 1.0, 2.0, 3.0],
+                vec![4.0, 5.0, 6.0, 7.0],
+                vec![8.0, 9.0, 10.0, 11.0],
+            ]
+        );
+        let vectors = reader
+            .read_fixed_size_list_f32(0, 1, [2_u32, 0].as_slice())
+            .await
+            .unwrap();
+        assert_eq!(
+            vectors,
+            vec![vec![8.0, 9.0, 10.0, 11.0], vec![0.0, 1.0, 2.0, 3.0]]
+        );
+
+        assert!(matches!(
+            reader.read_fixed_size_list_f32(0, 0, ..).await,
+            Err(Error::Schema(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_page_checksums() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..100)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..100).map(|v| format!("s-{v}")),
+                )),
+            ],
+        )
+        .unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema)
+            .await
+            .unwrap()
+            .with_page_checksums(true);
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path)
+            .await
+            .unwrap()
+            .with_verify_checksums(true);
+        let actual = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        assert_eq!(actual, batch);
+
+        // The pages of column "i" start at the beginning of the file.
+        let mut bytes = store
+            .inner
+            .get(&path)
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap()
+            .to_vec();
+        bytes[10] ^= 0xFF;
+        store.inner.put(&path, bytes.into()).await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path)
+            .await
+            .unwrap()
+            .with_verify_checksums(true);
+        let err = reader.read_batch(0, .., reader.schema()).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("page checksum mismatch for field i"),
+            "{}",
+            err
+        );
+
+        // The corruption goes unnoticed without verification.
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let actual = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        assert_ne!(actual, batch);
+    }
+
+    #[tokio::test]
+    async fn test_column_statistics() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new(
+                "l",
+                DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                true,
+            ),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        for b in 0..3_i64 {
+            // Batch `b` has values in [b * 100, b * 100 + 9] and `b` nulls.
+            let values = (0..10)
+                .map(|v| Some(b * 100 + v))
+                .chain((0..b).map(|_| None))
+                .collect::<Vec<_>>();
+            let num_rows = values.len();
+            let list_arr = ListArray::from_iter_primitive::<Int32Type, _, _>(
+                (0..num_rows).map(|_| Some(vec![Some(1)])),
+            );
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![Arc::new(Int64Array::from(values)), Arc::new(list_arr)],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        for b in 0..3_i64 {
+            let stats = reader.column_statistics(0, b as i32).unwrap();
+            assert_eq!(stats.null_count, b as usize);
+            assert_eq!(stats.min, Some(ScalarValue::Int64(Some(b * 100))));
+            assert_eq!(stats.max, Some(ScalarValue::Int64(Some(b * 100 + 9))));
+        }
+        // Nested columns do not have statistics.
+        assert_eq!(reader.column_statistics(1, 0), None);
+        // The list items do.
+        assert!(reader.column_statistics(2, 0).is_some());
+        assert_eq!(reader.column_statistics(0, 3), None);
+    }
+
+    #[tokio::test]
+    async fn test_scan_filtered() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        for b in 0..5_i64 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(b * 10..(b + 1) * 10)),
+                    Arc::new(StringArray::from_iter_values(
+                        (b * 10..(b + 1) * 10).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let range = ScalarValue::Int64(Some(15))..ScalarValue::Int64(Some(40));
+        let batches = reader
+            .scan_filtered(reader.schema(), "i", range)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        // Batch 0 ([0, 9]) and batch 4 ([40, 49]) are skipped.
+        assert_eq!(batches.len(), 3);
+        let batch = concat_batches(&batches[0].schema(), &batches).unwrap();
+        assert_eq!(
+            batch.column_by_name("i").unwrap().as_ref(),
+            &Int64Array::from_iter_values(10..40)
+        );
+
+        let mut stream = reader.scan_filtered(
+            reader.schema(),
+            "not_exist",
+            ScalarValue::Int64(Some(0))..ScalarValue::Int64(Some(1)),
+        );
+        assert!(matches!(stream.next().await, Some(Err(Error::Schema(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_take_empty_indices() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, true),
+            ArrowField::new(
+                "l",
+                DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                true,
+            ),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let mut list_builder = ListBuilder::new(Int32Builder::new());
+        for i in 0..10 {
+            list_builder.values().append_slice(&[i, i + 1]);
+            list_builder.append(true);
+        }
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema.clone()),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..10)),
+                Arc::new(list_builder.finish()),
+            ],
+        )
+        .unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/take_empty_indices");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let batch = reader.take(&[], &schema).await.unwrap();
+        assert_eq!(batch.num_rows(), 0);
+        assert_eq!(batch.schema().as_ref(), &arrow_schema);
+
+        let batch = reader
+            .read_batch(0, UInt32Array::from(Vec::<u32>::new()), &schema)
+            .await
+            .unwrap();
+        assert_eq!(batch.num_rows(), 0);
+        assert_eq!(batch.schema().as_ref(), &arrow_schema);
+    }
+
+    #[tokio::test]
+    async fn test_read_empty_file() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/empty");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        file_writer.finish().await.unwrap();
+
+        let mut reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(reader.num_batches(), 0);
+
+        let batch = reader.read_range(0..0, &schema).await.unwrap();
+        assert_eq!(batch.num_rows(), 0);
+        assert_eq!(batch.schema().as_ref(), &arrow_schema);
+        assert!(reader.read_range(0..1, &schema).await.is_err());
+
+        let batch = reader.take(&[], &schema).await.unwrap();
+        assert_eq!(batch.num_rows(), 0);
+        assert_eq!(batch.schema().as_ref(), &arrow_schema);
+
+        reader.with_row_id(true);
+        let batch = reader.read_range(0..0, &schema).await.unwrap();
+        assert_eq!(batch.schema().as_ref(), &reader.output_schema());
+        assert!(batch.column_by_name(ROW_ID_FIELD_NAME).is_some());
+    }
+
+    #[test]
+    fn test_row_id_field() {
+        assert_eq!(ROW_ID_FIELD_NAME, "_rowid");
+        let field = row_id_field();
+        assert_eq!(field.name(), ROW_ID_FIELD_NAME);
+        assert_eq!(field.data_type(), &DataType::UInt64);
+        assert!(!field.is_nullable());
+    }
+
+    #[tokio::test]
+    async fn test_output_schema() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/output_schema");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema.clone()),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..10)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..10).map(|v| v.to_string()),
+                )),
+            ],
+        )
+        .unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let mut reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert_eq!(reader.output_schema(), arrow_schema);
+        let batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        assert_eq!(batch.schema().as_ref(), &reader.output_schema());
+
+        reader.with_row_id(true);
+        let output_schema = reader.output_schema();
+        assert_eq!(output_schema.fields().len(), 3);
+        assert_eq!(
+            output_schema.field_with_name(ROW_ID).unwrap().data_type(),
+            &DataType::UInt64
+        );
+        let batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        assert_eq!(batch.schema().as_ref(), &output_schema);
+    }
+
+    #[tokio::test]
+    async fn test_row_id_large_offset() {
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new("i", DataType::Int64, false)]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/large_offset");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema.clone()),
+            vec![Arc::new(Int64Array::from_iter_values(0..10))],
+        )
+        .unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let fragment = 7;
+        let mut reader = FileReader::try_new_with_fragment(&store, &path, fragment, None)
+            .await
+            .unwrap();
+        reader.with_row_id(true);
+        // Pretend this batch sits right below i32::MAX, so adding the in-batch
+        // index overflows i32.
+        let batch_offset = i32::MAX - 4;
+        reader.metadata.batch_offsets = vec![batch_offset, i32::MAX];
+
+        let start = (fragment << 32) + batch_offset as u64;
+        let batch = reader
+            .read_batch(0, [0_u32, 5, 9].as_slice(), reader.schema())
+            .await
+            .unwrap();
+        assert_eq!(
+            &UInt64Array::from_iter_values([start, start + 5, start + 9]),
+            as_primitive_array(&batch["_rowid"])
+        );
+
+        let batch = reader.read_batch(0, 3.., reader.schema()).await.unwrap();
+        assert_eq!(
+            &UInt64Array::from_iter_values(start + 3..start + 10),
+            as_primitive_array(&batch["_rowid"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_take() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("f", DataType::Float32, false),
+            ArrowField::new("s", DataType::Utf8, false),
+            ArrowField::new(
+                "d",
+                DataType::Dictionary(Box::new(DataType::UInt8), Box::new(DataType::Utf8)),
+                false,
+            ),
+        ]);
+        let mut schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/take_test");
+
+        // Write 10 batches.
+        let values = StringArray::from_iter_values(["a", "b", "c", "d", "e", "f", "g"]);
+        let mut batches = vec![];
+        for batch_id in 0..10 {
+            let value_range: Range<i64> = batch_id * 10..batch_id * 10 + 10;
+            let keys = UInt8Array::from_iter_values(value_range.clone().map(|v| (v % 7) as u8));
+            let columns: Vec<ArrayRef> = vec![
+                Arc::new(Int64Array::from_iter(
+                    value_range.clone().collect::<Vec<_>>(),
+                )),
+                Arc::new(Float32Array::from_iter(
+                    value_range.clone().map(|n| n as f32).collect::<Vec<_>>(),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    value_range
+                        .clone()
+                        .map(|n| format!("str-{}", n))
+                        .collect::<Vec<_>>(),
+                )),
+                Arc::new(DictionaryArray::<UInt8Type>::try_new(&keys, &values).unwrap()),
+            ];
+            batches.push(RecordBatch::try_new(Arc::new(arrow_schema.clone()), columns).unwrap());
+        }
+        schema.set_dictionary(&batches[0]).unwrap();
+
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        for batch in batches.iter() {
+            file_writer.write(&[batch.clone()]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let batch = reader
+            .take(&[1, 15, 20, 25, 30, 48, 90], reader.schema())
+            .await
+            .unwrap();
+        let dict_keys = UInt8Array::from_iter_values([1, 1, 6, 4, 2, 6, 6]);
+        assert_eq!(
+            batch,
+            RecordBatch::try_new(
+                batch.schema(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values([1, 15, 20, 25, 30, 48, 90])),
+                    Arc::new(Float32Array::from_iter_values([
+                        1.0, 15.0, 20.0, 25.0, 30.0, 48.0, 90.0
+                    ])),
+                    Arc::new(StringArray::from_iter_values([
+                        "str-1", "str-15", "str-20", "str-25", "str-30", "str-48", "str-90"
+                    ])),
+                    Arc::new(DictionaryArray::try_new(&dict_keys, &values).unwrap()),
+                ]
+            )
+            .unwrap()
+        );
+
+        // The vocabulary of the dictionary column.
+        let dict_field_id = reader.schema().field("d").unwrap().id;
+        let vocabulary = reader.dictionary_values(dict_field_id).unwrap();
+        assert_eq!(vocabulary.len(), 7);
+        assert_eq!(vocabulary.as_ref(), &values);
+        let string_field_id = reader.schema().field("s").unwrap().id;
+        assert!(matches!(
+            reader.dictionary_values(string_field_id),
+            Err(Error::Schema(_))
+        ));
+    }
+
+    async fn test_write_null_string_in_struct(field_nullable: bool) {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "parent",
+            DataType::Struct(ArrowFields::from(vec![ArrowField::new(
+                "str",
+                DataType::Utf8,
+                field_nullable,
+            )])),
+            true,
+        )]));
+
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/null_strings");
+
+        let string_arr = Arc::new(StringArray::from_iter([Some("a"), Some(""), Some("b")]));
+        let struct_arr = Arc::new(StructArray::from(vec![(
+            ArrowField::new("str", DataType::Utf8, field_nullable),
+            string_arr.clone() as ArrayRef,
+        )]));
+        let batch = RecordBatch::try_new(arrow_schema.clone(), vec![struct_arr]).unwrap();
+
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let actual_batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
+
+        if field_nullable {
+            assert_eq!(
+                &StringArray::from_iter(vec![Some("a"), None, Some("b")]),
+                as_string_array(
+                    as_struct_array(actual_batch.column_by_name("parent").unwrap().as_ref())
+                        .column_by_name("str")
+                        .unwrap()
+                        .as_ref()
+                )
+            );
+        } else {
+            assert_eq!(actual_batch, batch);
+        }
+    }
+
+    #[tokio::test]
+    async fn read_nullable_string_in_struct() {
+        test_write_null_string_in_struct(true).await;
+        test_write_null_string_in_struct(false).await;
+    }
+
+    #[tokio::test]
+    async fn test_read_struct_of_list_arrays() {
+        let store = ObjectStore::memory();
+        let path = Path::from("/null_strings");
+
+        let arrow_schema = make_schema_of_list_array();
+        let schema: Schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+        let batches = (0..3)
+            .map(|_| {
+                let struct_array = make_struct_of_list_array(10, 10);
+                RecordBatch::try_new(arrow_schema.clone(), vec![struct_array]).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let batches_ref = batches.iter().collect::<Vec<_>>();
+
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&batches).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let actual_batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        let expected = concat_batches(&arrow_schema, batches_ref).unwrap();
+        assert_eq!(expected, actual_batch);
+    }
+
+    #[tokio::test]
+    async fn test_scan_struct_of_list_arrays() {
+        let store = ObjectStore::memory();
+        let path = Path::from("/null_strings");
+
+        let arrow_schema = make_schema_of_list_array();
+        let struct_array = make_struct_of_list_array(3, 10);
+        let schema: Schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let batch = RecordBatch::try_new(arrow_schema.clone(), vec![struct_array.clone()]).unwrap();
+
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let mut expected_columns: Vec<ArrayRef> = Vec::new();
+        for c in struct_array.columns().iter() {
+            expected_columns.push(c.slice(1, 1));
+        }
+
+        let expected_struct = match arrow_schema.fields[0].data_type() {
+            DataType::Struct(subfields) => subfields
+                .iter()
+                .zip(expected_columns)
+                .map(|(f, d)| (f.as_ref().clone(), d))
+                .collect::<Vec<_>>(),
+            _ => panic!("unexpected field"),
+        };
+
+        let expected_struct_array = StructArray::from(expected_struct);
+        let expected_batch = RecordBatch::from(&StructArray::from(vec![(
+            arrow_schema.fields[0].as_ref().clone(),
+            Arc::new(expected_struct_array) as ArrayRef,
+        )]));
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let params = ReadBatchParams::Range(1..2);
+        let slice_of_batch = reader.read_batch(0, params, reader.schema()).await.unwrap();
+        assert_eq!(expected_batch, slice_of_batch);
+    }
+
+    #[tokio::test]
+    async fn test_take_list_indices_within_batch() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "l",
+            DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+            true,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+        // Lists of different lengths, so that an off-by-some index reads wrong values.
+        let mut list_builder = ListBuilder::new(Int32Builder::new());
+        for i in 0..20 {
+            for j in 0..(i % 4) {
+                list_builder.values().append_value(i * 100 + j);
+            }
+            list_builder.append(true);
+        }
+        let list_arr: ArrayRef = Arc::new(list_builder.finish());
+        let batch = RecordBatch::try_new(arrow_schema.clone(), vec![list_arr.clone()]).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/take_list_indices");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        for indices in [vec![5_u32, 7, 12], vec![12, 5, 7], vec![]] {
+            let actual = reader
+                .read_batch(0, indices.as_slice(), reader.schema())
+                .await
+                .unwrap();
+            let expected = take(
+                list_arr.as_ref(),
+                &UInt32Array::from_iter_values(indices.iter().copied()),
+                None,
+            )
+            .unwrap();
+            assert_eq!(actual.column(0).as_ref(), expected.as_ref(), "{indices:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_struct_validity() {
+        let child = ArrowField::new("i", DataType::Int32, true);
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "s",
+            DataType::Struct(ArrowFields::from(vec![child.clone()])),
+            true,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+        // Rows 2 and 5 are null.
+        let struct_arr = StructArray::from((
+            vec![(
+                child,
+                Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+            )],
+            Buffer::from([0b11011011_u8, 0b11]),
+        ));
+        assert_eq!(struct_arr.null_count(), 2);
+        let batch = RecordBatch::try_new(arrow_schema.clone(), vec![Arc::new(struct_arr)]).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/struct_validity");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let actual = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        let actual_struct = actual.column(0);
+        assert_eq!(actual_struct.null_count(), 2);
+        assert!(actual_struct.is_null(2));
+        assert!(actual_struct.is_null(5));
+        assert_eq!(actual, batch);
+
+        let actual = reader.read_batch(0, 4..8, reader.schema()).await.unwrap();
+        assert_eq!(actual.column(0).null_count(), 1);
+        assert!(actual.column(0).is_null(1));
+
+        let actual = reader
+            .read_batch(0, [1_u32, 2, 5].as_slice(), reader.schema())
+            .await
+            .unwrap();
+        assert_eq!(actual.column(0).null_count(), 2);
+        assert!(actual.column(0).is_valid(0));
+    }
+
+    #[tokio::test]
+    async fn test_read_list_array_tail() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "l",
+            DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+            true,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+        let mut list_builder = ListBuilder::new(Int32Builder::new());
+        for i in 0..10 {
+            for j in 0..i {
+                list_builder.values().append_value(j);
+            }
+            list_builder.append(true);
+        }
+        let list_arr: ArrayRef = Arc::new(list_builder.finish());
+        let batch = RecordBatch::try_new(arrow_schema.clone(), vec![list_arr.clone()]).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/list_tail");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let actual = reader.read_batch(0, 6.., reader.schema()).await.unwrap();
+        assert_eq!(actual.column(0).as_ref(), list_arr.slice(6, 4).as_ref());
+
+        let actual = reader.read_batch(0, ..4, reader.schema()).await.unwrap();
+        assert_eq!(actual.column(0).as_ref(), list_arr.slice(0, 4).as_ref());
+
+        let actual = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        assert_eq!(actual, batch);
+    }
+
+    #[tokio::test]
+    async fn test_read_last_large_list_in_batch() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "ll",
+            DataType::LargeList(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+            false,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        let mut expected = vec![];
+        for b in 0..2 {
+            let mut builder = LargeListBuilder::new(Int32Builder::new());
+            for i in 0..10 {
+                for v in 0..i {
+                    builder.values().append_value(b * 100 + v);
+                }
+                builder.append(true);
+            }
+            let list_arr = Arc::new(builder.finish()) as ArrayRef;
+            let batch = RecordBatch::try_new(arrow_schema.clone(), vec![list_arr.clone()]).unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+            expected.push(list_arr);
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        for (batch_id, list_arr) in expected.iter().enumerate() {
+            let batch_id = batch_id as i32;
+            let last = list_arr.slice(9, 1);
+            let actual = reader
+                .read_batch(batch_id, 9..10, reader.schema())
+                .await
+                .unwrap();
+            assert_eq!(actual.column(0).as_ref(), last.as_ref());
+            let actual = reader
+                .read_batch(batch_id, 9.., reader.schema())
+                .await
+                .unwrap();
+            assert_eq!(actual.column(0).as_ref(), last.as_ref());
+            let actual = reader
+                .read_batch(batch_id, ..10, reader.schema())
+                .await
+                .unwrap();
+            assert_eq!(actual.column(0).as_ref(), list_arr.as_ref());
+        }
+
+        // Reading past the batch boundary does not read into the next page.
+        assert!(matches!(
+            reader.read_batch(0, 10..11, reader.schema()).await,
+            Err(Error::IO(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_cancellation() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int64,
+            false,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        for b in 0..5 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![Arc::new(Int64Array::from_iter_values(b * 10..(b + 1) * 10))],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let token = CancellationToken::new();
+        let reader = FileReader::try_new(&store, &path)
+            .await
+            .unwrap()
+            .with_cancellation(token.clone());
+        let excluded = RoaringBitmap::new();
+        let mut stream = Box::pin(reader.scan_excluding(reader.schema(), &excluded));
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.num_rows(), 10);
+
+        token.cancel();
+        let rest = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            stream.collect::<Vec<_>>(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(rest.len(), 1);
+        assert!(matches!(&rest[0], Err(Error::IO(msg)) if msg == "read cancelled"));
+
+        assert!(matches!(
+            reader.read_range(0..50, reader.schema()).await,
+            Err(Error::IO(_))
+        ));
+        assert!(matches!(
+            reader.take(&[1, 25], reader.schema()).await,
+            Err(Error::IO(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_num_pages() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int64,
+            false,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        for b in 0..5 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![Arc::new(Int64Array::from_iter_values(b * 10..(b + 1) * 10))],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert_eq!(reader.num_pages(0), 5);
+        let positions = reader.page_positions(0);
+        assert_eq!(positions.len(), 5);
+        // Batches are written one after another.
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(reader.num_pages(1), 0);
+        assert!(reader.page_positions(1).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_column() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int64, false),
+            ArrowField::new("s", DataType::Utf8, false),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        for b in 0..5 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(b * 20..(b + 1) * 20)),
+                    Arc::new(StringArray::from_iter_values(
+                        (b * 20..(b + 1) * 20).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let ids = reader
+            .read_column(0, ReadBatchParams::RangeFull)
+            .await
+            .unwrap();
+        assert_eq!(ids.as_ref(), &Int64Array::from_iter_values(0..100));
+
+        let heads = reader.read_column(0, (..2).into()).await.unwrap();
+        assert_eq!(
+            heads.as_ref(),
+            &Int64Array::from_iter_values((0..5).flat_map(|b| [b * 20, b * 20 + 1]))
+        );
+        assert!(matches!(
+            reader.read_column(0, (15..25).into()).await,
+            Err(Error::OutOfRange(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_batch_opts() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, false),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        for b in 0..2 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter((b * 10..(b + 1) * 10).map(|v| {
+                        if v % 3 == 0 {
+                            None
+                        } else {
+                            Some(v)
+                        }
+                    }))),
+                    Arc::new(StringArray::from_iter_values(
+                        (b * 10..(b + 1) * 10).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new_with_fragment(&store, &path, 4, None)
+            .await
+            .unwrap();
+        let options = ReadOptions {
+            projection: Some(schema.project(&["i"]).unwrap()),
+            with_row_id: true,
+            io_parallelism: 2,
+            null_fill: HashMap::from([(0, ScalarValue::Int64(Some(-1)))]),
+        };
+        let batch = reader.read_batch_opts(1, 1..5, &options).await.unwrap();
+        assert_eq!(
+            batch.schema().as_ref(),
+            &ArrowSchema::new(vec![
+                ArrowField::new("i", DataType::Int64, true),
+                row_id_field(),
+            ])
+        );
+        assert_eq!(
+            batch.column(0).as_ref(),
+            &Int64Array::from(vec![11, -1, 13, 14])
+        );
+        assert_eq!(
+            batch.column(1).as_ref(),
+            &UInt64Array::from_iter_values((11..15).map(|v| row_id(4, v)))
+        );
+
+        // The options do not change the reader.
+        let batch = reader.read_batch(1, 1..5, &schema).await.unwrap();
+        assert_eq!(batch.num_columns(), 2);
+        assert_eq!(batch.column(0).null_count(), 1);
+
+        // Default options read the schema of the reader.
+        let batch = reader
+            .read_batch_opts(0, .., &ReadOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(batch.schema().as_ref(), arrow_schema.as_ref());
+    }
+
+    #[tokio::test]
+    async fn test_read_column_by_name() {
+        let arrow_schema = make_schema_of_list_array();
+        let schema: Schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let struct_arr = make_struct_of_list_array(10, 3);
+        let batch =
+            RecordBatch::try_new(arrow_schema.clone(), vec![struct_arr.clone() as ArrayRef])
+                .unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/nested");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let li = reader.read_column_by_name("s.li", 0, ..).await.unwrap();
+        assert_eq!(li.as_ref(), struct_arr.column(0).as_ref());
+        let ls = reader.read_column_by_name("s.ls", 0, 2..5).await.unwrap();
+        assert_eq!(ls.as_ref(), struct_arr.column(1).slice(2, 3).as_ref());
+        let s = reader.read_column_by_name("s", 0, ..).await.unwrap();
+        assert_eq!(s.as_ref(), struct_arr.as_ref() as &dyn Array);
+
+        for name in ["s.missing", "missing", "s.li.missing"] {
+            assert!(matches!(
+                reader.read_column_by_name(name, 0, ..).await,
+                Err(Error::Schema(_))
+            ));
+        }
+    }
+
+    fn make_schema_of_list_array() -> Arc<arrow_schema::Schema> {
+        Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "s",
+            DataType::Struct(ArrowFields::from(vec![
+                ArrowField::new(
+                    "li",
+                    DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                    true,
+                ),
+                ArrowField::new(
+                    "ls",
+                    DataType::List(Arc::new(ArrowField::new("item", DataType::Utf8, true))),
+                    true,
+                ),
+                ArrowField::new(
+                    "ll",
+                    DataType::LargeList(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                    false,
+                ),
+            ])),
+            true,
+        )]))
+    }
+
+    fn make_struct_of_list_array(rows: i32, num_items: i32) -> Arc<StructArray> {
+        let mut li_builder = ListBuilder::new(Int32Builder::new());
+        let mut ls_builder = ListBuilder::new(StringBuilder::new());
+        let ll_value_builder = Int32Builder::new();
+        let mut large_list_builder = LargeListBuilder::new(ll_value_builder);
+        for i in 0..rows {
+            for j in 0..num_items {
+                li_builder.values().append_value(i * 10 + j);
+                ls_builder
+                    .values()
+                    .append_value(format!("str-{}", i * 10 + j));
+                large_list_builder.values().append_value(i * 10 + j);
+            }
+            li_builder.append(true);
+            ls_builder.append(true);
+            large_list_builder.append(true);
+        }
+        Arc::new(StructArray::from(vec![
+            (
+                ArrowField::new(
+                    "li",
+                    DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                    true,
+                ),
+                Arc::new(li_builder.finish()) as ArrayRef,
+            ),
+            (
+                ArrowField::new(
+                    "ls",
+                    DataType::List(Arc::new(ArrowField::new("item", DataType::Utf8, true))),
+                    true,
+                ),
+                Arc::new(ls_builder.finish()) as ArrayRef,
+            ),
+            (
+                ArrowField::new(
+                    "ll",
+                    DataType::LargeList(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                    false,
+                ),
+                Arc::new(large_list_builder.finish()) as ArrayRef,
+            ),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn test_read_struct_of_dictionary_arrays() {
+        let test_dir = tempdir().unwrap();
+
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "s",
+            DataType::Struct(ArrowFields::from(vec![ArrowField::new(
+                "d",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                true,
+            )])),
+            true,
+        )]));
+
+        let mut batches: Vec<RecordBatch> = Vec::new();
+        for _ in 1..2 {
+            let mut dict_builder = StringDictionaryBuilder::<Int32Type>::new();
+            dict_builder.append("a").unwrap();
+            dict_builder.append("b").unwrap();
+            dict_builder.append("c").unwrap();
+            dict_builder.append("d").unwrap();
+
+            let struct_array = Arc::new(StructArray::from(vec![(
+                ArrowField::new(
+                    "d",
+                    DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                    true,
+                ),
+                Arc::new(dict_builder.finish()) as ArrayRef,
+            )]));
+
+            let batch =
+                RecordBatch::try_new(arrow_schema.clone(), vec![struct_array.clone()]).unwrap();
+            batches.push(batch);
+        }
+
+        let test_uri = test_dir.path().to_str().unwrap();
+
+        let batch_buffer = crate::arrow::RecordBatchBuffer::new(batches.clone());
+        let mut batch_reader: Box<dyn RecordBatchReader> = Box::new(batch_buffer);
+        Dataset::write(&mut batch_reader, test_uri, Some(WriteParams::default()))
+            .await
+            .unwrap();
+
+        let _result = scan_dataset(test_uri).await.unwrap();
+
+        assert_eq!(batches, _result);
+    }
+
+    async fn scan_dataset(uri: &str) -> Result<Vec<RecordBatch>> {
+        let results = Dataset::open(uri)
+            .await?
+            .scan()
+            .try_into_stream()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(results)
+    }
+
+    #[tokio::test]
+    async fn test_read_nullable_arrays() {
+        use arrow_array::Array;
+
+        // create a record batch with a null array column
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, false),
+            ArrowField::new("n", DataType::Null, true),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from_iter_values(0..100)),
+            Arc::new(NullArray::new(100)),
+        ];
+        let batch = RecordBatch::try_new(Arc::new(arrow_schema), columns).unwrap();
+
+        // write to a lance file
+        let store = ObjectStore::memory();
+        let path = Path::from("/takes");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        // read the file back
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+
+        async fn read_array_w_params(
+            reader: &FileReader,
+            field: &Field,
+            params: ReadBatchParams,
+        ) -> ArrayRef {
+            let arr = read_array(reader, field, 0, &params)
+                .await
+                .expect("Error reading back the null array from file");
+            arr
+        }
+
+        let arr = read_array_w_params(&reader, &schema.fields[1], ReadBatchParams::RangeFull).await;
+        assert_eq!(100, arr.len());
+        assert_eq!(100, arr.null_count());
+
+        let arr =
+            read_array_w_params(&reader, &schema.fields[1], ReadBatchParams::Range(10..25)).await;
+        assert_eq!(15, arr.len());
+        assert_eq!(15, arr.null_count());
+
+        let arr =
+            read_array_w_params(&reader, &schema.fields[1], ReadBatchParams::RangeFrom(60..)).await;
+        assert_eq!(40, arr.len());
+        assert_eq!(40, arr.null_count());
+
+        let arr =
+            read_array_w_params(&reader, &schema.fields[1], ReadBatchParams::RangeTo(..25)).await;
+        assert_eq!(25, arr.len());
+        assert_eq!(25, arr.null_count());
+
+        let arr = read_array_w_params(
+            &reader,
+            &schema.fields[1],
+            ReadBatchParams::Indices(UInt32Array::from(vec![1, 9, 30, 72])),
+        )
+        .await;
+        assert_eq!(4, arr.len());
+        assert_eq!(4, arr.null_count());
+
+        // raise error if take indices are out of bounds
+        let params = ReadBatchParams::Indices(UInt32Array::from(vec![1, 9, 30, 72, 100]));
+        let arr = read_array(&reader, &schema.fields[1], 0, &params);
+        assert!(arr.await.is_err());
+
+        // raise error if range indices are out of bounds
+        let params = ReadBatchParams::RangeTo(..107);
+        let arr = read_array(&reader, &schema.fields[1], 0, &params);
+        assert!(arr.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_validity() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+            ArrowField::new("n", DataType::Null, true),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from_iter_values(0..20)),
+            Arc::new(StringArray::from_iter((0..20).map(|n| {
+                if n % 3 == 0 {
+                    None
+                } else {
+                    Some(format!("s-{n}"))
+                }
+            }))),
+            Arc::new(NullArray::new(20)),
+        ];
+        let batch = RecordBatch::try_new(Arc::new(arrow_schema), columns).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/validity");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let full_batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        for params in [
+            ReadBatchParams::RangeFull,
+            ReadBatchParams::Range(4..17),
+            ReadBatchParams::Indices(UInt32Array::from(vec![1, 3, 6, 7, 18])),
+        ] {
+            let expected_batch = reader
+                .read_batch(0, params.clone(), reader.schema())
+                .await
+                .unwrap();
+            for field in schema.fields[..2].iter() {
+                let validity = reader
+                    .read_validity(0, field.id, params.clone())
+                    .await
+                    .unwrap();
+                let column = expected_batch.column_by_name(&field.name).unwrap();
+                let expected = (0..column.len())
+                    .map(|i| column.is_valid(i))
+                    .collect::<Vec<_>>();
+                assert_eq!(validity, BooleanArray::from(expected));
+            }
+
+            let validity = reader
+                .read_validity(0, schema.fields[2].id, params.clone())
+                .await
+                .unwrap();
+            assert_eq!(validity.len(), expected_batch.num_rows());
+            assert_eq!(validity.true_count(), 0);
+        }
+
+        let validity = reader.read_validity(0, 1, ..).await.unwrap();
+        let nulls = full_batch.column(1).nulls().unwrap();
+        assert_eq!(validity.values(), nulls.inner());
+    }
+
+    #[tokio::test]
+    async fn test_dictionary_value_counts() {
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
+            "d",
+            DataType::Dictionary(Box::new(DataType::UInt8), Box::new(DataType::Utf8)),
+            false,
+        )]);
+        let mut schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let values = StringArray::from_iter_values(["a", "b", "c", "d"]);
+        let key_values = (0..50).map(|v| (v * v % 3) as u8).collect::<Vec<_>>();
+        let keys = UInt8Array::from(key_values.clone());
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema),
+            vec![Arc::new(
+                DictionaryArray::<UInt8Type>::try_new(&keys, &values).unwrap(),
+            )],
+        )
+        .unwrap();
+        schema.set_dictionary(&batch).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/dict_counts");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let counts = reader
+            .dictionary_value_counts(0, schema.fields[0].id)
+            .await
+            .unwrap();
+
+        let mut expected = vec![0_u64; values.len()];
+        key_values.iter().for_each(|k| expected[*k as usize] += 1);
+        assert_eq!(counts, UInt64Array::from(expected));
+    }
+
+    #[tokio::test]
+    async fn test_column_to_ipc() {
         let arrow_schema = ArrowSchema::new(vec![
             ArrowField::new("i", DataType::Int64, true),
-            ArrowField::new("f", DataType::Float32, false),
+            ArrowField::new("s", DataType::Utf8, true),
         ]);
         let schema = Schema::try_from(&arrow_schema).unwrap();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from_iter_values(0..20)),
+            Arc::new(StringArray::from_iter_values(
+                (0..20).map(|n| format!("s-{n}")),
+            )),
+        ];
+        let batch = RecordBatch::try_new(Arc::new(arrow_schema), columns).unwrap();
 
         let store = ObjectStore::memory();
-        let path = Path::from("/foo");
-
-        // Write 10 batches.
-        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
-        for batch_id in 0..10 {
-            let value_range = batch_id * 10..batch_id * 10 + 10;
-            let columns: Vec<ArrayRef> = vec![
-                Arc::new(Int64Array::from_iter(
-                    value_range.clone().collect::<Vec<_>>(),
-                )),
-                Arc::new(Float32Array::from_iter(
-                    value_range.map(|n| n as f32).collect::<Vec<_>>(),
-                )),
-            ];
-            let batch = RecordBatch::try_new(Arc::new(arrow_schema.clone()), columns).unwrap();
-            file_writer.write(&[batch]).await.unwrap();
-        }
-        file_writer.finish().await.unwrap();
-
-        let fragment = 123;
-        let mut reader = FileReader::try_new_with_fragment(&store, &path, fragment, None)
+        let path = Path::from("/ipc");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
             .await
             .unwrap();
-        reader.with_row_id(true);
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
 
-        for b in 0..10 {
-            let batch = reader.read_batch(b, .., reader.schema()).await.unwrap();
-            let row_ids_col = &batch["_rowid"];
-            // Do the same computation as `compute_row_id`.
-            let start_pos = (fragment << 32) as u64 + 10 * b as u64;
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let field = schema.field("s").unwrap();
+        let buf = reader.column_to_ipc(0, field.id, 5..15).await.unwrap();
 
-            assert_eq!(
-                &UInt64Array::from_iter_values(start_pos..start_pos + 10),
-                as_primitive_array(row_ids_col)
-            );
-        }
+        let ipc_reader = StreamReader::try_new(buf.as_slice(), None).unwrap();
+        assert_eq!(
+            ipc_reader.schema().as_ref(),
+            &ArrowSchema::new(vec![ArrowField::from(field)])
+        );
+        let batches = ipc_reader
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].column(0), &batch["s"].slice(5, 10));
+
+        // Dictionary-encoded columns are serialized as dictionaries.
+        let reader = FileReader::try_new(&store, &path)
+            .await
+            .unwrap()
+            .with_dict_encode(field.id)
+            .unwrap();
+        let buf = reader.column_to_ipc(0, field.id, 5..15).await.unwrap();
+        let ipc_reader = StreamReader::try_new(buf.as_slice(), None).unwrap();
+        assert_eq!(
+            ipc_reader.schema().field(0).data_type(),
+            &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+        );
+        let batches = ipc_reader
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            &cast(batches[0].column(0), &DataType::Utf8).unwrap(),
+            &batch["s"].slice(5, 10)
+        );
     }
 
     #[tokio::test]
-    async fn test_take() {
+    async fn test_take_lists() {
         let arrow_schema = ArrowSchema::new(vec![
-            ArrowField::new("i", DataType::Int64, true),
-            ArrowField::new("f", DataType::Float32, false),
-            ArrowField::new("s", DataType::Utf8, false),
             ArrowField::new(
-                "d",
-                DataType::Dictionary(Box::new(DataType::UInt8), Box::new(DataType::Utf8)),
+                "l",
+                DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                false,
+            ),
+            ArrowField::new(
+                "ll",
+                DataType::LargeList(Arc::new(ArrowField::new("item", DataType::Int32, true))),
                 false,
             ),
         ]);
-        let mut schema = Schema::try_from(&arrow_schema).unwrap();
-
-        let store = ObjectStore::memory();
-        let path = Path::from("/take_test");
 
-        // Write 10 batches.
-        let values = StringArray::from_iter_values(["a", "b", "c", "d", "e", "f", "g"]);
-        let mut batches = vec![];
-        for batch_id in 0..10 {
-            let value_range: Range<i64> = batch_id * 10..batch_id * 10 + 10;
-            let keys = UInt8Array::from_iter_values(value_range.clone().map(|v| (v % 7) as u8));
-            let columns: Vec<ArrayRef> = vec![
-                Arc::new(Int64Array::from_iter(
-                    value_range.clone().collect::<Vec<_>>(),
-                )),
-                Arc::new(Float32Array::from_iter(
-                    value_range.clone().map(|n| n as f32).collect::<Vec<_>>(),
-                )),
-                Arc::new(StringArray::from_iter_values(
-                    value_range
-                        .clone()
-                        .map(|n| format!("str-{}", n))
-                        .collect::<Vec<_>>(),
-                )),
-                Arc::new(DictionaryArray::<UInt8Type>::try_new(&keys, &values).unwrap()),
-            ];
-            batches.push(RecordBatch::try_new(Arc::new(arrow_schema.clone()), columns).unwrap());
+        let value_builder = Int32Builder::new();
+        let mut list_builder = ListBuilder::new(value_builder);
+        let ll_value_builder = Int32Builder::new();
+        let mut large_list_builder = LargeListBuilder::new(ll_value_builder);
+        for i in 0..100 {
+            list_builder.values().append_value(i);
+            large_list_builder.values().append_value(i);
+            if (i + 1) % 10 == 0 {
+                list_builder.append(true);
+                large_list_builder.append(true);
+            }
         }
-        schema.set_dictionary(&batches[0]).unwrap();
+        let list_arr = Arc::new(list_builder.finish());
+        let large_list_arr = Arc::new(large_list_builder.finish());
 
-        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
-        for batch in batches.iter() {
-            file_writer.write(&[batch.clone()]).await.unwrap();
-        }
-        file_writer.finish().await.unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema.clone()),
+            vec![list_arr as ArrayRef, large_list_arr as ArrayRef],
+        )
+        .unwrap();
 
-        let reader = FileReader::try_new(&store, &path).await.unwrap();
-        let batch = reader
-            .take(&[1, 15, 20, 25, 30, 48, 90], reader.schema())
+        // write to a lance file
+        let store = ObjectStore::memory();
+        let path = Path::from("/take_list");
+        let schema: Schema = (&arrow_schema).try_into().unwrap();
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
             .await
             .unwrap();
-        let dict_keys = UInt8Array::from_iter_values([1, 1, 6, 4, 2, 6, 6]);
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        // read the file back
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let actual = reader.take(&[1, 3, 5, 9], &schema).await.unwrap();
+
+        let value_builder = Int32Builder::new();
+        let mut list_builder = ListBuilder::new(value_builder);
+        let ll_value_builder = Int32Builder::new();
+        let mut large_list_builder = LargeListBuilder::new(ll_value_builder);
+        for i in [1, 3, 5, 9] {
+            for j in 0..10 {
+                list_builder.values().append_value(i * 10 + j);
+                large_list_builder.values().append_value(i * 10 + j);
+            }
+            list_builder.append(true);
+            large_list_builder.append(true);
+        }
+        let expected_list = list_builder.finish();
+        let expected_large_list = large_list_builder.finish();
+
+        assert_eq!(actual.column_by_name("l").unwrap().as_ref(), &expected_list);
         assert_eq!(
-            batch,
-            RecordBatch::try_new(
-                batch.schema(),
-                vec![
-                    Arc::new(Int64Array::from_iter_values([1, 15, 20, 25, 30, 48, 90])),
-                    Arc::new(Float32Array::from_iter_values([
-                        1.0, 15.0, 20.0, 25.0, 30.0, 48.0, 90.0
-                    ])),
-                    Arc::new(StringArray::from_iter_values([
-                        "str-1", "str-15", "str-20", "str-25", "str-30", "str-48", "str-90"
-                    ])),
-                    Arc::new(DictionaryArray::try_new(&dict_keys, &values).unwrap()),
-                ]
-            )
-            .unwrap()
+            actual.column_by_name("ll").unwrap().as_ref(),
+            &expected_large_list
         );
     }
 
-    async fn test_write_null_string_in_struct(field_nullable: bool) {
-        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
-            "parent",
-            DataType::Struct(ArrowFields::from(vec![ArrowField::new(
-                "str",
-                DataType::Utf8,
-                field_nullable,
-            )])),
-            true,
-        )]));
-
-        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+    #[tokio::test]
+    async fn test_list_array_with_offsets() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new(
+                "l",
+                DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                false,
+            ),
+            ArrowField::new(
+                "ll",
+                DataType::LargeList(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                false,
+            ),
+        ]);
 
         let store = ObjectStore::memory();
-        let path = Path::from("/null_strings");
+        let path = Path::from("/lists");
 
-        let string_arr = Arc::new(StringArray::from_iter([Some("a"), Some(""), Some("b")]));
-        let struct_arr = Arc::new(StructArray::from(vec![(
-            ArrowField::new("str", DataType::Utf8, field_nullable),
-            string_arr.clone() as ArrayRef,
-        )]));
-        let batch = RecordBatch::try_new(arrow_schema.clone(), vec![struct_arr]).unwrap();
+        let list_array = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), Some(2)]),
+            Some(vec![Some(3), Some(4)]),
+            Some((0..2_000).map(|n| Some(n)).collect::<Vec<_>>()),
+        ])
+        .slice(1, 1);
+        let large_list_array = LargeListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(10), Some(11)]),
+            Some(vec![Some(12), Some(13)]),
+            Some((0..2_000).map(|n| Some(n)).collect::<Vec<_>>()),
+        ])
+        .slice(1, 1);
+
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema.clone()),
+            vec![Arc::new(list_array), Arc::new(large_list_array)],
+        )
+        .unwrap();
 
+        let schema: Schema = (&arrow_schema).try_into().unwrap();
         let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
         file_writer.write(&[batch.clone()]).await.unwrap();
         file_writer.finish().await.unwrap();
 
+        // Make sure the big array was not written to the file
+        let file_size_bytes = store.size(&path).await.unwrap();
+        assert!(file_size_bytes < 1_000);
+
         let reader = FileReader::try_new(&store, &path).await.unwrap();
         let actual_batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        assert_eq!(batch, actual_batch);
+    }
 
-        if field_nullable {
-            assert_eq!(
-                &StringArray::from_iter(vec![Some("a"), None, Some("b")]),
-                as_string_array(
-                    as_struct_array(actual_batch.column_by_name("parent").unwrap().as_ref())
-                        .column_by_name("str")
-                        .unwrap()
-                        .as_ref()
-                )
-            );
-        } else {
-            assert_eq!(actual_batch, batch);
-        }
+    #[tokio::test]
+    async fn test_read_ranges() {
+        // create a record batch with a null array column
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new("i", DataType::Int64, false)]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        let columns: Vec<ArrayRef> = vec![Arc::new(Int64Array::from_iter_values(0..100))];
+        let batch = RecordBatch::try_new(Arc::new(arrow_schema), columns).unwrap();
+
+        // write to a lance file
+        let store = ObjectStore::memory();
+        let path = Path::from("/read_range");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let actual_batch = reader.read_range(7..25, reader.schema()).await.unwrap();
+
+        assert_eq!(
+            actual_batch.column_by_name("i").unwrap().as_ref(),
+            &Int64Array::from_iter_values(7..25)
+        );
     }
 
     #[tokio::test]
-    async fn read_nullable_string_in_struct() {
-        test_write_null_string_in_struct(true).await;
-        test_write_null_string_in_struct(false).await;
-    }
+    async fn test_read_binary_value_range() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "blob",
+            DataType::LargeBinary,
+            true,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let blob = (0..1024).map(|v| (v % 251) as u8).collect::<Vec<_>>();
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![Arc::new(LargeBinaryArray::from_iter(vec![
+                Some(b"small".as_slice()),
+                Some(blob.as_slice()),
+                None,
+            ]))],
+        )
+        .unwrap();
 
-    #[tokio::test]
-    async fn test_read_struct_of_list_arrays() {
         let store = ObjectStore::memory();
-        let path = Path::from("/null_strings");
-
-        let arrow_schema = make_schema_of_list_array();
-        let schema: Schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
-
-        let batches = (0..3)
-            .map(|_| {
-                let struct_array = make_struct_of_list_array(10, 10);
-                RecordBatch::try_new(arrow_schema.clone(), vec![struct_array]).unwrap()
-            })
-            .collect::<Vec<_>>();
-        let batches_ref = batches.iter().collect::<Vec<_>>();
-
+        let path = Path::from("/foo");
         let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
-        file_writer.write(&batches).await.unwrap();
+        file_writer.write(&[batch]).await.unwrap();
         file_writer.finish().await.unwrap();
 
         let reader = FileReader::try_new(&store, &path).await.unwrap();
-        let actual_batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
-        let expected = concat_batches(&arrow_schema, batches_ref).unwrap();
-        assert_eq!(expected, actual_batch);
+        let bytes = reader
+            .read_binary_value_range(0, 0, 1, 10..20)
+            .await
+            .unwrap();
+        assert_eq!(bytes.as_ref(), &blob[10..20]);
+        let bytes = reader.read_binary_value_range(0, 0, 0, 0..5).await.unwrap();
+        assert_eq!(bytes.as_ref(), b"small");
+
+        // Out of bounds.
+        assert!(reader
+            .read_binary_value_range(0, 0, 1, 1000..1030)
+            .await
+            .is_err());
+        assert!(reader.read_binary_value_range(0, 0, 2, 0..1).await.is_err());
+        assert!(matches!(
+            reader.read_binary_value_range(0, 0, 3, 0..1).await,
+            Err(Error::OutOfRange(_))
+        ));
+        assert!(reader.read_binary_value_range(1, 0, 0, 0..1).await.is_err());
     }
 
     #[tokio::test]
-    async fn test_scan_struct_of_list_arrays() {
-        let store = ObjectStore::memory();
-        let path = Path::from("/null_strings");
-
-        let arrow_schema = make_schema_of_list_array();
-        let struct_array = make_struct_of_list_array(3, 10);
-        let schema: Schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
-        let batch = RecordBatch::try_new(arrow_schema.clone(), vec![struct_array.clone()]).unwrap();
+    async fn test_read_batch_with_stats() {
+        let struct_fields = vec![
+            ArrowField::new("a", DataType::Int32, true),
+            ArrowField::new("b", DataType::Utf8, true),
+        ];
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+            ArrowField::new(
+                "st",
+                DataType::Struct(ArrowFields::from(struct_fields.clone())),
+                true,
+            ),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter((0..20).map(|v| {
+                    if v % 3 == 0 {
+                        None
+                    } else {
+                        Some(v)
+                    }
+                }))),
+                Arc::new(StringArray::from_iter((0..20).map(|v| {
+                    if v % 4 == 0 {
+                        None
+                    } else {
+                        Some(format!("s-{v}"))
+                    }
+                }))),
+                Arc::new(StructArray::from(vec![
+                    (
+                        struct_fields[0].clone(),
+                        Arc::new(Int32Array::from_iter((0..20).map(|v| {
+                            if v % 5 == 0 {
+                                None
+                            } else {
+                                Some(v)
+                            }
+                        }))) as ArrayRef,
+                    ),
+                    (
+                        struct_fields[1].clone(),
+                        Arc::new(StringArray::from_iter_values(
+                            (0..20).map(|v| format!("b-{v}")),
+                        )) as ArrayRef,
+                    ),
+                ])),
+            ],
+        )
+        .unwrap();
 
-        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
         file_writer.write(&[batch]).await.unwrap();
         file_writer.finish().await.unwrap();
 
-        let mut expected_columns: Vec<ArrayRef> = Vec::new();
-        for c in struct_array.columns().iter() {
-            expected_columns.push(c.slice(1, 1));
-        }
-
-        let expected_struct = match arrow_schema.fields[0].data_type() {
-            DataType::Struct(subfields) => subfields
-                .iter()
-                .zip(expected_columns)
-                .map(|(f, d)| (f.as_ref().clone(), d))
-                .collect::<Vec<_>>(),
-            _ => panic!("unexpected field"),
-        };
+        let mut reader = FileReader::try_new(&store, &path).await.unwrap();
+        reader.with_row_id(true);
+        let (batch, null_counts) = reader
+            .read_batch_with_stats(0, 2..18, &schema)
+            .await
+            .unwrap();
+        let st = as_struct_array(batch.column(2).as_ref());
+        assert_eq!(
+            null_counts,
+            vec![
+                batch.column(0).null_count(),
+                batch.column(1).null_count(),
+                st.null_count(),
+                st.column(0).null_count(),
+                st.column(1).null_count(),
+            ]
+        );
+        assert_eq!(null_counts, vec![5, 4, 0, 3, 0]);
+    }
 
-        let expected_struct_array = StructArray::from(expected_struct);
-        let expected_batch = RecordBatch::from(&StructArray::from(vec![(
-            arrow_schema.fields[0].as_ref().clone(),
-            Arc::new(expected_struct_array) as ArrayRef,
-        )]));
+    #[tokio::test]
+    async fn test_read_error_kinds() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, false),
+            ArrowField::new("n", DataType::Null, true),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..10)),
+                Arc::new(NullArray::new(10)),
+            ],
+        )
+        .unwrap();
 
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
         let reader = FileReader::try_new(&store, &path).await.unwrap();
-        let params = ReadBatchParams::Range(1..2);
-        let slice_of_batch = reader.read_batch(0, params, reader.schema()).await.unwrap();
-        assert_eq!(expected_batch, slice_of_batch);
-    }
 
-    fn make_schema_of_list_array() -> Arc<arrow_schema::Schema> {
-        Arc::new(ArrowSchema::new(vec![ArrowField::new(
-            "s",
-            DataType::Struct(ArrowFields::from(vec![
-                ArrowField::new(
-                    "li",
-                    DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
-                    true,
-                ),
-                ArrowField::new(
-                    "ls",
-                    DataType::List(Arc::new(ArrowField::new("item", DataType::Utf8, true))),
-                    true,
-                ),
-                ArrowField::new(
-                    "ll",
-                    DataType::LargeList(Arc::new(ArrowField::new("item", DataType::Int32, true))),
-                    false,
-                ),
-            ])),
-            true,
-        )]))
+        assert!(matches!(
+            reader.read_batch(1, .., &schema).await,
+            Err(Error::NotFound(_))
+        ));
+        let nulls = schema.project(&["n"]).unwrap();
+        assert!(matches!(
+            reader.read_batch(0, 5..20, &nulls).await,
+            Err(Error::OutOfRange(_))
+        ));
+        assert!(matches!(
+            reader.read_batch(0, [3_u32, 10].as_slice(), &nulls).await,
+            Err(Error::OutOfRange(_))
+        ));
     }
 
-    fn make_struct_of_list_array(rows: i32, num_items: i32) -> Arc<StructArray> {
-        let mut li_builder = ListBuilder::new(Int32Builder::new());
-        let mut ls_builder = ListBuilder::new(StringBuilder::new());
-        let ll_value_builder = Int32Builder::new();
-        let mut large_list_builder = LargeListBuilder::new(ll_value_builder);
-        for i in 0..rows {
-            for j in 0..num_items {
-                li_builder.values().append_value(i * 10 + j);
-                ls_builder
-                    .values()
-                    .append_value(format!("str-{}", i * 10 + j));
-                large_list_builder.values().append_value(i * 10 + j);
-            }
-            li_builder.append(true);
-            ls_builder.append(true);
-            large_list_builder.append(true);
+    #[tokio::test]
+    async fn test_read_batch_parallelism() {
+        let mut arrow_fields = vec![];
+        let mut columns: Vec<ArrayRef> = vec![];
+        for i in 0..16 {
+            arrow_fields.push(ArrowField::new(format!("i{i}"), DataType::Int64, true));
+            columns.push(Arc::new(Int64Array::from_iter((0..100).map(|v| {
+                if v % 7 == i {
+                    None
+                } else {
+                    Some(v * i)
+                }
+            }))));
+            arrow_fields.push(ArrowField::new(format!("s{i}"), DataType::Utf8, false));
+            columns.push(Arc::new(StringArray::from_iter_values(
+                (0..100).map(|v| format!("s-{i}-{v}")),
+            )));
         }
-        Arc::new(StructArray::from(vec![
-            (
-                ArrowField::new(
-                    "li",
-                    DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
-                    true,
-                ),
-                Arc::new(li_builder.finish()) as ArrayRef,
-            ),
+        let struct_fields = vec![
+            ArrowField::new("a", DataType::Int32, true),
+            ArrowField::new("b", DataType::Utf8, true),
+        ];
+        arrow_fields.push(ArrowField::new(
+            "st",
+            DataType::Struct(ArrowFields::from(struct_fields.clone())),
+            true,
+        ));
+        columns.push(Arc::new(StructArray::from(vec![
             (
-                ArrowField::new(
-                    "ls",
-                    DataType::List(Arc::new(ArrowField::new("item", DataType::Utf8, true))),
-                    true,
-                ),
-                Arc::new(ls_builder.finish()) as ArrayRef,
+                struct_fields[0].clone(),
+                Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
             ),
             (
-                ArrowField::new(
-                    "ll",
-                    DataType::LargeList(Arc::new(ArrowField::new("item", DataType::Int32, true))),
-                    false,
-                ),
-                Arc::new(large_list_builder.finish()) as ArrayRef,
+                struct_fields[1].clone(),
+                Arc::new(StringArray::from_iter_values(
+                    (0..100).map(|v| format!("b-{v}")),
+                )) as ArrayRef,
             ),
-        ]))
+        ])));
+        arrow_fields.push(ArrowField::new(
+            "l",
+            DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+            true,
+        ));
+        columns.push(Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(
+            (0..100).map(|v| Some((0..v % 5).map(Some).collect::<Vec<_>>())),
+        )));
+        let arrow_schema = Arc::new(ArrowSchema::new(arrow_fields));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let batch = RecordBatch::try_new(arrow_schema.clone(), columns).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let sequential = FileReader::try_new(&store, &path)
+            .await
+            .unwrap()
+            .with_read_parallelism(1);
+        let concurrent = FileReader::try_new(&store, &path)
+            .await
+            .unwrap()
+            .with_read_parallelism(8);
+        for params in [
+            ReadBatchParams::RangeFull,
+            ReadBatchParams::Range(10..50),
+            ReadBatchParams::from([1_u32, 20, 99].as_slice()),
+        ] {
+            let expected = sequential.read_batch(0, &params, &schema).await.unwrap();
+            let actual = concurrent.read_batch(0, &params, &schema).await.unwrap();
+            assert_eq!(expected, actual);
+        }
+        assert_eq!(concurrent.read_batch(0, .., &schema).await.unwrap(), batch);
     }
 
     #[tokio::test]
-    async fn test_read_struct_of_dictionary_arrays() {
-        let test_dir = tempdir().unwrap();
-
-        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
-            "s",
-            DataType::Struct(ArrowFields::from(vec![ArrowField::new(
-                "d",
-                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+    async fn test_read_offsets() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, false),
+            ArrowField::new(
+                "l",
+                DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
                 true,
-            )])),
-            true,
-        )]));
+            ),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        for b in 0..2 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from_iter_values(b * 20..(b + 1) * 20)),
+                    Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(
+                        (b * 20..(b + 1) * 20)
+                            .map(|v| Some((0..v % 4).map(Some).collect::<Vec<_>>())),
+                    )),
+                    Arc::new(StringArray::from_iter_values(
+                        (b * 20..(b + 1) * 20).map(|v| "x".repeat(v as usize % 5)),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
 
-        let mut batches: Vec<RecordBatch> = Vec::new();
-        for _ in 1..2 {
-            let mut dict_builder = StringDictionaryBuilder::<Int32Type>::new();
-            dict_builder.append("a").unwrap();
-            dict_builder.append("b").unwrap();
-            dict_builder.append("c").unwrap();
-            dict_builder.append("d").unwrap();
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let lengths = |offsets: &Int64Array| {
+            offsets
+                .values()
+                .windows(2)
+                .map(|w| w[1] - w[0])
+                .collect::<Vec<_>>()
+        };
+        let offsets = reader.read_offsets(1, 1, 5..10).await.unwrap();
+        assert_eq!(offsets.len(), 6);
+        assert_eq!(
+            lengths(&offsets),
+            (25..30).map(|v| v % 4).collect::<Vec<i64>>()
+        );
+        let offsets = reader.read_offsets(1, 0, ..).await.unwrap();
+        assert_eq!(
+            lengths(&offsets),
+            (0..20).map(|v| v % 4).collect::<Vec<i64>>()
+        );
 
-            let struct_array = Arc::new(StructArray::from(vec![(
-                ArrowField::new(
-                    "d",
-                    DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
-                    true,
-                ),
-                Arc::new(dict_builder.finish()) as ArrayRef,
-            )]));
+        let offsets = reader.read_offsets(3, 1, ..8).await.unwrap();
+        assert_eq!(
+            lengths(&offsets),
+            (20..28).map(|v| v % 5).collect::<Vec<i64>>()
+        );
 
-            let batch =
-                RecordBatch::try_new(arrow_schema.clone(), vec![struct_array.clone()]).unwrap();
-            batches.push(batch);
+        assert!(matches!(
+            reader.read_offsets(0, 0, ..).await,
+            Err(Error::Schema(_))
+        ));
+        assert!(matches!(
+            reader.read_offsets(1, 0, 15..21).await,
+            Err(Error::OutOfRange(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_scan_coalesced() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, false),
+            ArrowField::new("s", DataType::Utf8, false),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        for b in 0..10 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(b * 10..(b + 1) * 10)),
+                    Arc::new(StringArray::from_iter_values(
+                        (b * 10..(b + 1) * 10).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
         }
+        file_writer.finish().await.unwrap();
 
-        let test_uri = test_dir.path().to_str().unwrap();
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert_eq!(reader.num_batches(), 10);
+        let batches = reader
+            .scan_coalesced(&schema, 35)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![35, 35, 30]
+        );
+        let batch = concat_batches(&arrow_schema, &batches).unwrap();
+        assert_eq!(
+            batch.column(0).as_ref(),
+            &Int64Array::from_iter_values(0..100)
+        );
+        assert_eq!(
+            batch.column(1).as_ref(),
+            &StringArray::from_iter_values((0..100).map(|v| format!("s-{v}")))
+        );
+    }
 
-        let batch_buffer = crate::arrow::RecordBatchBuffer::new(batches.clone());
-        let mut batch_reader: Box<dyn RecordBatchReader> = Box::new(batch_buffer);
-        Dataset::write(&mut batch_reader, test_uri, Some(WriteParams::default()))
+    #[tokio::test]
+    async fn test_scan_excluding() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, false),
+            ArrowField::new("s", DataType::Utf8, false),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
             .await
             .unwrap();
+        for b in 0..5 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(b * 10..(b + 1) * 10)),
+                    Arc::new(StringArray::from_iter_values(
+                        (b * 10..(b + 1) * 10).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
 
-        let _result = scan_dataset(test_uri).await.unwrap();
+        let mut reader = FileReader::try_new_with_fragment(&store, &path, 2, None)
+            .await
+            .unwrap();
+        reader.with_row_id(true);
+        let excluded = RoaringBitmap::from_iter([3_u32, 17, 42]);
+        let batches = reader
+            .scan_excluding(&schema, &excluded)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![9, 9, 10, 10, 9]
+        );
 
-        assert_eq!(batches, _result);
-    }
+        let expected = (0..50_u32).filter(|v| !excluded.contains(*v));
+        let batch = concat_batches(&batches[0].schema(), &batches).unwrap();
+        assert_eq!(
+            batch.column_by_name("i").unwrap().as_ref(),
+            &Int64Array::from_iter_values(expected.clone().map(|v| v as i64))
+        );
+        assert_eq!(
+            batch.column_by_name("s").unwrap().as_ref(),
+            &StringArray::from_iter_values(expected.clone().map(|v| format!("s-{v}")))
+        );
+        assert_eq!(
+            batch.column_by_name(ROW_ID_FIELD_NAME).unwrap().as_ref(),
+            &UInt64Array::from_iter_values(expected.map(|v| row_id(2, v)))
+        );
 
-    async fn scan_dataset(uri: &str) -> Result<Vec<RecordBatch>> {
-        let results = Dataset::open(uri)
-            .await?
-            .scan()
-            .try_into_stream()
-            .await?
+        // A batch with all its rows excluded is empty.
+        let excluded = RoaringBitmap::from_iter(10_u32..20);
+        let batches = reader
+            .scan_excluding(&schema, &excluded)
             .try_collect::<Vec<_>>()
-            .await?;
-        Ok(results)
+            .await
+            .unwrap();
+        assert_eq!(batches[1].num_rows(), 0);
+        assert_eq!(batches[1].schema(), batches[0].schema());
     }
 
     #[tokio::test]
-    async fn test_read_nullable_arrays() {
-        use arrow_array::Array;
-
-        // create a record batch with a null array column
-        let arrow_schema = ArrowSchema::new(vec![
+    async fn test_take_stream() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
             ArrowField::new("i", DataType::Int64, false),
-            ArrowField::new("n", DataType::Null, true),
-        ]);
-        let schema = Schema::try_from(&arrow_schema).unwrap();
-        let columns: Vec<ArrayRef> = vec![
-            Arc::new(Int64Array::from_iter_values(0..100)),
-            Arc::new(NullArray::new(100)),
-        ];
-        let batch = RecordBatch::try_new(Arc::new(arrow_schema), columns).unwrap();
-
-        // write to a lance file
+            ArrowField::new("s", DataType::Utf8, false),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
         let store = ObjectStore::memory();
-        let path = Path::from("/takes");
+        let path = Path::from("/foo");
         let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
             .await
             .unwrap();
-        file_writer.write(&[batch]).await.unwrap();
+        for b in 0..3 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(b * 40..(b + 1) * 40)),
+                    Arc::new(StringArray::from_iter_values(
+                        (b * 40..(b + 1) * 40).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
         file_writer.finish().await.unwrap();
 
-        // read the file back
         let reader = FileReader::try_new(&store, &path).await.unwrap();
+        // Unsorted indices across all the batches.
+        let indices = (0..50).map(|i| (i * 37) % 120).collect::<Vec<u32>>();
+        let batches = reader
+            .take_stream(&indices, &schema, 16)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![16, 16, 16, 2]
+        );
+        let batch = concat_batches(&arrow_schema, &batches).unwrap();
+        assert_eq!(
+            batch.column(0).as_ref(),
+            &Int64Array::from_iter_values(indices.iter().map(|&i| i as i64))
+        );
+        assert_eq!(
+            batch.column(1).as_ref(),
+            &StringArray::from_iter_values(indices.iter().map(|i| format!("s-{i}")))
+        );
 
-        async fn read_array_w_params(
-            reader: &FileReader,
-            field: &Field,
-            params: ReadBatchParams,
-        ) -> ArrayRef {
-            let arr = read_array(reader, field, 0, &params)
-                .await
-                .expect("Error reading back the null array from file");
-            arr
+        // Sorted indices give the same batches as take.
+        let sorted = (10..60).collect::<Vec<u32>>();
+        let batches = reader
+            .take_stream(&sorted, &schema, 16)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            concat_batches(&arrow_schema, &batches).unwrap(),
+            reader.take(&sorted, &schema).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_count_matching() {
+        use arrow_ord::comparison::gt_scalar;
+
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, false),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        for b in 0..4 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter((b * 25..(b + 1) * 25).map(|v| {
+                        if v == 70 {
+                            None
+                        } else {
+                            Some(v)
+                        }
+                    }))),
+                    Arc::new(StringArray::from_iter_values(
+                        (b * 25..(b + 1) * 25).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
         }
+        file_writer.finish().await.unwrap();
 
-        let arr = read_array_w_params(&reader, &schema.fields[1], ReadBatchParams::RangeFull).await;
-        assert_eq!(100, arr.len());
-        assert_eq!(100, arr.null_count());
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let count = reader
+            .count_matching(0, |arr| {
+                gt_scalar(as_primitive_array::<Int64Type>(arr.as_ref()), 50).unwrap()
+            })
+            .await
+            .unwrap();
+        // 51..100, without the null 70.
+        assert_eq!(count, 48);
+        assert!(reader
+            .count_matching(5, |arr| BooleanArray::from(vec![true; arr.len()]))
+            .await
+            .is_err());
+    }
 
-        let arr =
-            read_array_w_params(&reader, &schema.fields[1], ReadBatchParams::Range(10..25)).await;
-        assert_eq!(15, arr.len());
-        assert_eq!(15, arr.null_count());
+    #[tokio::test]
+    async fn test_open_many() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, false),
+            ArrowField::new("s", DataType::Utf8, false),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let mut paths = vec![];
+        for f in 0..3 {
+            let path = Path::from(format!("/data/{f}.lance"));
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(f * 10..f * 10 + 10)),
+                    Arc::new(StringArray::from_iter_values(
+                        (f * 10..f * 10 + 10).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+                .await
+                .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+            file_writer.finish().await.unwrap();
+            paths.push(path);
+        }
 
-        let arr =
-            read_array_w_params(&reader, &schema.fields[1], ReadBatchParams::RangeFrom(60..)).await;
-        assert_eq!(40, arr.len());
-        assert_eq!(40, arr.null_count());
+        // The schema of the shared manifest differs from the one in the files, so the
+        // readers prove that the manifests of the files are not read.
+        let projection = schema.project(&["i"]).unwrap();
+        let manifest = Arc::new(Manifest::new(&projection, Arc::new(vec![])));
+        let readers = FileReader::open_many(&store, &paths, manifest)
+            .await
+            .unwrap();
+        assert_eq!(readers.len(), 3);
+        for (f, reader) in readers.iter().enumerate() {
+            assert_eq!(reader.schema(), &projection);
+            let batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
+            let f = f as i64;
+            assert_eq!(
+                batch.column(0).as_ref(),
+                &Int64Array::from_iter_values(f * 10..f * 10 + 10)
+            );
+        }
 
-        let arr =
-            read_array_w_params(&reader, &schema.fields[1], ReadBatchParams::RangeTo(..25)).await;
-        assert_eq!(25, arr.len());
-        assert_eq!(25, arr.null_count());
+        assert!(FileReader::open_many(
+            &store,
+            &[],
+            Arc::new(Manifest::new(&schema, Arc::new(vec![])))
+        )
+        .await
+        .unwrap()
+        .is_empty());
+    }
 
-        let arr = read_array_w_params(
-            &reader,
-            &schema.fields[1],
-            ReadBatchParams::Indices(UInt32Array::from(vec![1, 9, 30, 72])),
+    #[tokio::test]
+    async fn test_try_new_from_object_reader() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int64,
+            false,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![Arc::new(Int64Array::from_iter_values(0..100))],
         )
-        .await;
-        assert_eq!(4, arr.len());
-        assert_eq!(4, arr.null_count());
+        .unwrap();
 
-        // raise error if take indices are out of bounds
-        let params = ReadBatchParams::Indices(UInt32Array::from(vec![1, 9, 30, 72, 100]));
-        let arr = read_array(&reader, &schema.fields[1], 0, &params);
-        assert!(arr.await.is_err());
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
 
-        // raise error if range indices are out of bounds
-        let params = ReadBatchParams::RangeTo(..107);
-        let arr = read_array(&reader, &schema.fields[1], 0, &params);
-        assert!(arr.await.is_err());
+        let object_reader: Arc<dyn ObjectReader> = store.open(&path).await.unwrap().into();
+        let reader = FileReader::try_new_from_object_reader(object_reader.clone(), 3, None)
+            .await
+            .unwrap();
+        assert_eq!(reader.schema(), &schema);
+        assert_eq!(reader.block_size(), object_reader.block_size());
+        assert_eq!(reader.read_batch(0, .., &schema).await.unwrap(), batch);
+
+        let manifest = Manifest::new(&schema, Arc::new(vec![]));
+        let mut reader = FileReader::try_new_from_object_reader(object_reader, 3, Some(&manifest))
+            .await
+            .unwrap();
+        reader.with_row_id(true);
+        let batch = reader.read_batch(0, 10..12, &schema).await.unwrap();
+        assert_eq!(
+            batch.column_by_name(ROW_ID_FIELD_NAME).unwrap().as_ref(),
+            &UInt64Array::from_iter_values([(3 << 32) + 10, (3 << 32) + 11])
+        );
     }
 
     #[tokio::test]
-    async fn test_take_lists() {
-        let arrow_schema = ArrowSchema::new(vec![
-            ArrowField::new(
-                "l",
-                DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
-                false,
-            ),
+    async fn test_read_raw_fixed_stride() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, false),
             ArrowField::new(
-                "ll",
-                DataType::LargeList(Arc::new(ArrowField::new("item", DataType::Int32, true))),
-                false,
+                "vec",
+                DataType::FixedSizeList(
+                    Arc::new(ArrowField::new("item", DataType::Float32, true)),
+                    4,
+                ),
+                true,
             ),
-        ]);
+            ArrowField::new("b", DataType::Boolean, true),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let values = Float32Array::from_iter_values((0..40).map(|v| v as f32));
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..10)),
+                Arc::new(FixedSizeListArray::try_new_from_values(values, 4).unwrap()),
+                Arc::new(BooleanArray::from_iter((0..10).map(|v| Some(v % 2 == 0)))),
+            ],
+        )
+        .unwrap();
 
-        let value_builder = Int32Builder::new();
-        let mut list_builder = ListBuilder::new(value_builder);
-        let ll_value_builder = Int32Builder::new();
-        let mut large_list_builder = LargeListBuilder::new(ll_value_builder);
-        for i in 0..100 {
-            list_builder.values().append_value(i);
-            large_list_builder.values().append_value(i);
-            if (i + 1) % 10 == 0 {
-                list_builder.append(true);
-                large_list_builder.append(true);
-            }
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let buf = reader.read_raw_fixed_stride(0, 0, ..).await.unwrap();
+        assert_eq!(buf.len(), 10 * 4);
+        assert_eq!(buf.typed_data::<i32>(), (0..10).collect::<Vec<_>>());
+
+        let buf = reader.read_raw_fixed_stride(1, 0, 2..5).await.unwrap();
+        assert_eq!(buf.len(), 3 * 4 * 4);
+        assert_eq!(
+            buf.typed_data::<f32>(),
+            (8..20).map(|v| v as f32).collect::<Vec<_>>()
+        );
+        let buf = reader
+            .read_raw_fixed_stride(1, 0, [1_u32, 7].as_slice())
+            .await
+            .unwrap();
+        assert_eq!(buf.len(), 2 * 4 * 4);
+        assert_eq!(buf.typed_data::<f32>()[4..], [28.0, 29.0, 30.0, 31.0]);
+
+        // Boolean is bit-packed.
+        assert!(reader.read_raw_fixed_stride(3, 0, ..).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_batches_with_same_projection() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, false),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        for b in 0..5 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(b * 10..(b + 1) * 10)),
+                    Arc::new(StringArray::from_iter_values(
+                        (b * 10..(b + 1) * 10).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
         }
-        let list_arr = Arc::new(list_builder.finish());
-        let large_list_arr = Arc::new(large_list_builder.finish());
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let projection = schema.project(&["s"]).unwrap();
+        let mut schemas = vec![];
+        for b in 0..5 {
+            let batch = reader.read_batch(b, .., &projection).await.unwrap();
+            assert_eq!(
+                batch.column(0).as_ref(),
+                &StringArray::from_iter_values(
+                    (b as i64 * 10..(b as i64 + 1) * 10).map(|v| format!("s-{v}"))
+                )
+            );
+            schemas.push(batch.schema());
+        }
+        assert!(schemas.iter().all(|s| Arc::ptr_eq(s, &schemas[0])));
+        assert_eq!(
+            schemas[0].as_ref(),
+            &ArrowSchema::new(vec![ArrowField::new("s", DataType::Utf8, true)])
+        );
+
+        // A different projection is not served from the previous one.
+        let batch = reader.read_batch(1, .., &schema).await.unwrap();
+        assert_eq!(batch.schema(), arrow_schema);
+        let batch = reader.read_batch(2, .., &projection).await.unwrap();
+        assert_eq!(batch.num_columns(), 1);
+        assert_eq!(batch.schema().field(0).name(), "s");
+    }
 
+    #[tokio::test]
+    async fn test_read_batch_in_projection_order() {
+        let struct_fields = ArrowFields::from(vec![
+            ArrowField::new("x", DataType::Int32, false),
+            ArrowField::new("y", DataType::Utf8, false),
+        ]);
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int64, false),
+            ArrowField::new("b", DataType::Utf8, false),
+            ArrowField::new("s", DataType::Struct(struct_fields.clone()), false),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
         let batch = RecordBatch::try_new(
-            Arc::new(arrow_schema.clone()),
-            vec![list_arr as ArrayRef, large_list_arr as ArrayRef],
+            arrow_schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..10)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..10).map(|v| format!("b-{v}")),
+                )),
+                Arc::new(StructArray::from(vec![
+                    (
+                        struct_fields[0].as_ref().clone(),
+                        Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+                    ),
+                    (
+                        struct_fields[1].as_ref().clone(),
+                        Arc::new(StringArray::from_iter_values(
+                            (0..10).map(|v| format!("y-{v}")),
+                        )) as ArrayRef,
+                    ),
+                ])),
+            ],
         )
         .unwrap();
 
-        // write to a lance file
         let store = ObjectStore::memory();
-        let path = Path::from("/take_list");
-        let schema: Schema = (&arrow_schema).try_into().unwrap();
+        let path = Path::from("/foo");
         let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
             .await
             .unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        // Reverse of the file order, including the struct children.
+        let projection = schema.project(&["s.y", "s.x", "b", "a"]).unwrap();
+        let actual = reader.read_batch(0, .., &projection).await.unwrap();
+
+        let reversed_struct_fields = ArrowFields::from(vec![
+            struct_fields[1].as_ref().clone(),
+            struct_fields[0].as_ref().clone(),
+        ]);
+        let expected_schema = ArrowSchema::new(vec![
+            ArrowField::new("s", DataType::Struct(reversed_struct_fields), false),
+            ArrowField::new("b", DataType::Utf8, false),
+            ArrowField::new("a", DataType::Int64, false),
+        ]);
+        assert_eq!(actual.schema().as_ref(), &expected_schema);
+        let s = as_struct_array(actual.column(0));
+        assert_eq!(
+            s.column(0).as_ref(),
+            &StringArray::from_iter_values((0..10).map(|v| format!("y-{v}")))
+        );
+        assert_eq!(s.column(1).as_ref(), &Int32Array::from_iter_values(0..10));
+        assert_eq!(actual.column(1), batch.column(1));
+        assert_eq!(actual.column(2), batch.column(0));
+    }
+
+    #[tokio::test]
+    async fn test_read_with_null_fill() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), None, Some(3), None])),
+                Arc::new(StringArray::from(vec![Some("a"), None, None, Some("d")])),
+            ],
+        )
+        .unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
         file_writer.write(&[batch]).await.unwrap();
         file_writer.finish().await.unwrap();
 
-        // read the file back
+        let reader = FileReader::try_new(&store, &path)
+            .await
+            .unwrap()
+            .with_null_fill(0, ScalarValue::Int64(Some(-1)))
+            .unwrap();
+        let actual = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        let filled = actual.column_by_name("i").unwrap();
+        assert_eq!(filled.null_count(), 0);
+        assert!(filled.nulls().is_none());
+        assert_eq!(filled.as_ref(), &Int64Array::from(vec![1, -1, 3, -1]));
+        // Other columns keep their nulls.
+        assert_eq!(actual.column_by_name("s").unwrap().null_count(), 2);
+
+        let reopened = reader.reopen().await.unwrap();
+        let actual = reopened.read_batch(0, .., reopened.schema()).await.unwrap();
+        assert_eq!(
+            actual.column_by_name("i").unwrap().as_ref(),
+            &Int64Array::from(vec![1, -1, 3, -1])
+        );
+
         let reader = FileReader::try_new(&store, &path).await.unwrap();
-        let actual = reader.take(&[1, 3, 5, 9], &schema).await.unwrap();
+        assert!(matches!(
+            reader.with_null_fill(0, ScalarValue::Int32(Some(-1))),
+            Err(Error::Schema(_))
+        ));
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert!(reader.with_null_fill(0, ScalarValue::Int64(None)).is_err());
+    }
 
-        let value_builder = Int32Builder::new();
-        let mut list_builder = ListBuilder::new(value_builder);
-        let ll_value_builder = Int32Builder::new();
-        let mut large_list_builder = LargeListBuilder::new(ll_value_builder);
-        for i in [1, 3, 5, 9] {
-            for j in 0..10 {
-                list_builder.values().append_value(i * 10 + j);
-                large_list_builder.values().append_value(i * 10 + j);
-            }
-            list_builder.append(true);
-            large_list_builder.append(true);
-        }
-        let expected_list = list_builder.finish();
-        let expected_large_list = large_list_builder.finish();
+    #[tokio::test]
+    async fn test_read_with_dict_encode() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, false),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let values = (0..30)
+            .map(|i| match i % 4 {
+                0 => Some("red"),
+                1 => Some("green"),
+                2 => None,
+                _ => Some("blue"),
+            })
+            .collect::<Vec<_>>();
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..30)),
+                Arc::new(StringArray::from(values.clone())),
+            ],
+        )
+        .unwrap();
 
-        assert_eq!(actual.column_by_name("l").unwrap().as_ref(), &expected_list);
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path)
+            .await
+            .unwrap()
+            .with_dict_encode(1)
+            .unwrap();
+        let dict_type = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        assert_eq!(reader.output_schema().field(1).data_type(), &dict_type);
+
+        let actual = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        assert_eq!(actual.schema().field(1).data_type(), &dict_type);
+        let dict = actual
+            .column(1)
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int32Type>>()
+            .unwrap();
+        let mut vocab = as_string_array(dict.values())
+            .iter()
+            .map(|v| v.unwrap())
+            .collect::<Vec<_>>();
+        vocab.sort_unstable();
+        assert_eq!(vocab, vec!["blue", "green", "red"]);
+        assert_eq!(dict.null_count(), 7);
         assert_eq!(
-            actual.column_by_name("ll").unwrap().as_ref(),
-            &expected_large_list
+            cast(actual.column(1), &DataType::Utf8).unwrap().as_ref(),
+            &StringArray::from(values)
         );
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert!(matches!(reader.with_dict_encode(0), Err(Error::Schema(_))));
     }
 
     #[tokio::test]
-    async fn test_list_array_with_offsets() {
-        let arrow_schema = ArrowSchema::new(vec![
-            ArrowField::new(
-                "l",
-                DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
-                false,
-            ),
-            ArrowField::new(
-                "ll",
-                DataType::LargeList(Arc::new(ArrowField::new("item", DataType::Int32, true))),
-                false,
-            ),
-        ]);
+    async fn test_read_utf8_as_large_utf8() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "s",
+            DataType::Utf8,
+            true,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        // An empty value in a nullable column is read back as null, so none is written.
+        let values = vec![Some("a"), None, Some("ccc"), Some("dd")];
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![Arc::new(StringArray::from(values.clone()))],
+        )
+        .unwrap();
 
         let store = ObjectStore::memory();
-        let path = Path::from("/lists");
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.finish().await.unwrap();
 
-        let list_array = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
-            Some(vec![Some(1), Some(2)]),
-            Some(vec![Some(3), Some(4)]),
-            Some((0..2_000).map(|n| Some(n)).collect::<Vec<_>>()),
-        ])
-        .slice(1, 1);
-        let large_list_array = LargeListArray::from_iter_primitive::<Int32Type, _, _>(vec![
-            Some(vec![Some(10), Some(11)]),
-            Some(vec![Some(12), Some(13)]),
-            Some((0..2_000).map(|n| Some(n)).collect::<Vec<_>>()),
-        ])
-        .slice(1, 1);
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let projection = reader
+            .schema()
+            .project_by_schema(&ArrowSchema::new(vec![ArrowField::new(
+                "s",
+                DataType::LargeUtf8,
+                true,
+            )]))
+            .unwrap();
+        let actual = reader.read_batch(0, .., &projection).await.unwrap();
+        assert_eq!(actual.schema().field(0).data_type(), &DataType::LargeUtf8);
+        assert_eq!(
+            actual.column(0).as_ref(),
+            &LargeStringArray::from(values.clone())
+        );
+        let actual = reader.read_batch(0, 1..3, &projection).await.unwrap();
+        assert_eq!(
+            actual.column(0).as_ref(),
+            &LargeStringArray::from(values[1..3].to_vec())
+        );
+
+        // Incompatible types are still rejected.
+        assert!(reader
+            .schema()
+            .project_by_schema(&ArrowSchema::new(vec![ArrowField::new(
+                "s",
+                DataType::Binary,
+                true,
+            )]))
+            .is_err());
+    }
 
+    #[tokio::test]
+    async fn test_read_utf8_and_large_utf8_alternating() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "s",
+            DataType::Utf8,
+            true,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let values = vec![Some("a"), None, Some("ccc")];
         let batch = RecordBatch::try_new(
-            Arc::new(arrow_schema.clone()),
-            vec![Arc::new(list_array), Arc::new(large_list_array)],
+            arrow_schema.clone(),
+            vec![Arc::new(StringArray::from(values.clone()))],
         )
         .unwrap();
 
-        let schema: Schema = (&arrow_schema).try_into().unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
         let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
-        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.write(&[batch]).await.unwrap();
         file_writer.finish().await.unwrap();
 
-        // Make sure the big array was not written to the file
-        let file_size_bytes = store.size(&path).await.unwrap();
-        assert!(file_size_bytes < 1_000);
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let large = reader
+            .schema()
+            .project_by_schema(&ArrowSchema::new(vec![ArrowField::new(
+                "s",
+                DataType::LargeUtf8,
+                true,
+            )]))
+            .unwrap();
+        // The projections have the same field ids, but not the same types.
+        for _ in 0..2 {
+            let actual = reader.read_batch(0, .., reader.schema()).await.unwrap();
+            assert_eq!(
+                actual.column(0).as_ref(),
+                &StringArray::from(values.clone())
+            );
+            let actual = reader.read_batch(0, .., &large).await.unwrap();
+            assert_eq!(
+                actual.column(0).as_ref(),
+                &LargeStringArray::from(values.clone())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_with_stored_projection() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, false),
+            ArrowField::new("s", DataType::Utf8, false),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        for b in 0..2 {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(b * 10..(b + 1) * 10)),
+                    Arc::new(StringArray::from_iter_values(
+                        (b * 10..(b + 1) * 10).map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path)
+            .await
+            .unwrap()
+            .project(schema.project(&["s"]).unwrap())
+            .unwrap();
+        let expected_schema = ArrowSchema::new(vec![ArrowField::new("s", DataType::Utf8, false)]);
+
+        let batch = reader.read_batch_projected(1, ..).await.unwrap();
+        assert_eq!(batch.schema().as_ref(), &expected_schema);
+        assert_eq!(
+            batch.column(0).as_ref(),
+            &StringArray::from_iter_values((10..20).map(|v| format!("s-{v}")))
+        );
+        let batch = reader.read_range_projected(5..15).await.unwrap();
+        assert_eq!(batch.schema().as_ref(), &expected_schema);
+        assert_eq!(batch.num_rows(), 10);
+        let batch = reader.take_projected(&[3, 12]).await.unwrap();
+        assert_eq!(
+            batch.column(0).as_ref(),
+            &StringArray::from_iter_values(["s-3", "s-12"])
+        );
 
+        // The projection must keep the field ids of the file.
+        let mut other = schema.project(&["s"]).unwrap();
+        other.fields[0].id = 0;
         let reader = FileReader::try_new(&store, &path).await.unwrap();
-        let actual_batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
-        assert_eq!(batch, actual_batch);
+        assert!(matches!(reader.project(other), Err(Error::Schema(_))));
     }
 
     #[tokio::test]
-    async fn test_read_ranges() {
-        // create a record batch with a null array column
-        let arrow_schema = ArrowSchema::new(vec![ArrowField::new("i", DataType::Int64, false)]);
-        let schema = Schema::try_from(&arrow_schema).unwrap();
-        let columns: Vec<ArrayRef> = vec![Arc::new(Int64Array::from_iter_values(0..100))];
-        let batch = RecordBatch::try_new(Arc::new(arrow_schema), columns).unwrap();
+    async fn test_map_rows_to_batches() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int64,
+            false,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
 
-        // write to a lance file
         let store = ObjectStore::memory();
-        let path = Path::from("/read_range");
+        let path = Path::from("/foo");
         let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
-        file_writer.write(&[batch]).await.unwrap();
+        for start in [0, 10, 20] {
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![Arc::new(Int64Array::from_iter_values(start..start + 10))],
+            )
+            .unwrap();
+            file_writer.write(&[batch]).await.unwrap();
+        }
         file_writer.finish().await.unwrap();
 
         let reader = FileReader::try_new(&store, &path).await.unwrap();
-        let actual_batch = reader.read_range(7..25, reader.schema()).await.unwrap();
+        assert_eq!(
+            reader.range_to_batches(5..15).unwrap(),
+            vec![(0, 5..10), (1, 0..5)]
+        );
+        assert!(reader.range_to_batches(25..35).is_err());
 
         assert_eq!(
-            actual_batch.column_by_name("i").unwrap().as_ref(),
-            &Int64Array::from_iter_values(7..25)
+            reader.group_indices_to_batches(&[12, 3, 19, 8]),
+            vec![
+                BatchOffsets {
+                    batch_id: 0,
+                    offsets: vec![3, 8]
+                },
+                BatchOffsets {
+                    batch_id: 1,
+                    offsets: vec![2, 9]
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_roundtrip_field_metadata_in_manifest() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, false),
+            ArrowField::new(
+                "emb",
+                DataType::FixedSizeList(
+                    Arc::new(ArrowField::new("item", DataType::Float32, true)),
+                    8,
+                ),
+                true,
+            )
+            .with_metadata(HashMap::from([("model".to_string(), "clip".to_string())])),
+        ]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        let store = ObjectStore::memory();
+        let path = Path::from("/manifest");
+        let mut writer = store.create(&path).await.unwrap();
+        let mut manifest = Manifest::new(&schema, Arc::new(vec![]));
+        let pos = write_manifest(&mut writer, &mut manifest, None)
+            .await
+            .unwrap();
+        writer.write_magics(pos).await.unwrap();
+        writer.shutdown().await.unwrap();
+
+        let manifest = read_manifest(&store, &path).await.unwrap();
+        let field = manifest.schema.field("emb").unwrap();
+        assert_eq!(
+            field.metadata,
+            HashMap::from([("model".to_string(), "clip".to_string())])
         );
+        assert!(manifest.schema.field("i").unwrap().metadata.is_empty());
+        assert_eq!(manifest.schema.to_arrow(), arrow_schema);
     }
 
     async fn test_roundtrip_manifest(prefix_size: usize, manifest_min_size: usize) {
@@ -1250,9 +5208,44 @@ mod tests {
 
         assert_eq!(manifest, roundtripped_manifest);
 
+        let manifest_bytes = read_manifest_bytes(&store, &path).await.unwrap();
+        let decoded = Manifest::from(pb::Manifest::decode(manifest_bytes).unwrap());
+        assert_eq!(decoded, roundtripped_manifest);
+
         store.inner.delete(&path).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_read_corrupt_manifest() {
+        let store = ObjectStore::memory();
+
+        // Manifest position beyond the end of the file.
+        let path = Path::from("/bad_position");
+        let mut writer = store.create(&path).await.unwrap();
+        writer.write_all(&[0_u8; 64]).await.unwrap();
+        writer.write_magics(1024).await.unwrap();
+        writer.shutdown().await.unwrap();
+        let err = read_manifest(&store, &path).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::IO(msg) if msg.contains("manifest position 1024")),
+            "{err}"
+        );
+
+        // Recorded manifest length larger than the rest of the file.
+        let path = Path::from("/bad_length");
+        let mut writer = store.create(&path).await.unwrap();
+        writer.write_all(&[0_u8; 16]).await.unwrap();
+        writer.write_u32_le(1_000_000).await.unwrap();
+        writer.write_all(&[0_u8; 32]).await.unwrap();
+        writer.write_magics(16).await.unwrap();
+        writer.shutdown().await.unwrap();
+        let err = read_manifest_bytes(&store, &path).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::IO(msg) if msg.contains("manifest length 1000000")),
+            "{err}"
+        );
+    }
+
     #[tokio::test]
     async fn test_read_large_manifest() {
         test_roundtrip_manifest(0, 100_000).await;
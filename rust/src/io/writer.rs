@@ -12,26 +12,41 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
+use arrow::row::{RowConverter, SortField};
 use arrow_array::builder::{ArrayBuilder, PrimitiveBuilder};
-use arrow_array::cast::{as_large_list_array, as_list_array, as_struct_array};
-use arrow_array::types::{Int32Type, Int64Type};
-use arrow_array::{Array, ArrayRef, RecordBatch, StructArray};
+use arrow_array::cast::{
+    as_fixed_size_list_array, as_generic_binary_array, as_large_list_array, as_largestring_array,
+    as_list_array, as_primitive_array, as_string_array, as_struct_array,
+};
+use arrow_array::types::{Int32Type, Int64Type, UInt32Type};
+use arrow_array::{
+    make_array, new_empty_array, Array, ArrayRef, BooleanArray, RecordBatch, RecordBatchReader,
+    StructArray, UInt32Array, UInt64Array,
+};
 use arrow_buffer::ArrowNativeType;
+use arrow_cast::cast::cast;
 use arrow_schema::DataType;
+use arrow_select::{concat::concat, take::take};
 use async_recursion::async_recursion;
 use object_store::path::Path;
+use prost::Message;
+use tokio::io::AsyncWriteExt;
 
 use crate::arrow::*;
 use crate::datatypes::{Field, Schema};
 use crate::encodings::dictionary::DictionaryEncoder;
 use crate::encodings::{binary::BinaryEncoder, plain::PlainEncoder, Encoder, Encoding};
-use crate::format::{pb, Index, Manifest, Metadata, PageInfo, PageTable};
+use crate::format::{
+    pb, ColumnStats, Index, Manifest, Metadata, PageChecksum, PageChecksums, PageInfo, PageTable,
+    Statistics, MAGIC,
+};
 use crate::io::object_writer::ObjectWriter;
 use crate::{Error, Result};
 
-use super::ObjectStore;
+use super::{FileReader, ObjectStore};
 
 /// Write manifest to an open file.
 pub async fn write_manifest(
@@ -88,6 +103,374 @@ pub async fn write_manifest(
     writer.write_struct(manifest).await
 }
 
+/// Estimate the size of the Lance file written from `reader`, without writing it.
+///
+/// Each [RecordBatch] from `reader` is counted as one batch on disk, as if it was passed to
+/// [`FileWriter::write`] on its own. The estimate includes the data pages, the page table,
+/// the column statistics, the manifest with the dictionary values, and the metadata. The
+/// page checksums, written by [`FileWriter::with_page_checksums`], are not included.
+///
+/// The dictionary values are taken from `schema` if set, otherwise from the largest
+/// dictionary of the batches.
+pub fn estimate_file_size(schema: &Schema, reader: &mut dyn RecordBatchReader) -> Result<usize> {
+    let mut data_size = 0;
+    let mut dictionaries: HashMap<i32, usize> = HashMap::new();
+    let mut metadata = Metadata::default();
+    let mut statistics = Statistics::default();
+    for batch in reader {
+        let batch = batch?;
+        let batch_id = metadata.num_batches() as i32;
+        for field in schema.fields.iter() {
+            let column = batch.column_by_name(&field.name).ok_or_else(|| {
+                Error::Schema(format!(
+                    "estimate_file_size: column {} does not exist in the batch",
+                    field.name
+                ))
+            })?;
+            data_size += estimate_array_size(
+                field,
+                column.as_ref(),
+                &mut dictionaries,
+                &mut statistics,
+                batch_id,
+            )?;
+        }
+        metadata.push_batch_length(batch.num_rows() as i32);
+    }
+
+    // Page table: position and length of each page, as i64.
+    let num_columns = schema.max_field_id().map_or(0, |id| id as usize + 1);
+    let page_table_size = num_columns * metadata.num_batches() * 2 * 8;
+
+    let statistics_size = if statistics.is_empty() {
+        0
+    } else {
+        metadata.statistics_position = Some(data_size + page_table_size);
+        4 + pb::Statistics::from(&statistics).encoded_len()
+    };
+
+    let mut dictionary_size = 0;
+    for field in schema.fields.iter() {
+        dictionary_size += estimate_dictionary_size(field, &dictionaries);
+    }
+    let manifest = Manifest::new(schema, Arc::new(vec![]));
+    let manifest_size = 4 + pb::Manifest::from(&manifest).encoded_len();
+
+    metadata.page_table_position = data_size;
+    metadata.manifest_position =
+        Some(data_size + page_table_size + statistics_size + dictionary_size);
+    let metadata_size = 4 + pb::Metadata::from(&metadata).encoded_len();
+
+    // Footer: metadata position, major and minor versions, and the magic.
+    let footer_size = 8 + 2 + 2 + MAGIC.len();
+
+    Ok(data_size
+        + page_table_size
+        + statistics_size
+        + dictionary_size
+        + manifest_size
+        + metadata_size
+        + footer_size)
+}
+
+/// Size of the dictionary values of `field` and its children, which are stored in the manifest.
+fn estimate_dictionary_size(field: &Field, dictionaries: &HashMap<i32, usize>) -> usize {
+    let own_size = if field.data_type().is_dictionary() {
+        match field.dictionary.as_ref().and_then(|d| d.values.as_ref()) {
+            Some(values) => value_array_size(values.as_ref()),
+            None => dictionaries.get(&field.id).copied().unwrap_or_default(),
+        }
+    } else {
+        0
+    };
+    own_size
+        + field
+            .children
+            .iter()
+            .map(|c| estimate_dictionary_size(c, dictionaries))
+            .sum::<usize>()
+}
+
+/// Number of bytes to store a dictionary value array.
+fn value_array_size(values: &dyn Array) -> usize {
+    let data_type = values.data_type();
+    if data_type.is_binary_like() {
+        binary_values_len(values) + (values.len() + 1) * 8
+    } else if data_type == &DataType::Boolean {
+        (values.len() + 7) / 8
+    } else {
+        values.len() * data_type.byte_width()
+    }
+}
+
+/// Number of value bytes referenced by a binary-like array.
+fn binary_values_len(arr: &dyn Array) -> usize {
+    fn offsets_len<O: ArrowNativeType>(offsets: &[O]) -> usize {
+        offsets[offsets.len() - 1].as_usize() - offsets[0].as_usize()
+    }
+    match arr.data_type() {
+        DataType::Utf8 => offsets_len(as_string_array(arr).value_offsets()),
+        DataType::LargeUtf8 => offsets_len(as_largestring_array(arr).value_offsets()),
+        DataType::Binary => offsets_len(as_generic_binary_array::<i32>(arr).value_offsets()),
+        DataType::LargeBinary => offsets_len(as_generic_binary_array::<i64>(arr).value_offsets()),
+        _ => 0,
+    }
+}
+
+/// Estimate the bytes of the data pages to write `arr`, following the layout of [FileWriter].
+///
+/// The statistics of the pages are collected to `statistics`, as [FileWriter] does.
+fn estimate_array_size(
+    field: &Field,
+    arr: &dyn Array,
+    dictionaries: &mut HashMap<i32, usize>,
+    statistics: &mut Statistics,
+    batch_id: i32,
+) -> Result<usize> {
+    if ColumnStats::is_supported(arr.data_type()) {
+        statistics.set(field.id, batch_id, &ColumnStats::try_new(&[arr])?)?;
+    }
+    estimate_page_size(field, arr, dictionaries, statistics, batch_id)
+}
+
+/// Same as [estimate_array_size], without collecting the statistics of `arr` itself.
+fn estimate_page_size(
+    field: &Field,
+    arr: &dyn Array,
+    dictionaries: &mut HashMap<i32, usize>,
+    statistics: &mut Statistics,
+    batch_id: i32,
+) -> Result<usize> {
+    let size = match arr.data_type() {
+        DataType::Null => 0,
+        DataType::Boolean => (arr.len() + 7) / 8,
+        DataType::FixedSizeList(_, _) => {
+            let list_arr = as_fixed_size_list_array(arr);
+            let value_length = list_arr.value_length() as usize;
+            let values = list_arr.values().slice(
+                list_arr.value_offset(0) as usize,
+                list_arr.len() * value_length,
+            );
+            // The values are written in the page of the list, without statistics.
+            estimate_page_size(field, values.as_ref(), dictionaries, statistics, batch_id)?
+        }
+        dt if dt.is_fixed_stride() => arr.len() * dt.byte_width(),
+        dt if dt.is_binary_like() => binary_values_len(arr) + (arr.len() + 1) * 8,
+        DataType::Dictionary(key_type, _) => {
+            let values = make_array(arr.data().child_data()[0].clone());
+            let values_size = value_array_size(values.as_ref());
+            let entry = dictionaries.entry(field.id).or_default();
+            *entry = std::cmp::max(*entry, values_size);
+            arr.len() * key_type.byte_width()
+        }
+        DataType::Struct(_) => {
+            let struct_arr = as_struct_array(arr);
+            let mut size = if field.nullable {
+                (arr.len() + 7) / 8
+            } else {
+                0
+            };
+            for child in field.children.iter() {
+                let column = struct_arr.column_by_name(&child.name).ok_or_else(|| {
+                    Error::Schema(format!(
+                        "estimate_file_size: column {} does not exist in array: {:?}",
+                        child.name,
+                        struct_arr.data_type()
+                    ))
+                })?;
+                size += estimate_array_size(
+                    child,
+                    column.as_ref(),
+                    dictionaries,
+                    statistics,
+                    batch_id,
+                )?;
+            }
+            size
+        }
+        DataType::List(_) => {
+            let list_arr = as_list_array(arr);
+            let offsets = list_arr.value_offsets();
+            let start = offsets[0].as_usize();
+            let end = offsets[offsets.len() - 1].as_usize();
+            let values = list_arr.values().slice(start, end - start);
+            (arr.len() + 1) * 4
+                + estimate_array_size(
+                    &field.children[0],
+                    values.as_ref(),
+                    dictionaries,
+                    statistics,
+                    batch_id,
+                )?
+        }
+        DataType::LargeList(_) => {
+            let list_arr = as_large_list_array(arr);
+            let offsets = list_arr.value_offsets();
+            let start = offsets[0].as_usize();
+            let end = offsets[offsets.len() - 1].as_usize();
+            let values = list_arr.values().slice(start, end - start);
+            (arr.len() + 1) * 8
+                + estimate_array_size(
+                    &field.children[0],
+                    values.as_ref(),
+                    dictionaries,
+                    statistics,
+                    batch_id,
+                )?
+        }
+        data_type => {
+            return Err(Error::Schema(format!(
+                "estimate_file_size: unsupported data type: {data_type}"
+            )))
+        }
+    };
+    Ok(size)
+}
+
+/// Remap the keys of the dictionary arrays to the dictionary values of `field`.
+///
+/// The values that are not in the dictionary yet are appended to it, so that all the
+/// batches of a file share one dictionary, which is written to the manifest.
+fn unify_dictionary(field: &mut Field, arrs: &[&dyn Array]) -> Result<Vec<ArrayRef>> {
+    let dict_type = field.data_type();
+    let DataType::Dictionary(key_type, value_type) = &dict_type else {
+        return Err(Error::Schema(format!(
+            "Field {} is not a dictionary field: {}",
+            field.name, dict_type
+        )));
+    };
+
+    let existing = field
+        .dictionary
+        .as_ref()
+        .and_then(|d| d.values.clone())
+        .unwrap_or_else(|| new_empty_array(value_type));
+    let converter = RowConverter::new(vec![SortField::new(value_type.as_ref().clone())])?;
+    // Built on the first array whose dictionary differs from `existing`.
+    let mut lookup: Option<HashMap<Vec<u8>, u64>> = None;
+
+    let mut values = vec![existing.clone()];
+    let mut num_values = existing.len() as u64;
+    let mut keys_per_array = Vec::with_capacity(arrs.len());
+    for arr in arrs {
+        let DataType::Dictionary(arr_key_type, _) = arr.data_type() else {
+            return Err(Error::Schema(format!(
+                "Field {}: {} is not a dictionary array",
+                field.name,
+                arr.data_type()
+            )));
+        };
+        let dict_values = make_array(arr.data().child_data()[0].clone());
+        // Most batches reuse the dictionary, whose keys are kept as is.
+        if arr.data_type() == &dict_type && dict_values.data() == existing.data() {
+            keys_per_array.push(None);
+            continue;
+        }
+
+        if lookup.is_none() {
+            let rows = converter.convert_columns(&[existing.clone()])?;
+            let mut existing_lookup = HashMap::new();
+            for (i, row) in rows.iter().enumerate() {
+                existing_lookup
+                    .entry(row.as_ref().to_vec())
+                    .or_insert(i as u64);
+            }
+            lookup = Some(existing_lookup);
+        }
+        let lookup = lookup.as_mut().unwrap();
+
+        // Map each key of the dictionary of `arr` to a key of the unified dictionary,
+        // so only the dictionary values are converted to rows.
+        let dict_values = cast(&dict_values, value_type)?;
+        let rows = converter.convert_columns(&[dict_values.clone()])?;
+        let mut new_values = vec![];
+        let key_map = (0..dict_values.len())
+            .map(|i| {
+                if dict_values.is_null(i) {
+                    return None;
+                }
+                let key = lookup
+                    .entry(rows.row(i).as_ref().to_vec())
+                    .or_insert_with(|| {
+                        new_values.push(i as u32);
+                        num_values += 1;
+                        num_values - 1
+                    });
+                Some(*key)
+            })
+            .collect::<UInt64Array>();
+        if !new_values.is_empty() {
+            values.push(take(
+                dict_values.as_ref(),
+                &UInt32Array::from(new_values),
+                None,
+            )?);
+        }
+
+        let keys = make_array(
+            arr.data()
+                .clone()
+                .into_builder()
+                .data_type(arr_key_type.as_ref().clone())
+                .child_data(vec![])
+                .build()?,
+        );
+        let keys = cast(&keys, &DataType::UInt32)?;
+        keys_per_array.push(Some(take(
+            &key_map,
+            as_primitive_array::<UInt32Type>(keys.as_ref()),
+            None,
+        )?));
+    }
+
+    if num_values > max_dictionary_size(key_type) {
+        return Err(Error::Schema(format!(
+            "Field {}: {} dictionary values do not fit in key type {}",
+            field.name, num_values, key_type
+        )));
+    }
+    let values = if values.len() == 1 {
+        existing
+    } else {
+        concat(&values.iter().map(|v| v.as_ref()).collect::<Vec<_>>())?
+    };
+    field.set_dictionary_values(&values);
+
+    arrs.iter()
+        .zip(keys_per_array)
+        .map(|(arr, keys)| {
+            let Some(keys) = keys else {
+                return Ok(make_array(arr.data().clone()));
+            };
+            let keys = cast(&keys, key_type)?;
+            let data = keys
+                .data()
+                .clone()
+                .into_builder()
+                .data_type(dict_type.clone())
+                .child_data(vec![values.data().clone()])
+                .build()?;
+            Ok(make_array(data))
+        })
+        .collect()
+}
+
+/// The max number of dictionary values that can be addressed by `key_type`.
+fn max_dictionary_size(key_type: &DataType) -> u64 {
+    match key_type {
+        DataType::Int8 => i8::MAX as u64 + 1,
+        DataType::Int16 => i16::MAX as u64 + 1,
+        DataType::Int32 => i32::MAX as u64 + 1,
+        DataType::UInt8 => u8::MAX as u64 + 1,
+        DataType::UInt16 => u16::MAX as u64 + 1,
+        DataType::UInt32 => u32::MAX as u64 + 1,
+        _ => u64::MAX,
+    }
+}
+
+/// Number of bytes copied per request when re-opening a file for append.
+const APPEND_COPY_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
 /// [FileWriter] writes Arrow [RecordBatch] to one Lance file.
 ///
 /// ```ignored
@@ -107,6 +490,23 @@ pub struct FileWriter {
     batch_id: i32,
     page_table: PageTable,
     metadata: Metadata,
+
+    /// Max number of rows in one Lance batch, if set.
+    max_rows_per_batch: Option<usize>,
+
+    /// Rows waiting to be grouped into a batch of `max_rows_per_batch` rows.
+    pending: VecDeque<RecordBatch>,
+
+    /// Checksums of the written pages, if enabled.
+    page_checksums: Option<PageChecksums>,
+
+    /// Statistics of the written columns.
+    statistics: Statistics,
+
+    /// Whether the file is neither finished nor aborted, to catch writers dropped
+    /// before [`FileWriter::finish`].
+    #[cfg(debug_assertions)]
+    unfinished: bool,
 }
 
 impl FileWriter {
@@ -122,14 +522,157 @@ impl FileWriter {
             batch_id: 0,
             page_table: PageTable::default(),
             metadata: Metadata::default(),
+            max_rows_per_batch: None,
+            pending: VecDeque::new(),
+            page_checksums: None,
+            statistics: Statistics::default(),
+            #[cfg(debug_assertions)]
+            unfinished: true,
         })
     }
 
+    /// Compute a CRC32C checksum of each page, which is stored in the file and
+    /// can be verified by [`FileReader::with_verify_checksums`].
+    ///
+    /// Only the pages written after this call are checksummed.
+    pub fn with_page_checksums(mut self, enabled: bool) -> Self {
+        if !enabled {
+            self.page_checksums = None;
+        } else if self.page_checksums.is_none() {
+            self.page_checksums = Some(PageChecksums::default());
+        }
+        self
+    }
+
+    /// Open an existing Lance file to append more batches to it.
+    ///
+    /// `schema` must be logically equal to the schema of the file. The batches are written
+    /// with the field ids and dictionaries of the existing file.
+    ///
+    /// Object stores can not append to an object in place, so the data pages of the existing
+    /// file are copied to a new object at the same path, keeping their positions. The page
+    /// table, manifest and metadata are rewritten at the tail on [`Self::finish`]. The existing
+    /// file is left intact until then.
+    ///
+    /// If the existing file stores page checksums, the new pages are checksummed as well.
+    pub async fn open_for_append(
+        object_store: &ObjectStore,
+        path: &Path,
+        schema: &Schema,
+    ) -> Result<FileWriter> {
+        let reader = FileReader::try_new(object_store, path).await?;
+        if !reader.schema().logically_equal(schema) {
+            return Err(Error::Schema(format!(
+                "FileWriter::open_for_append: schema mismatch, file has: {}, got: {}",
+                reader.schema(),
+                schema
+            )));
+        }
+        let (file_schema, mut metadata, page_table, page_checksums, statistics) =
+            reader.into_parts();
+
+        // Copy the data pages, which are all stored before the page table.
+        let mut object_writer = object_store.create(path).await?;
+        let data_end = metadata.page_table_position;
+        let mut offset = 0;
+        while offset < data_end {
+            let end = std::cmp::min(offset + APPEND_COPY_CHUNK_SIZE, data_end);
+            let bytes = object_store.inner.get_range(path, offset..end).await?;
+            object_writer.write_all(&bytes).await?;
+            offset = end;
+        }
+        metadata.manifest_position = None;
+        metadata.page_checksums_position = None;
+        metadata.statistics_position = None;
+
+        Ok(Self {
+            object_writer,
+            schema: file_schema,
+            batch_id: metadata.num_batches() as i32,
+            page_table,
+            metadata,
+            max_rows_per_batch: None,
+            pending: VecDeque::new(),
+            page_checksums,
+            statistics: statistics.unwrap_or_default(),
+            #[cfg(debug_assertions)]
+            unfinished: true,
+        })
+    }
+
+    /// Group the written rows into Lance batches of at most `max_rows` rows.
+    ///
+    /// Large input batches are split, and small ones are coalesced until `max_rows`
+    /// rows are buffered. The remaining rows are written as the last batch in [`Self::finish`].
+    ///
+    /// By default, each call to [`Self::write`] produces exactly one batch.
+    pub fn with_max_rows_per_batch(mut self, max_rows: usize) -> Self {
+        assert!(max_rows > 0, "max_rows_per_batch must be positive");
+        self.max_rows_per_batch = Some(max_rows);
+        self
+    }
+
+    /// Store the top-level column `column` with `encoding` instead of the default
+    /// encoding of its data type.
+    ///
+    /// For example, a high-cardinality dictionary column of strings can be stored
+    /// with [`Encoding::VarBinary`]. It is then written and read back as plain strings.
+    ///
+    /// Must be called before any batch is written.
+    pub fn with_column_encoding(mut self, column: &str, encoding: Encoding) -> Result<Self> {
+        if self.batch_id > 0 {
+            return Err(Error::IO(
+                "FileWriter: can not change encodings after writing batches".to_string(),
+            ));
+        }
+        let field = self
+            .schema
+            .fields
+            .iter_mut()
+            .find(|f| f.name == column)
+            .ok_or_else(|| Error::Schema(format!("FileWriter: column {column} does not exist")))?;
+        field.set_encoding(encoding)?;
+        Ok(self)
+    }
+
     /// Write a [RecordBatch] to the open file.
-    /// All RecordBatch will be treated as one RecordBatch on disk
+    /// All RecordBatch will be treated as one RecordBatch on disk, unless
+    /// [`Self::with_max_rows_per_batch`] is set.
     ///
     /// Returns [Err] if the schema does not match with the batch.
     pub async fn write(&mut self, batches: &[RecordBatch]) -> Result<()> {
+        let Some(max_rows) = self.max_rows_per_batch else {
+            return self.write_batch(batches).await;
+        };
+
+        self.pending.extend(batches.iter().cloned());
+        while self.num_pending_rows() >= max_rows {
+            let mut chunk = vec![];
+            let mut num_rows = 0;
+            while num_rows < max_rows {
+                let batch = self.pending.pop_front().unwrap();
+                let needed = max_rows - num_rows;
+                if batch.num_rows() > needed {
+                    chunk.push(batch.slice(0, needed));
+                    self.pending
+                        .push_front(batch.slice(needed, batch.num_rows() - needed));
+                    num_rows += needed;
+                } else {
+                    num_rows += batch.num_rows();
+                    chunk.push(batch);
+                }
+            }
+            self.write_batch(&chunk).await?;
+        }
+        Ok(())
+    }
+
+    fn num_pending_rows(&self) -> usize {
+        self.pending.iter().map(|b| b.num_rows()).sum()
+    }
+
+    /// Write `batches` as one batch on disk.
+    async fn write_batch(&mut self, batches: &[RecordBatch]) -> Result<()> {
         // Copy a list of fields to avoid borrow checker error.
         let fields = self
             .schema
@@ -141,13 +684,21 @@ impl FileWriter {
             let arrs = batches
                 .iter()
                 .map(|batch| {
-                    batch.column_by_name(&field.name).ok_or_else(|| {
+                    let arr = batch.column_by_name(&field.name).ok_or_else(|| {
                         Error::IO(format!("FileWriter::write: Field {} not found", field.name))
-                    })
+                    })?;
+                    // Dictionary columns configured without dictionary are stored as values.
+                    let data_type = field.data_type();
+                    if arr.data_type().is_dictionary() && !data_type.is_dictionary() {
+                        Ok(cast(arr, &data_type)?)
+                    } else {
+                        Ok(arr.clone())
+                    }
                 })
                 .collect::<Result<Vec<_>>>()?;
 
-            self.write_array(field, &arrs).await?;
+            self.write_array(field, &arrs.iter().collect::<Vec<_>>())
+                .await?;
         }
         let batch_length = batches.iter().map(|b| b.num_rows() as i32).sum();
         self.metadata.push_batch_length(batch_length);
@@ -156,25 +707,55 @@ impl FileWriter {
     }
 
     pub async fn finish(&mut self) -> Result<()> {
+        #[cfg(debug_assertions)]
+        {
+            self.unfinished = false;
+        }
+        if self.num_pending_rows() > 0 {
+            let batches = Vec::from_iter(self.pending.drain(..));
+            self.write_batch(&batches).await?;
+        }
         self.write_footer().await?;
         self.object_writer.shutdown().await
     }
 
-    /// Total records written in this file.
+    /// Discard the file being written.
+    ///
+    /// Nothing is written to the path. If the writer was opened with
+    /// [`FileWriter::open_for_append`], the existing file is left unchanged.
+    pub async fn abort(mut self) -> Result<()> {
+        #[cfg(debug_assertions)]
+        {
+            self.unfinished = false;
+        }
+        self.object_writer.abort().await
+    }
+
+    /// Total records written in this file, including the rows buffered for the next batch.
     pub fn len(&self) -> usize {
-        self.metadata.len()
+        self.metadata.len() + self.num_pending_rows()
     }
 
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
+    /// Schema of the file, with the dictionaries extended by the written batches.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
     #[async_recursion]
     async fn write_array(&mut self, field: &Field, arrs: &[&ArrayRef]) -> Result<()> {
         assert!(!arrs.is_empty());
         let data_type = arrs[0].data_type();
         let arrs_ref = arrs.iter().map(|a| a.as_ref()).collect::<Vec<_>>();
 
+        if ColumnStats::is_supported(data_type) {
+            let stats = ColumnStats::try_new(arrs_ref.as_slice())?;
+            self.statistics.set(field.id, self.batch_id, &stats)?;
+        }
+
         match data_type {
             DataType::Null => self.write_null_array(field, arrs_ref.as_slice()).await,
             dt if dt.is_fixed_stride() => {
@@ -208,6 +789,27 @@ impl FileWriter {
         }
     }
 
+    /// Mark the beginning of a page, and returns its file position.
+    fn begin_page(&mut self) -> usize {
+        if self.page_checksums.is_some() {
+            self.object_writer.start_checksum();
+        }
+        self.object_writer.tell()
+    }
+
+    /// Record the page of `field_id` in the current batch, which was written from `start`.
+    fn finish_page(&mut self, field_id: i32, start: usize, page_info: PageInfo) {
+        self.page_table.set(field_id, self.batch_id, page_info);
+        if let Some(checksums) = self.page_checksums.as_mut() {
+            let checksum = PageChecksum {
+                position: start,
+                length: self.object_writer.tell() - start,
+                crc32c: self.object_writer.take_checksum().unwrap_or_default(),
+            };
+            checksums.set(field_id, self.batch_id, checksum);
+        }
+    }
+
     async fn write_null_array(&mut self, field: &Field, arrs: &[&dyn Array]) -> Result<()> {
         let arrs_length: i32 = arrs.iter().map(|a| a.len() as i32).sum();
         let page_info = PageInfo::new(self.object_writer.tell(), arrs_length as usize);
@@ -221,22 +823,24 @@ impl FileWriter {
         assert!(!arrs.is_empty());
         let data_type = arrs[0].data_type();
 
+        let start = self.begin_page();
         let mut encoder = PlainEncoder::new(&mut self.object_writer, data_type);
         let pos = encoder.encode(arrs).await?;
         let arrs_length: i32 = arrs.iter().map(|a| a.len() as i32).sum();
         let page_info = PageInfo::new(pos, arrs_length as usize);
-        self.page_table.set(field.id, self.batch_id, page_info);
+        self.finish_page(field.id, start, page_info);
         Ok(())
     }
 
     /// Write var-length binary arrays.
     async fn write_binary_array(&mut self, field: &Field, arrs: &[&dyn Array]) -> Result<()> {
         assert_eq!(field.encoding, Some(Encoding::VarBinary));
+        let start = self.begin_page();
         let mut encoder = BinaryEncoder::new(&mut self.object_writer);
         let pos = encoder.encode(arrs).await?;
         let arrs_length: i32 = arrs.iter().map(|a| a.len() as i32).sum();
         let page_info = PageInfo::new(pos, arrs_length as usize);
-        self.page_table.set(field.id, self.batch_id, page_info);
+        self.finish_page(field.id, start, page_info);
         Ok(())
     }
 
@@ -248,12 +852,20 @@ impl FileWriter {
     ) -> Result<()> {
         assert_eq!(field.encoding, Some(Encoding::Dictionary));
 
+        // Remap the keys to the dictionary shared by all the batches in this file.
+        let lance_field = self.schema.mut_field_by_id(field.id).ok_or_else(|| {
+            Error::Schema(format!("FileWriter: field {} does not exist", field.name))
+        })?;
+        let arrs = unify_dictionary(lance_field, arrs)?;
+        let arrs = arrs.iter().map(|a| a.as_ref()).collect::<Vec<_>>();
+
         // Write the dictionary keys.
+        let start = self.begin_page();
         let mut encoder = DictionaryEncoder::new(&mut self.object_writer, key_type);
-        let pos = encoder.encode(arrs).await?;
+        let pos = encoder.encode(arrs.as_slice()).await?;
         let arrs_length: i32 = arrs.iter().map(|a| a.len() as i32).sum();
         let page_info = PageInfo::new(pos, arrs_length as usize);
-        self.page_table.set(field.id, self.batch_id, page_info);
+        self.finish_page(field.id, start, page_info);
         Ok(())
     }
 
@@ -263,6 +875,20 @@ impl FileWriter {
             .iter()
             .for_each(|a| assert_eq!(a.num_columns(), field.children.len()));
 
+        if field.nullable {
+            // Struct level validity.
+            let validity = BooleanArray::from_iter(
+                arrays
+                    .iter()
+                    .flat_map(|a| (0..a.len()).map(|i| Some(a.is_valid(i)))),
+            );
+            let start = self.begin_page();
+            let mut encoder = PlainEncoder::new(&mut self.object_writer, &DataType::Boolean);
+            let pos = encoder.encode(&[&validity as &dyn Array]).await?;
+            let page_info = PageInfo::new(pos, validity.len());
+            self.finish_page(field.id, start, page_info);
+        }
+
         for child in &field.children {
             let mut arrs: Vec<&ArrayRef> = Vec::new();
             for struct_array in arrays {
@@ -353,6 +979,14 @@ impl FileWriter {
         // Step 1. Write page table.
         let pos = self.page_table.write(&mut self.object_writer).await?;
         self.metadata.page_table_position = pos;
+        if let Some(checksums) = self.page_checksums.as_ref() {
+            let pos = self.object_writer.write_struct(checksums).await?;
+            self.metadata.page_checksums_position = Some(pos);
+        }
+        if !self.statistics.is_empty() {
+            let pos = self.object_writer.write_struct(&self.statistics).await?;
+            self.metadata.statistics_position = Some(pos);
+        }
 
         // Step 2. Write manifest and dictionary values.
         let mut manifest = Manifest::new(&self.schema, Arc::new(vec![]));
@@ -367,6 +1001,18 @@ impl FileWriter {
     }
 }
 
+#[cfg(debug_assertions)]
+impl Drop for FileWriter {
+    fn drop(&mut self) {
+        if self.unfinished && !std::thread::panicking() {
+            eprintln!(
+                "Warning: FileWriter dropped without calling finish() or abort(), \
+                 the written data is lost"
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -374,11 +1020,12 @@ mod tests {
     use std::sync::Arc;
 
     use arrow_array::{
-        types::UInt32Type, BooleanArray, Decimal128Array, Decimal256Array, DictionaryArray,
-        DurationMicrosecondArray, DurationMillisecondArray, DurationNanosecondArray,
-        DurationSecondArray, FixedSizeBinaryArray, FixedSizeListArray, Float32Array, Int64Array,
-        LargeListArray, ListArray, NullArray, StringArray, TimestampMicrosecondArray,
-        TimestampSecondArray, UInt8Array,
+        types::{UInt16Type, UInt32Type},
+        BooleanArray, Decimal128Array, Decimal256Array, DictionaryArray, DurationMicrosecondArray,
+        DurationMillisecondArray, DurationNanosecondArray, DurationSecondArray,
+        FixedSizeBinaryArray, FixedSizeListArray, Float32Array, Int64Array, LargeListArray,
+        ListArray, NullArray, StringArray, TimestampMicrosecondArray, TimestampSecondArray,
+        UInt8Array,
     };
     use arrow_buffer::i256;
     use arrow_schema::{
@@ -386,7 +1033,7 @@ mod tests {
     };
     use object_store::path::Path;
 
-    use crate::io::{FileReader, ObjectStore};
+    use crate::io::ObjectStore;
 
     #[tokio::test]
     async fn test_write_file() {
@@ -610,6 +1257,383 @@ mod tests {
         assert_eq!(actual, batch);
     }
 
+    #[tokio::test]
+    async fn test_write_with_column_encoding() {
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
+            "d",
+            DataType::Dictionary(Box::new(DataType::UInt32), Box::new(DataType::Utf8)),
+            true,
+        )]);
+        let mut schema = Schema::try_from(&arrow_schema).unwrap();
+
+        let dict_arr: DictionaryArray<UInt32Type> =
+            (0..100).map(|n| ["a", "b", "c"][n % 3]).collect();
+        let batch = RecordBatch::try_new(Arc::new(arrow_schema), vec![Arc::new(dict_arr)]).unwrap();
+        schema.set_dictionary(&batch).unwrap();
+
+        let store = ObjectStore::memory();
+        let dict_path = Path::from("/dict");
+        let mut file_writer = FileWriter::try_new(&store, &dict_path, schema.clone())
+            .await
+            .unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let plain_path = Path::from("/plain");
+        let mut file_writer = FileWriter::try_new(&store, &plain_path, schema.clone())
+            .await
+            .unwrap()
+            .with_column_encoding("d", Encoding::VarBinary)
+            .unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &dict_path).await.unwrap();
+        let dict_batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        assert_eq!(dict_batch, batch);
+
+        let reader = FileReader::try_new(&store, &plain_path).await.unwrap();
+        assert_eq!(
+            reader.schema().fields[0].encoding,
+            Some(Encoding::VarBinary)
+        );
+        let plain_batch = reader.read_batch(0, .., reader.schema()).await.unwrap();
+        assert_eq!(plain_batch.column(0).data_type(), &DataType::Utf8);
+        assert_eq!(
+            plain_batch.column(0).as_ref(),
+            cast(dict_batch.column(0), &DataType::Utf8)
+                .unwrap()
+                .as_ref()
+        );
+
+        for encoding in [Encoding::Plain, Encoding::RLE] {
+            assert!(matches!(
+                FileWriter::try_new(&store, &Path::from("/invalid"), schema.clone())
+                    .await
+                    .unwrap()
+                    .with_column_encoding("d", encoding),
+                Err(Error::Schema(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_with_max_rows_per_batch() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int64,
+            true,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let make_batch = |range: std::ops::Range<i64>| {
+            RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![Arc::new(Int64Array::from_iter_values(range))],
+            )
+            .unwrap()
+        };
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema)
+            .await
+            .unwrap()
+            .with_max_rows_per_batch(100);
+        // Small batches are coalesced, and large ones are split.
+        file_writer
+            .write(&[make_batch(0..20), make_batch(20..50)])
+            .await
+            .unwrap();
+        file_writer.write(&[make_batch(50..250)]).await.unwrap();
+        assert_eq!(file_writer.len(), 250);
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert_eq!(reader.num_batches(), 3);
+        assert_eq!(
+            (0..3)
+                .map(|b| reader.num_rows_in_batch(b))
+                .collect::<Vec<_>>(),
+            vec![100, 100, 50]
+        );
+        let batch = reader.read_range(0..250, reader.schema()).await.unwrap();
+        assert_eq!(batch, make_batch(0..250));
+    }
+
+    #[tokio::test]
+    async fn test_open_for_append() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let make_batch = |range: std::ops::Range<i64>| {
+            RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(range.clone())),
+                    Arc::new(StringArray::from_iter_values(
+                        range.map(|v| format!("s-{v}")),
+                    )),
+                ],
+            )
+            .unwrap()
+        };
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        for i in 0..5 {
+            file_writer
+                .write(&[make_batch(i * 10..(i + 1) * 10)])
+                .await
+                .unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let mut file_writer = FileWriter::open_for_append(&store, &path, &schema)
+            .await
+            .unwrap();
+        assert_eq!(file_writer.len(), 50);
+        for i in 5..10 {
+            file_writer
+                .write(&[make_batch(i * 10..(i + 1) * 10)])
+                .await
+                .unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert_eq!(reader.num_batches(), 10);
+        let batch = reader.read_range(0..100, reader.schema()).await.unwrap();
+        assert_eq!(batch, make_batch(0..100));
+
+        let other_schema = Schema::try_from(&ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int64,
+            true,
+        )]))
+        .unwrap();
+        assert!(matches!(
+            FileWriter::open_for_append(&store, &path, &other_schema).await,
+            Err(Error::Schema(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unify_dictionary_across_batches() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "d",
+            DataType::Dictionary(Box::new(DataType::UInt32), Box::new(DataType::Utf8)),
+            true,
+        )]));
+        let mut schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+        let batches = [
+            vec![Some("a"), Some("b"), None, Some("c"), Some("a")],
+            vec![Some("c"), Some("d"), Some("a"), None, Some("d")],
+        ]
+        .into_iter()
+        .map(|values| {
+            let dict_arr: DictionaryArray<UInt32Type> = values.into_iter().collect();
+            RecordBatch::try_new(arrow_schema.clone(), vec![Arc::new(dict_arr)]).unwrap()
+        })
+        .collect::<Vec<_>>();
+        schema.set_dictionary(&batches[0]).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        for batch in batches.iter() {
+            file_writer.write(&[batch.clone()]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        let dict_values = reader.schema().fields[0]
+            .dictionary
+            .as_ref()
+            .unwrap()
+            .values
+            .clone()
+            .unwrap();
+        assert_eq!(
+            dict_values.as_ref(),
+            &StringArray::from(vec!["a", "b", "c", "d"]) as &dyn Array
+        );
+
+        for (batch_id, batch) in batches.iter().enumerate() {
+            let actual = reader
+                .read_batch(batch_id as i32, .., reader.schema())
+                .await
+                .unwrap();
+            assert_eq!(
+                cast(actual.column(0), &DataType::Utf8).unwrap().as_ref(),
+                cast(batch.column(0), &DataType::Utf8).unwrap().as_ref()
+            );
+        }
+    }
+
+    #[test]
+    fn test_unify_dictionary_same_values() {
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
+            "d",
+            DataType::Dictionary(Box::new(DataType::UInt32), Box::new(DataType::Utf8)),
+            true,
+        )]);
+        let dict_arr: DictionaryArray<UInt32Type> = vec![Some("a"), Some("b"), None, Some("a")]
+            .into_iter()
+            .collect();
+        let batch =
+            RecordBatch::try_new(Arc::new(arrow_schema.clone()), vec![Arc::new(dict_arr)]).unwrap();
+        let mut schema = Schema::try_from(&arrow_schema).unwrap();
+        schema.set_dictionary(&batch).unwrap();
+
+        // The keys of an array with the same dictionary are not remapped.
+        let arrs = unify_dictionary(&mut schema.fields[0], &[batch.column(0).as_ref()]).unwrap();
+        assert_eq!(
+            arrs[0].data().buffers()[0].as_ptr(),
+            batch.column(0).data().buffers()[0].as_ptr()
+        );
+        assert_eq!(
+            schema.fields[0]
+                .dictionary
+                .as_ref()
+                .unwrap()
+                .values
+                .as_ref()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_estimate_file_size() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int64, true),
+            ArrowField::new("b", DataType::Boolean, true),
+            ArrowField::new("s", DataType::Utf8, true),
+            ArrowField::new(
+                "d",
+                DataType::Dictionary(Box::new(DataType::UInt16), Box::new(DataType::Utf8)),
+                true,
+            ),
+            ArrowField::new(
+                "vec",
+                DataType::FixedSizeList(
+                    Arc::new(ArrowField::new("item", DataType::Float32, true)),
+                    8,
+                ),
+                true,
+            ),
+            ArrowField::new(
+                "l",
+                DataType::List(Arc::new(ArrowField::new("item", DataType::Int32, true))),
+                true,
+            ),
+        ]));
+        let mut schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+
+        let batches = (0..10)
+            .map(|b| {
+                let range = b * 100..(b + 1) * 100;
+                let dict_arr: DictionaryArray<UInt16Type> =
+                    range.clone().map(|n| ["a", "bb", "ccc"][n % 3]).collect();
+                let values = Float32Array::from_iter_values((0..800).map(|v| v as f32));
+                let list_arr = ListArray::from_iter_primitive::<Int32Type, _, _>(
+                    range
+                        .clone()
+                        .map(|n| Some((0..(n % 5) as i32).map(Some).collect::<Vec<_>>())),
+                );
+                RecordBatch::try_new(
+                    arrow_schema.clone(),
+                    vec![
+                        Arc::new(Int64Array::from_iter_values(
+                            range.clone().map(|n| n as i64),
+                        )),
+                        Arc::new(BooleanArray::from_iter(
+                            range.clone().map(|n| Some(n % 2 == 0)),
+                        )),
+                        Arc::new(StringArray::from_iter_values(
+                            range.clone().map(|n| format!("str-{n}")),
+                        )),
+                        Arc::new(dict_arr),
+                        Arc::new(FixedSizeListArray::try_new(values, 8).unwrap()),
+                        Arc::new(list_arr),
+                    ],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        schema.set_dictionary(&batches[0]).unwrap();
+
+        let mut reader = RecordBatchBuffer::new(batches.clone());
+        let estimate = estimate_file_size(&schema, &mut reader).unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema).await.unwrap();
+        for batch in batches.iter() {
+            file_writer.write(&[batch.clone()]).await.unwrap();
+        }
+        file_writer.finish().await.unwrap();
+        let actual = store.size(&path).await.unwrap();
+
+        let diff = (estimate as f64 - actual as f64).abs() / actual as f64;
+        assert!(
+            diff < 0.01,
+            "estimate {estimate} is not within 1% of the actual size {actual}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_finish_and_abort() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::Int64,
+            false,
+        )]));
+        let schema = Schema::try_from(arrow_schema.as_ref()).unwrap();
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![Arc::new(Int64Array::from_iter_values(0..10))],
+        )
+        .unwrap();
+
+        let store = ObjectStore::memory();
+        let path = Path::from("/foo");
+        let mut file_writer = FileWriter::try_new(&store, &path, schema.clone())
+            .await
+            .unwrap();
+        #[cfg(debug_assertions)]
+        assert!(file_writer.unfinished);
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.finish().await.unwrap();
+        #[cfg(debug_assertions)]
+        assert!(!file_writer.unfinished);
+
+        // Aborting a new file leaves nothing behind.
+        let aborted_path = Path::from("/aborted");
+        let mut file_writer = FileWriter::try_new(&store, &aborted_path, schema.clone())
+            .await
+            .unwrap();
+        file_writer.write(&[batch.clone()]).await.unwrap();
+        file_writer.abort().await.unwrap();
+        assert!(store.inner.head(&aborted_path).await.is_err());
+
+        // Aborting an append leaves the existing file unchanged.
+        let mut file_writer = FileWriter::open_for_append(&store, &path, &schema)
+            .await
+            .unwrap();
+        file_writer.write(&[batch]).await.unwrap();
+        file_writer.abort().await.unwrap();
+        let reader = FileReader::try_new(&store, &path).await.unwrap();
+        assert_eq!(reader.len(), 10);
+    }
+
     #[tokio::test]
     async fn test_write_temporal_types() {
         let arrow_schema = Arc::new(ArrowSchema::new(vec![
@@ -12,10 +12,129 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Linear algebra and vector distance kernels.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow_array::Float32Array;
+
+use crate::index::vector::MetricType;
+use crate::{Error, Result};
+
+pub mod add;
 pub mod cosine;
 pub mod dot;
+pub mod l1;
 pub mod l2;
 pub mod norm_l2;
 
 #[cfg(target_arch = "x86_64")]
 pub mod x86_64;
+
+/// The metric used to compute the distance between vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceType {
+    /// Squared Euclidean distance, see [l2::l2_distance_batch].
+    L2,
+    /// Cosine distance, see [cosine::cosine_distance_batch].
+    Cosine,
+    /// Dot distance, see [dot::dot_distance_batch].
+    Dot,
+    /// Manhattan distance, see [l1::l1_distance_batch].
+    L1,
+}
+
+impl From<MetricType> for DistanceType {
+    fn from(mt: MetricType) -> Self {
+        match mt {
+            MetricType::L2 => Self::L2,
+            MetricType::Cosine => Self::Cosine,
+        }
+    }
+}
+
+impl FromStr for DistanceType {
+    type Err = Error;
+
+    /// Parse `dot`, `l1` and `manhattan`, and the names of the [MetricType]s.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "dot" => Ok(Self::Dot),
+            "l1" | "manhattan" => Ok(Self::L1),
+            _ => MetricType::try_from(s).map(Self::from),
+        }
+    }
+}
+
+/// Compute the distance of `kind` between a vector and a batch of vectors.
+///
+/// `kind` is a [DistanceType] or the [MetricType] of a vector index. `to` is a list of
+/// dense vectors of `dimension` values each.
+pub fn distance_batch(
+    kind: impl Into<DistanceType>,
+    from: &[f32],
+    to: &[f32],
+    dimension: usize,
+) -> Arc<Float32Array> {
+    match kind.into() {
+        DistanceType::L2 => l2::l2_distance_batch(from, to, dimension),
+        DistanceType::Cosine => cosine::cosine_distance_batch(from, to, dimension),
+        DistanceType::Dot => dot::dot_distance_batch(from, to, dimension),
+        DistanceType::L1 => l1::l1_distance_batch(from, to, dimension),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_distance_type_from_str() {
+        assert_eq!(DistanceType::from_str("L2").unwrap(), DistanceType::L2);
+        assert_eq!(
+            DistanceType::from_str("cosine").unwrap(),
+            DistanceType::Cosine
+        );
+        assert_eq!(DistanceType::from_str("dot").unwrap(), DistanceType::Dot);
+        assert_eq!(
+            DistanceType::from_str("manhattan").unwrap(),
+            DistanceType::L1
+        );
+        assert!(DistanceType::from_str("hamming").is_err());
+    }
+
+    #[test]
+    fn test_distance_type_from_metric_type() {
+        assert_eq!(DistanceType::from(MetricType::L2), DistanceType::L2);
+        assert_eq!(DistanceType::from(MetricType::Cosine), DistanceType::Cosine);
+
+        let from = [1.0_f32, 0.0];
+        let to = [1.0_f32, 0.0, 0.0, 2.0];
+        assert_eq!(
+            distance_batch(MetricType::Cosine, &from, &to, 2),
+            distance_batch(DistanceType::Cosine, &from, &to, 2)
+        );
+    }
+
+    #[test]
+    fn test_distance_batch() {
+        let from = [1.0_f32, 0.0];
+        let to = [1.0_f32, 0.0, 0.0, 2.0, -3.0, 0.0];
+        let cases = [
+            (DistanceType::L2, [0.0, 5.0, 16.0]),
+            (DistanceType::Cosine, [0.0, 1.0, 2.0]),
+            (DistanceType::Dot, [0.0, 1.0, 4.0]),
+            (DistanceType::L1, [0.0, 3.0, 4.0]),
+        ];
+        for (kind, expected) in cases {
+            let dists = distance_batch(kind, &from, &to, 2);
+            assert_eq!(dists.len(), expected.len());
+            for (d, e) in dists.values().iter().zip(expected) {
+                assert_relative_eq!(*d, e, epsilon = 1e-6);
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2023 Lance Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Element-wise vector addition.
+
+use once_cell::sync::Lazy;
+
+/// Add `v` to `acc` element-wise, using scalar operations.
+#[inline]
+fn add_assign_scalar(acc: &mut [f32], v: &[f32]) {
+    acc.iter_mut().zip(v.iter()).for_each(|(a, b)| *a += b);
+}
+
+/// Select the fastest add kernel supported by the running CPU.
+fn select_add_assign_f32() -> fn(&mut [f32], &[f32]) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return x86_64::avx::add_assign_f32;
+        }
+    }
+
+    #[cfg(any(target_arch = "aarch64"))]
+    {
+        return aarch64::neon::add_assign_f32;
+    }
+
+    #[cfg(not(target_arch = "aarch64"))]
+    add_assign_scalar
+}
+
+static ADD_ASSIGN_F32: Lazy<fn(&mut [f32], &[f32])> = Lazy::new(select_add_assign_f32);
+
+/// Add vector `v` to `acc` element-wise, i.e., `acc += v`.
+///
+/// Panics if the vectors have different lengths.
+#[inline]
+pub fn add_assign(acc: &mut [f32], v: &[f32]) {
+    assert_eq!(acc.len(), v.len());
+    (*ADD_ASSIGN_F32)(acc, v)
+}
+
+/// Compute the mean of `num` vectors of `dimension` values each, stored contiguously
+/// in `vectors`.
+///
+/// Returns a zero vector if `num` is zero.
+pub fn mean_vectors(vectors: &[f32], num: usize, dimension: usize) -> Vec<f32> {
+    assert_eq!(vectors.len(), num * dimension);
+
+    let mut sum = vec![0_f32; dimension];
+    if num == 0 || dimension == 0 {
+        return sum;
+    }
+    vectors
+        .chunks_exact(dimension)
+        .for_each(|v| add_assign(&mut sum, v));
+    sum.iter_mut().for_each(|s| *s /= num as f32);
+    sum
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86_64 {
+    pub(crate) mod avx {
+        use super::super::add_assign_scalar;
+
+        #[inline]
+        pub(crate) fn add_assign_f32(acc: &mut [f32], v: &[f32]) {
+            unsafe {
+                use std::arch::x86_64::*;
+                debug_assert_eq!(acc.len(), v.len());
+
+                let len = acc.len() / 8 * 8;
+                for i in (0..len).step_by(8) {
+                    let left = _mm256_loadu_ps(acc.as_ptr().add(i));
+                    let right = _mm256_loadu_ps(v.as_ptr().add(i));
+                    _mm256_storeu_ps(acc.as_mut_ptr().add(i), _mm256_add_ps(left, right));
+                }
+                add_assign_scalar(&mut acc[len..], &v[len..]);
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64 {
+    pub(super) mod neon {
+        use super::super::add_assign_scalar;
+        use std::arch::aarch64::*;
+
+        #[inline]
+        pub(crate) fn add_assign_f32(acc: &mut [f32], v: &[f32]) {
+            unsafe {
+                let len = acc.len() / 4 * 4;
+                for i in (0..len).step_by(4) {
+                    let left = vld1q_f32(acc.as_ptr().add(i));
+                    let right = vld1q_f32(v.as_ptr().add(i));
+                    vst1q_f32(acc.as_mut_ptr().add(i), vaddq_f32(left, right));
+                }
+                add_assign_scalar(&mut acc[len..], &v[len..]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_add_assign() {
+        // Not a multiple of the SIMD lanes.
+        let dimension = 11;
+        let vectors = (0..3 * dimension)
+            .map(|v| v as f32 * 0.5)
+            .collect::<Vec<_>>();
+        let mut acc = vec![0_f32; dimension];
+        vectors
+            .chunks_exact(dimension)
+            .for_each(|v| add_assign(&mut acc, v));
+
+        let expected = (0..dimension)
+            .map(|i| (0..3).map(|j| vectors[j * dimension + i]).sum::<f32>())
+            .collect::<Vec<_>>();
+        for (a, e) in acc.iter().zip(expected.iter()) {
+            assert_relative_eq!(*a, *e);
+        }
+    }
+
+    #[test]
+    fn test_mean_vectors() {
+        let vectors = (0..8)
+            .map(|v| v as f32)
+            .chain((1..9).map(|v| v as f32))
+            .chain((2..10).map(|v| v as f32))
+            .collect::<Vec<_>>();
+        let mean = mean_vectors(&vectors, 3, 8);
+        assert_eq!(mean, (1..9).map(|v| v as f32).collect::<Vec<_>>());
+
+        assert_eq!(mean_vectors(&[], 0, 4), vec![0.0; 4]);
+    }
+}
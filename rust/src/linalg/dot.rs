@@ -14,8 +14,11 @@
 
 //! Dot product.
 
-use num_traits::real::Real;
 use std::iter::Sum;
+use std::sync::Arc;
+
+use arrow_array::Float32Array;
+use num_traits::real::Real;
 
 #[inline]
 pub fn dot<T: Real + Sum>(from: &[T], to: &[T]) -> T {
@@ -36,3 +39,18 @@ impl Dot for [f32] {
         dot(self, other)
     }
 }
+
+/// Dot distance between a vector and a batch of vectors, defined as `1 - x·y`.
+///
+/// Smaller values are closer, same as the other distances.
+pub fn dot_distance_batch(from: &[f32], to: &[f32], dimension: usize) -> Arc<Float32Array> {
+    assert_eq!(from.len(), dimension);
+    assert_eq!(to.len() % dimension, 0);
+
+    let dists = unsafe {
+        Float32Array::from_trusted_len_iter(
+            to.chunks_exact(dimension).map(|y| Some(1.0 - from.dot(y))),
+        )
+    };
+    Arc::new(dists)
+}
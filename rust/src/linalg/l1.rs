@@ -0,0 +1,261 @@
+// Copyright 2023 Lance Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! L1 (Manhattan) distance.
+//!
+
+use std::iter::Sum;
+use std::sync::Arc;
+
+use arrow_array::Float32Array;
+use num_traits::real::Real;
+use once_cell::sync::Lazy;
+
+/// Calculate the L1 distance between two vectors.
+///
+pub trait L1 {
+    type Output;
+
+    /// Calculate the L1 distance between two vectors.
+    fn l1(&self, other: &Self) -> Self::Output;
+}
+
+/// Calculate the L1 distance between two vectors, using scalar operations.
+///
+/// Rely on compiler auto-vectorization.
+#[inline]
+fn l1_scalar<T: Real + Sum>(from: &[T], to: &[T]) -> T {
+    from.iter()
+        .zip(to.iter())
+        .map(|(a, b)| a.sub(*b).abs())
+        .sum::<T>()
+}
+
+/// Select the fastest L1 kernel supported by the running CPU.
+fn select_l1_f32() -> fn(&[f32], &[f32]) -> f32 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return x86_64::avx::l1_f32;
+        }
+    }
+
+    #[cfg(any(target_arch = "aarch64"))]
+    {
+        return aarch64::neon::l1_f32;
+    }
+
+    // Fallback on x86_64 without AVX2, or other platforms.
+    #[cfg(not(target_arch = "aarch64"))]
+    l1_scalar::<f32>
+}
+
+/// The L1 kernel for the running CPU, resolved once.
+static L1_F32: Lazy<fn(&[f32], &[f32]) -> f32> = Lazy::new(select_l1_f32);
+
+impl L1 for [f32] {
+    type Output = f32;
+
+    #[inline]
+    fn l1(&self, other: &[f32]) -> f32 {
+        (*L1_F32)(self, other)
+    }
+}
+
+impl L1 for Float32Array {
+    type Output = f32;
+
+    #[inline]
+    fn l1(&self, other: &Float32Array) -> f32 {
+        self.values().l1(other.values())
+    }
+}
+
+/// Compute L1 distance between two vectors.
+#[inline]
+pub fn l1_distance(from: &[f32], to: &[f32]) -> f32 {
+    from.l1(to)
+}
+
+/// Compute L1 distance between a vector and a batch of vectors.
+///
+/// Parameters
+///
+/// - `from`: the vector to compute distance from.
+/// - `to`: a list of vectors to compute distance to.
+/// - `dimension`: the dimension of the vectors.
+pub fn l1_distance_batch(from: &[f32], to: &[f32], dimension: usize) -> Arc<Float32Array> {
+    assert_eq!(from.len(), dimension);
+    assert_eq!(to.len() % dimension, 0);
+
+    let dists = unsafe {
+        Float32Array::from_trusted_len_iter(to.chunks_exact(dimension).map(|v| Some(from.l1(v))))
+    };
+    Arc::new(dists)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86_64 {
+    pub(crate) mod avx {
+        #[inline]
+        pub(crate) fn l1_f32(from: &[f32], to: &[f32]) -> f32 {
+            unsafe {
+                use std::arch::x86_64::*;
+                debug_assert_eq!(from.len(), to.len());
+
+                // Clearing the sign bit takes the absolute value.
+                let sign_mask = _mm256_set1_ps(-0.0);
+                let len = from.len() / 8 * 8;
+                let mut sums = _mm256_setzero_ps();
+                for i in (0..len).step_by(8) {
+                    let left = _mm256_loadu_ps(from.as_ptr().add(i));
+                    let right = _mm256_loadu_ps(to.as_ptr().add(i));
+                    let sub = _mm256_sub_ps(left, right);
+                    sums = _mm256_add_ps(_mm256_andnot_ps(sign_mask, sub), sums);
+                }
+
+                // Remaining, loads only the first `remaining` lanes and zeros the rest,
+                // without touching the memory beyond the end of the vectors.
+                let remaining = from.len() - len;
+                if remaining > 0 {
+                    let mask = _mm256_cmpgt_epi32(
+                        _mm256_set1_epi32(remaining as i32),
+                        _mm256_setr_epi32(0, 1, 2, 3, 4, 5, 6, 7),
+                    );
+                    let left = _mm256_maskload_ps(from.as_ptr().add(len), mask);
+                    let right = _mm256_maskload_ps(to.as_ptr().add(len), mask);
+                    let sub = _mm256_sub_ps(left, right);
+                    sums = _mm256_add_ps(_mm256_andnot_ps(sign_mask, sub), sums);
+                }
+
+                // Shift and add vector, until only 1 value left.
+                let mut shift = _mm256_permute2f128_ps(sums, sums, 1);
+                sums = _mm256_add_ps(sums, shift);
+                shift = _mm256_permute_ps(sums, 14);
+                sums = _mm256_add_ps(sums, shift);
+                sums = _mm256_hadd_ps(sums, sums);
+                let mut results: [f32; 8] = [0f32; 8];
+                _mm256_storeu_ps(results.as_mut_ptr(), sums);
+                results[0]
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64 {
+
+    pub(super) mod neon {
+        use super::super::l1_scalar;
+        use std::arch::aarch64::*;
+
+        #[inline]
+        pub(crate) fn l1_f32(from: &[f32], to: &[f32]) -> f32 {
+            unsafe {
+                let len = from.len() / 4 * 4;
+                let mut sum = vdupq_n_f32(0.0);
+                for i in (0..len).step_by(4) {
+                    let left = vld1q_f32(from.as_ptr().add(i));
+                    let right = vld1q_f32(to.as_ptr().add(i));
+                    sum = vaddq_f32(sum, vabdq_f32(left, right));
+                }
+                let mut sum = vaddvq_f32(sum);
+                sum += l1_scalar(&from[len..], &to[len..]);
+                sum
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_l1_distance_batch() {
+        let mat = (0..8)
+            .chain(1..9)
+            .chain(2..10)
+            .chain(3..11)
+            .map(|v| v as f32)
+            .collect::<Vec<_>>();
+        let point = (2..10).map(|v| v as f32).collect::<Vec<_>>();
+        let scores = l1_distance_batch(&point, &mat, 8);
+
+        assert_eq!(
+            scores.as_ref(),
+            &Float32Array::from(vec![16.0, 8.0, 0.0, 8.0])
+        );
+    }
+
+    #[test]
+    fn test_not_aligned() {
+        let mat = (0..6)
+            .chain(0..8)
+            .chain(1..9)
+            .chain(2..10)
+            .chain(3..11)
+            .map(|v| v as f32)
+            .collect::<Vec<_>>();
+        let point = Float32Array::from((0..10).map(|v| Some(v as f32)).collect::<Vec<_>>());
+        let scores = l1_distance_batch(&point.values()[2..], &mat[6..], 8);
+
+        assert_eq!(
+            scores.as_ref(),
+            &Float32Array::from(vec![16.0, 8.0, 0.0, 8.0])
+        );
+    }
+
+    #[test]
+    fn test_odd_length_vector() {
+        let mat = Float32Array::from_iter((0..5).map(|v| Some(v as f32)));
+        let point = Float32Array::from((2..7).map(|v| Some(v as f32)).collect::<Vec<_>>());
+        let scores = l1_distance_batch(point.values(), mat.values(), 5);
+
+        assert_eq!(scores.as_ref(), &Float32Array::from(vec![10.0]));
+        assert_eq!(point.l1(&mat), 10.0);
+    }
+
+    #[test]
+    fn test_l1_implementations_agree() {
+        let from = (0..33).map(|v| v as f32 * 0.3 - 2.0).collect::<Vec<_>>();
+        let to = (0..33).map(|v| (v as f32 * 0.7).sin()).collect::<Vec<_>>();
+        for dim in 1..=33 {
+            let expected = l1_scalar(&from[..dim], &to[..dim]);
+            // Dispatched through the cached kernel.
+            assert_relative_eq!(expected, l1_distance(&from[..dim], &to[..dim]));
+
+            #[cfg(target_arch = "x86_64")]
+            {
+                if is_x86_feature_detected!("avx2") {
+                    assert_relative_eq!(
+                        expected,
+                        x86_64::avx::l1_f32(&from[..dim], &to[..dim]),
+                        max_relative = 1e-6
+                    );
+                }
+            }
+            #[cfg(target_arch = "aarch64")]
+            {
+                assert_relative_eq!(
+                    expected,
+                    aarch64::neon::l1_f32(&from[..dim], &to[..dim]),
+                    max_relative = 1e-6
+                );
+            }
+        }
+    }
+}
@@ -18,8 +18,12 @@
 use std::iter::Sum;
 use std::sync::Arc;
 
-use arrow_array::Float32Array;
+use arrow_array::{Array, ArrayRef, FixedSizeListArray, Float32Array};
+use arrow_schema::DataType;
 use num_traits::real::Real;
+use once_cell::sync::Lazy;
+
+use crate::{Error, Result};
 
 /// Calculate the L2 distance between two vectors.
 ///
@@ -41,32 +45,42 @@ fn l2_scalar<T: Real + Sum>(from: &[T], to: &[T]) -> T {
         .sum::<T>()
 }
 
+/// Select the fastest L2 kernel supported by the running CPU.
+fn select_l2_f32() -> fn(&[f32], &[f32]) -> f32 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        // TODO: Only known platform that does not support FMA is Github Action Mac(Intel) Runner.
+        if is_x86_feature_detected!("avx2") {
+            // AVX2 / FMA is the lowest x86_64 CPU requirement (released from 2011) for Lance.
+            return x86_64::avx::l2_f32;
+        }
+        if is_x86_feature_detected!("sse2") {
+            // Older or virtualized CPUs without AVX2.
+            return x86_64::sse::l2_f32;
+        }
+    }
+
+    #[cfg(any(target_arch = "aarch64"))]
+    {
+        // Neon is the lowest aarch64 CPU requirement (available in all Apple Silicon / Arm V7+).
+        return aarch64::neon::l2_f32;
+    }
+
+    // Fallback on x86_64 without AVX2 / FMA, or other platforms.
+    #[cfg(not(target_arch = "aarch64"))]
+    l2_scalar::<f32>
+}
+
+/// The L2 kernel for the running CPU, resolved once so that the hot loops
+/// do not run the CPU feature detection on every call.
+static L2_F32: Lazy<fn(&[f32], &[f32]) -> f32> = Lazy::new(select_l2_f32);
+
 impl L2 for [f32] {
     type Output = f32;
 
     #[inline]
     fn l2(&self, other: &[f32]) -> f32 {
-        #[cfg(target_arch = "x86_64")]
-        {
-            // TODO: Only known platform that does not support FMA is Github Action Mac(Intel) Runner.
-            // However, it introduces one more branch, which may affect performance.
-            if is_x86_feature_detected!("avx2") {
-                // AVX2 / FMA is the lowest x86_64 CPU requirement (released from 2011) for Lance.
-                use x86_64::avx::l2_f32;
-                return l2_f32(self, other);
-            }
-        }
-
-        #[cfg(any(target_arch = "aarch64"))]
-        {
-            // Neon is the lowest aarch64 CPU requirement (available in all Apple Silicon / Arm V7+).
-            use aarch64::neon::l2_f32;
-            return l2_f32(self, other);
-        }
-
-        // Fallback on x86_64 without AVX2 / FMA, or other platforms.
-        #[cfg(not(target_arch = "aarch64"))]
-        l2_scalar(self, other)
+        (*L2_F32)(self, other)
     }
 }
 
@@ -92,6 +106,9 @@ pub fn l2_distance(from: &[f32], to: &[f32]) -> f32 {
 /// - `from`: the vector to compute distance from.
 /// - `to`: a list of vectors to compute distance to.
 /// - `dimension`: the dimension of the vectors.
+///
+/// `to` must be dense vectors without nulls. To compute distance to the vectors in a
+/// [FixedSizeListArray] which may have null vectors, use [l2_distance_batch_opt].
 pub fn l2_distance_batch(from: &[f32], to: &[f32], dimension: usize) -> Arc<Float32Array> {
     assert_eq!(from.len(), dimension);
     assert_eq!(to.len() % dimension, 0);
@@ -102,11 +119,145 @@ pub fn l2_distance_batch(from: &[f32], to: &[f32], dimension: usize) -> Arc<Floa
     Arc::new(dists)
 }
 
+/// Compute L2 distance between a vector and a batch of vectors stored in columnar layout.
+///
+/// Parameters
+///
+/// - `from`: the vector to compute distance from.
+/// - `to`: `dimension` contiguous columns of `num_vectors` values each, i.e., the
+///   `j`-th dimension of the `i`-th vector is `to[j * num_vectors + i]`.
+/// - `num_vectors`: the number of vectors in `to`.
+/// - `dimension`: the dimension of the vectors.
+///
+/// Use [l2_distance_batch] if the values of each vector are contiguous instead.
+pub fn l2_distance_batch_columnar(
+    from: &[f32],
+    to: &[f32],
+    num_vectors: usize,
+    dimension: usize,
+) -> Arc<Float32Array> {
+    assert_eq!(from.len(), dimension);
+    assert_eq!(to.len(), num_vectors * dimension);
+
+    let mut dists = vec![0_f32; num_vectors];
+    if num_vectors > 0 {
+        for (q, column) in from.iter().zip(to.chunks_exact(num_vectors)) {
+            // The column is contiguous, so this loop is auto-vectorized.
+            dists.iter_mut().zip(column).for_each(|(d, v)| {
+                let sub = v - q;
+                *d += sub * sub;
+            });
+        }
+    }
+    Arc::new(Float32Array::from(dists))
+}
+
+/// Compute L2 distance between a vector and each vector in a [FixedSizeListArray].
+///
+/// The dimension is the list size of `targets`. A null vector in `targets` has a null distance.
+pub fn l2_distance_to_fsl(
+    query: &[f32],
+    targets: &FixedSizeListArray,
+) -> Result<Arc<Float32Array>> {
+    let dimension = targets.value_length() as usize;
+    if query.len() != dimension {
+        return Err(Error::Arrow(format!(
+            "L2 distance: query has {} dimensions, but the targets have {}",
+            query.len(),
+            dimension
+        )));
+    }
+    if targets.value_type() != DataType::Float32 {
+        return Err(Error::Arrow(format!(
+            "L2 distance: expect float32 vectors, got {}",
+            targets.value_type()
+        )));
+    }
+    Ok(l2_distance_batch_opt(query, targets))
+}
+
+/// Compute L2 distance between a vector and the vectors in an [ArrayRef].
+///
+/// `targets` is either a flat [Float32Array] of dense vectors of `dimension` values each,
+/// or a [FixedSizeListArray] of float32 vectors with list size `dimension`.
+///
+/// Returns [Error::Arrow] if `targets` is of other types, or the dimensions do not match.
+pub fn l2_distance_array(
+    query: &[f32],
+    targets: &ArrayRef,
+    dimension: usize,
+) -> Result<Arc<Float32Array>> {
+    if query.len() != dimension {
+        return Err(Error::Arrow(format!(
+            "L2 distance: query has {} dimensions, expect {}",
+            query.len(),
+            dimension
+        )));
+    }
+    match targets.data_type() {
+        DataType::Float32 => {
+            let values = targets
+                .as_any()
+                .downcast_ref::<Float32Array>()
+                .expect("L2 distance: expect float32 array");
+            if dimension == 0 || values.len() % dimension != 0 {
+                return Err(Error::Arrow(format!(
+                    "L2 distance: {} values can not be split into vectors of {} dimensions",
+                    values.len(),
+                    dimension
+                )));
+            }
+            if values.null_count() > 0 {
+                return Err(Error::Arrow(
+                    "L2 distance: float32 values must not contain nulls".to_string(),
+                ));
+            }
+            Ok(l2_distance_batch(query, values.values(), dimension))
+        }
+        DataType::FixedSizeList(_, _) => {
+            let fsl = targets
+                .as_any()
+                .downcast_ref::<FixedSizeListArray>()
+                .expect("L2 distance: expect fixed size list array");
+            l2_distance_to_fsl(query, fsl)
+        }
+        dt => Err(Error::Arrow(format!(
+            "L2 distance: unsupported target type {dt}"
+        ))),
+    }
+}
+
+/// Compute L2 distance between a vector and a batch of vectors, which may contain nulls.
+///
+/// The distance to a null vector in `to` is null, instead of being computed over the
+/// stale values underneath it.
+///
+/// Panics if `to` is not a list of float32 vectors of the same dimension as `from`.
+pub fn l2_distance_batch_opt(from: &[f32], to: &FixedSizeListArray) -> Arc<Float32Array> {
+    let dimension = to.value_length() as usize;
+    assert_eq!(from.len(), dimension);
+    let values = to
+        .values()
+        .as_any()
+        .downcast_ref::<Float32Array>()
+        .expect("L2 distance: expect float32 vectors");
+    let start = to.value_offset(0) as usize;
+    let vectors = &values.values()[start..start + to.len() * dimension];
+
+    if to.null_count() == 0 {
+        return l2_distance_batch(from, vectors, dimension);
+    }
+    let dists = vectors
+        .chunks_exact(dimension)
+        .enumerate()
+        .map(|(i, v)| to.is_valid(i).then(|| from.l2(v)))
+        .collect::<Float32Array>();
+    Arc::new(dists)
+}
+
 #[cfg(target_arch = "x86_64")]
 mod x86_64 {
     pub(crate) mod avx {
-        use super::super::l2_scalar;
-
         #[inline]
         pub(crate) fn l2_f32(from: &[f32], to: &[f32]) -> f32 {
             unsafe {
@@ -123,6 +274,21 @@ mod x86_64 {
                     // sum = sub * sub + sum
                     sums = _mm256_fmadd_ps(sub, sub, sums);
                 }
+
+                // Remaining, loads only the first `remaining` lanes and zeros the rest,
+                // without touching the memory beyond the end of the vectors.
+                let remaining = from.len() - len;
+                if remaining > 0 {
+                    let mask = _mm256_cmpgt_epi32(
+                        _mm256_set1_epi32(remaining as i32),
+                        _mm256_setr_epi32(0, 1, 2, 3, 4, 5, 6, 7),
+                    );
+                    let left = _mm256_maskload_ps(from.as_ptr().add(len), mask);
+                    let right = _mm256_maskload_ps(to.as_ptr().add(len), mask);
+                    let sub = _mm256_sub_ps(left, right);
+                    sums = _mm256_fmadd_ps(sub, sub, sums);
+                }
+
                 // Shift and add vector, until only 1 value left.
                 // sums = [x0-x7], shift = [x4-x7]
                 let mut shift = _mm256_permute2f128_ps(sums, sums, 1);
@@ -133,13 +299,36 @@ mod x86_64 {
                 sums = _mm256_hadd_ps(sums, sums);
                 let mut results: [f32; 8] = [0f32; 8];
                 _mm256_storeu_ps(results.as_mut_ptr(), sums);
-
-                // Remaining
-                results[0] += l2_scalar(&from[len..], &to[len..]);
                 results[0]
             }
         }
     }
+
+    pub(crate) mod sse {
+        use super::super::l2_scalar;
+        use std::arch::x86_64::*;
+
+        #[inline]
+        pub(crate) fn l2_f32(from: &[f32], to: &[f32]) -> f32 {
+            unsafe {
+                debug_assert_eq!(from.len(), to.len());
+
+                let len = from.len() / 4 * 4;
+                let mut sums = _mm_setzero_ps();
+                for i in (0..len).step_by(4) {
+                    let left = _mm_loadu_ps(from.as_ptr().add(i));
+                    let right = _mm_loadu_ps(to.as_ptr().add(i));
+                    let sub = _mm_sub_ps(left, right);
+                    sums = _mm_add_ps(_mm_mul_ps(sub, sub), sums);
+                }
+                let mut results: [f32; 4] = [0f32; 4];
+                _mm_storeu_ps(results.as_mut_ptr(), sums);
+                let mut sum = results.iter().sum::<f32>();
+                sum += l2_scalar(&from[len..], &to[len..]);
+                sum
+            }
+        }
+    }
 }
 
 #[cfg(target_arch = "aarch64")]
@@ -174,7 +363,7 @@ mod tests {
     use super::*;
 
     use approx::assert_relative_eq;
-    use arrow::array::{as_primitive_array, FixedSizeListArray};
+    use arrow::array::{as_fixed_size_list_array, as_primitive_array, FixedSizeListArray};
     use arrow_array::types::Float32Type;
 
     #[test]
@@ -218,6 +407,119 @@ mod tests {
             &Float32Array::from(vec![32.0, 8.0, 0.0, 8.0])
         );
     }
+
+    #[test]
+    fn test_l2_distance_batch_columnar() {
+        let dimension = 13;
+        let num_vectors = 21;
+        let from = (0..dimension)
+            .map(|v| (v as f32 * 0.3).cos())
+            .collect::<Vec<_>>();
+        // Row-major: each vector is contiguous.
+        let rows = (0..num_vectors * dimension)
+            .map(|v| (v as f32 * 0.7).sin())
+            .collect::<Vec<_>>();
+        // Column-major: each dimension is contiguous.
+        let mut columns = vec![0_f32; rows.len()];
+        for i in 0..num_vectors {
+            for j in 0..dimension {
+                columns[j * num_vectors + i] = rows[i * dimension + j];
+            }
+        }
+
+        let expected = l2_distance_batch(&from, &rows, dimension);
+        let actual = l2_distance_batch_columnar(&from, &columns, num_vectors, dimension);
+        assert_eq!(actual.len(), num_vectors);
+        for (e, a) in expected.values().iter().zip(actual.values()) {
+            assert_relative_eq!(*e, *a, max_relative = 1e-5);
+        }
+
+        let empty = l2_distance_batch_columnar(&from, &[], 0, dimension);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_l2_distance_to_fsl() {
+        let mat = FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
+            vec![
+                Some((0..8).map(|v| Some(v as f32)).collect::<Vec<_>>()),
+                None,
+                Some((2..10).map(|v| Some(v as f32)).collect::<Vec<_>>()),
+                Some((3..11).map(|v| Some(v as f32)).collect::<Vec<_>>()),
+            ],
+            8,
+        );
+        let point = (2..10).map(|v| v as f32).collect::<Vec<_>>();
+        let scores = l2_distance_to_fsl(&point, &mat).unwrap();
+        assert_eq!(
+            scores.as_ref(),
+            &Float32Array::from(vec![Some(32.0), None, Some(0.0), Some(8.0)])
+        );
+
+        // Sliced array.
+        let sliced = Array::slice(&mat, 2, 2);
+        let scores = l2_distance_to_fsl(&point, as_fixed_size_list_array(&sliced)).unwrap();
+        assert_eq!(scores.as_ref(), &Float32Array::from(vec![0.0, 8.0]));
+
+        assert!(l2_distance_to_fsl(&point[..4], &mat).is_err());
+    }
+
+    #[test]
+    fn test_l2_distance_array() {
+        let point = (2..10).map(|v| v as f32).collect::<Vec<_>>();
+        let expected = Float32Array::from(vec![32.0, 8.0, 0.0, 8.0]);
+
+        let flat: ArrayRef = Arc::new(Float32Array::from_iter_values(
+            (0..4).flat_map(|i| (i..i + 8).map(|v| v as f32)),
+        ));
+        let scores = l2_distance_array(&point, &flat, 8).unwrap();
+        assert_eq!(scores.as_ref(), &expected);
+
+        let fsl: ArrayRef = Arc::new(
+            FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
+                (0..4).map(|i| Some((i..i + 8).map(|v| Some(v as f32)).collect::<Vec<_>>())),
+                8,
+            ),
+        );
+        let scores = l2_distance_array(&point, &fsl, 8).unwrap();
+        assert_eq!(scores.as_ref(), &expected);
+
+        // Mismatched dimensions.
+        assert!(matches!(
+            l2_distance_array(&point[..4], &fsl, 4),
+            Err(Error::Arrow(_))
+        ));
+        assert!(matches!(
+            l2_distance_array(&point[..5], &flat, 5),
+            Err(Error::Arrow(_))
+        ));
+        // Unsupported type.
+        let ints: ArrayRef = Arc::new(arrow_array::Int32Array::from(vec![1, 2]));
+        assert!(matches!(
+            l2_distance_array(&point[..2], &ints, 2),
+            Err(Error::Arrow(_))
+        ));
+    }
+
+    #[test]
+    fn test_l2_distance_batch_opt() {
+        let mat = FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
+            vec![
+                None,
+                Some((1..6).map(|v| Some(v as f32)).collect::<Vec<_>>()),
+                None,
+                Some((2..7).map(|v| Some(v as f32)).collect::<Vec<_>>()),
+            ],
+            5,
+        );
+        let point = (2..7).map(|v| v as f32).collect::<Vec<_>>();
+        let scores = l2_distance_batch_opt(&point, &mat);
+        assert_eq!(
+            scores.as_ref(),
+            &Float32Array::from(vec![None, Some(5.0), None, Some(0.0)])
+        );
+    }
+
     #[test]
     fn test_odd_length_vector() {
         let mat = Float32Array::from_iter((0..5).map(|v| Some(v as f32)));
@@ -227,8 +529,8 @@ mod tests {
         assert_eq!(scores.as_ref(), &Float32Array::from(vec![20.0]));
     }
 
-    #[test]
-    fn test_l2_distance_cases() {
+    /// Vectors of the same dimension, and their L2 distance.
+    fn l2_distance_fixture() -> (Float32Array, Float32Array, f32) {
         let values: Float32Array = vec![
             0.25335717, 0.24663818, 0.26330215, 0.14988247, 0.06042378, 0.21077952, 0.26687378,
             0.22145681, 0.18319066, 0.18688454, 0.05216244, 0.11470364, 0.10554603, 0.19964123,
@@ -274,7 +576,61 @@ mod tests {
         ]
         .into();
 
+        (values, q, 0.31935785)
+    }
+
+    #[test]
+    fn test_l2_distance_cases() {
+        let (values, q, expected) = l2_distance_fixture();
         let d = l2_distance_batch(q.values(), values.values(), 32);
-        assert_relative_eq!(0.31935785197341404, d.value(0));
+        assert_relative_eq!(expected, d.value(0));
+    }
+
+    #[test]
+    fn test_l2_avx_tail() {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if !is_x86_feature_detected!("avx2") {
+                return;
+            }
+            let from = (0..33).map(|v| v as f32 * 0.3 - 2.0).collect::<Vec<_>>();
+            let to = (0..33).map(|v| (v as f32 * 0.7).sin()).collect::<Vec<_>>();
+            for dim in 1..=33 {
+                assert_relative_eq!(
+                    l2_scalar(&from[..dim], &to[..dim]),
+                    x86_64::avx::l2_f32(&from[..dim], &to[..dim]),
+                    max_relative = 1e-6
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_l2_implementations_agree() {
+        let (values, q, expected) = l2_distance_fixture();
+        let (from, to) = (q.values(), values.values());
+        assert_relative_eq!(expected, l2_scalar(from, to));
+        // Dispatched through the cached kernel.
+        assert_relative_eq!(expected, from.l2(to));
+        assert_relative_eq!(expected, select_l2_f32()(from, to));
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("sse2") {
+                assert_relative_eq!(expected, x86_64::sse::l2_f32(from, to));
+                // Not aligned to the lanes.
+                assert_relative_eq!(
+                    l2_scalar(&from[..7], &to[..7]),
+                    x86_64::sse::l2_f32(&from[..7], &to[..7])
+                );
+            }
+            if is_x86_feature_detected!("avx2") {
+                assert_relative_eq!(expected, x86_64::avx::l2_f32(from, to));
+                assert_relative_eq!(
+                    l2_scalar(&from[..13], &to[..13]),
+                    x86_64::avx::l2_f32(&from[..13], &to[..13])
+                );
+            }
+        }
     }
 }